@@ -0,0 +1,387 @@
+//! Agent-specific skill format adapters applied after install.
+//!
+//! Skills are packaged in the Claude `SKILL.md` layout. Claude and OpenCode read
+//! that layout natively, while other agents need extra files to discover a skill:
+//! Codex looks for pointers in `AGENTS.md` and Kiro loads steering files.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::agent::AgentFramework;
+use crate::validate::parse_frontmatter;
+
+/// Start marker of the section `sb` manages inside `AGENTS.md`.
+pub const AGENTS_MD_START: &str = "<!-- skill-builder:start -->";
+
+/// End marker of the section `sb` manages inside `AGENTS.md`.
+pub const AGENTS_MD_END: &str = "<!-- skill-builder:end -->";
+
+/// Where adapters write files that live outside the skill directory.
+pub struct AdapterContext<'a> {
+    /// Project root used for project-level installs.
+    pub project_root: &'a Path,
+    /// Whether the skill was installed to global agent directories.
+    pub global: bool,
+}
+
+impl AdapterContext<'_> {
+    /// Base directory for agent files: the project root, or the agent's global
+    /// config directory under home.
+    fn agent_base(&self, agent: AgentFramework) -> PathBuf {
+        if !self.global {
+            return self.project_root.to_path_buf();
+        }
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        match agent {
+            AgentFramework::Claude => home.join(".claude"),
+            AgentFramework::OpenCode => home.join(".config/opencode"),
+            AgentFramework::Codex => home.join(".codex"),
+            AgentFramework::Kiro => home,
+        }
+    }
+}
+
+/// Metadata of an installed skill needed by the adapters.
+#[derive(Debug)]
+struct InstalledSkill {
+    name: String,
+    description: String,
+    /// Path to the skill's SKILL.md as it should be referenced from agent files.
+    skill_md: String,
+}
+
+impl InstalledSkill {
+    fn read(skill_path: &Path, ctx: &AdapterContext) -> Result<Self> {
+        let skill_md_path = skill_path.join("SKILL.md");
+        let content = fs::read_to_string(&skill_md_path)
+            .with_context(|| format!("Failed to read {}", skill_md_path.display()))?;
+        let frontmatter = parse_frontmatter(&content).unwrap_or_default();
+
+        let dir_name = skill_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        Ok(Self {
+            name: frontmatter
+                .name
+                .filter(|n| !n.is_empty())
+                .unwrap_or(dir_name),
+            description: frontmatter.description.unwrap_or_default(),
+            skill_md: reference_path(&skill_md_path, ctx),
+        })
+    }
+}
+
+/// Path to reference from agent files: relative to the project root when the
+/// skill lives inside it, absolute otherwise.
+fn reference_path(path: &Path, ctx: &AdapterContext) -> String {
+    let path = if path.is_relative() || ctx.global {
+        path.to_path_buf()
+    } else {
+        path.strip_prefix(ctx.project_root)
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Transform an installed skill for the given agent.
+///
+/// Returns the paths of any files created or updated outside the skill directory.
+pub fn adapt_installed_skill(
+    agent: AgentFramework,
+    skill_path: &Path,
+    ctx: &AdapterContext,
+) -> Result<Vec<PathBuf>> {
+    match agent {
+        // Both read the SKILL.md layout natively.
+        AgentFramework::Claude | AgentFramework::OpenCode => Ok(Vec::new()),
+        AgentFramework::Codex => {
+            let skill = InstalledSkill::read(skill_path, ctx)?;
+            let path = ctx.agent_base(agent).join("AGENTS.md");
+            write_agents_md_entry(&path, &skill)?;
+            Ok(vec![path])
+        }
+        AgentFramework::Kiro => {
+            let skill = InstalledSkill::read(skill_path, ctx)?;
+            let path = ctx
+                .agent_base(agent)
+                .join(".kiro/steering")
+                .join(format!("{}.md", file_stem(&skill.name)));
+            write_kiro_steering(&path, &skill)?;
+            Ok(vec![path])
+        }
+    }
+}
+
+/// Add or replace the skill's pointer line in the managed section of `AGENTS.md`.
+fn write_agents_md_entry(path: &Path, skill: &InstalledSkill) -> Result<()> {
+    let existing = if path.exists() {
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?
+    } else {
+        String::new()
+    };
+
+    let entry_prefix = format!("- `{}`:", skill.name);
+    let entry = format!(
+        "{} {} See `{}`.",
+        entry_prefix,
+        skill.description.trim_end_matches('.'),
+        skill.skill_md
+    );
+
+    let updated = match (existing.find(AGENTS_MD_START), existing.find(AGENTS_MD_END)) {
+        (Some(start), Some(end)) if start < end => {
+            let body_start = start + AGENTS_MD_START.len();
+            let mut entries: Vec<String> = existing[body_start..end]
+                .lines()
+                .filter(|l| l.starts_with("- `"))
+                .map(|l| l.to_string())
+                .collect();
+            match entries.iter().position(|l| l.starts_with(&entry_prefix)) {
+                Some(pos) => entries[pos] = entry,
+                None => entries.push(entry),
+            }
+            format!(
+                "{}{}{}",
+                &existing[..start],
+                managed_section(&entries),
+                &existing[end + AGENTS_MD_END.len()..]
+            )
+        }
+        _ => {
+            let mut content = existing;
+            if !content.is_empty() && !content.ends_with("\n\n") {
+                content.push_str(if content.ends_with('\n') {
+                    "\n"
+                } else {
+                    "\n\n"
+                });
+            }
+            content.push_str(&managed_section(&[entry]));
+            content.push('\n');
+            content
+        }
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, updated).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn managed_section(entries: &[String]) -> String {
+    format!(
+        "{}\n## Skills\n\nRead the linked SKILL.md when a task matches a skill below.\n\n{}\n{}",
+        AGENTS_MD_START,
+        entries.join("\n"),
+        AGENTS_MD_END
+    )
+}
+
+/// Write a Kiro steering file that points at the installed skill.
+///
+/// Kiro steering frontmatter uses `inclusion` rather than `name`, so the skill is
+/// included manually (via `#name` in chat) and its SKILL.md is pulled in by reference.
+fn write_kiro_steering(path: &Path, skill: &InstalledSkill) -> Result<()> {
+    let content = format!(
+        "---\ninclusion: manual\ndescription: {}\n---\n\n# {}\n\n{}\n\n#[[file:{}]]\n",
+        yaml_string(&skill.description),
+        skill.name,
+        skill.description,
+        skill.skill_md
+    );
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// `value` as a double-quoted YAML scalar, so `:`, `#` or a leading `-` or
+/// `[` can't change how the frontmatter parses.
+fn yaml_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// A file name for `name`: characters other than ASCII letters, digits, `.`,
+/// `_` and `-` become `-`, and leading dots are dropped so the name can't
+/// climb out of the directory or hide the file.
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let stem = stem.trim_start_matches('.');
+    if stem.is_empty() {
+        "skill".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Copy the valid skill fixture into `<root>/<skills_dir>/test-skill`.
+    fn install_fixture(root: &Path, skills_dir: &str) -> PathBuf {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/fixtures/valid_skill");
+        let dest = root.join(skills_dir).join("test-skill");
+        fs::create_dir_all(dest.join("references")).unwrap();
+        fs::copy(fixture.join("SKILL.md"), dest.join("SKILL.md")).unwrap();
+        fs::copy(
+            fixture.join("references/example.md"),
+            dest.join("references/example.md"),
+        )
+        .unwrap();
+        dest
+    }
+
+    fn project_ctx(root: &Path) -> AdapterContext<'_> {
+        AdapterContext {
+            project_root: root,
+            global: false,
+        }
+    }
+
+    #[test]
+    fn test_claude_and_opencode_are_native() {
+        let tmp = TempDir::new().unwrap();
+        let ctx = project_ctx(tmp.path());
+
+        for agent in [AgentFramework::Claude, AgentFramework::OpenCode] {
+            let skill = install_fixture(tmp.path(), agent.project_skills_dir());
+            let generated = adapt_installed_skill(agent, &skill, &ctx).unwrap();
+            assert!(generated.is_empty());
+        }
+        assert!(!tmp.path().join("AGENTS.md").exists());
+    }
+
+    #[test]
+    fn test_codex_creates_agents_md_entry() {
+        let tmp = TempDir::new().unwrap();
+        let skill = install_fixture(tmp.path(), AgentFramework::Codex.project_skills_dir());
+
+        let generated =
+            adapt_installed_skill(AgentFramework::Codex, &skill, &project_ctx(tmp.path())).unwrap();
+        assert_eq!(generated, vec![tmp.path().join("AGENTS.md")]);
+
+        let content = fs::read_to_string(tmp.path().join("AGENTS.md")).unwrap();
+        assert!(content.contains(AGENTS_MD_START));
+        assert!(content.contains(AGENTS_MD_END));
+        assert!(content.contains("- `test-skill`: A test skill for unit tests"));
+        assert!(content.contains("See `.agents/skills/test-skill/SKILL.md`."));
+    }
+
+    #[test]
+    fn test_codex_preserves_existing_agents_md() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("AGENTS.md"), "# Agents\n\nUse tabs.\n").unwrap();
+        let skill = install_fixture(tmp.path(), AgentFramework::Codex.project_skills_dir());
+        let ctx = project_ctx(tmp.path());
+
+        adapt_installed_skill(AgentFramework::Codex, &skill, &ctx).unwrap();
+        // Re-installing must not duplicate the entry
+        adapt_installed_skill(AgentFramework::Codex, &skill, &ctx).unwrap();
+
+        let content = fs::read_to_string(tmp.path().join("AGENTS.md")).unwrap();
+        assert!(content.starts_with("# Agents\n\nUse tabs.\n\n"));
+        assert_eq!(content.matches("- `test-skill`:").count(), 1);
+        assert_eq!(content.matches(AGENTS_MD_START).count(), 1);
+    }
+
+    #[test]
+    fn test_codex_appends_second_skill() {
+        let tmp = TempDir::new().unwrap();
+        let ctx = project_ctx(tmp.path());
+        let skill = install_fixture(tmp.path(), AgentFramework::Codex.project_skills_dir());
+        adapt_installed_skill(AgentFramework::Codex, &skill, &ctx).unwrap();
+
+        let other = tmp.path().join(".agents/skills/other-skill");
+        fs::create_dir_all(&other).unwrap();
+        fs::write(
+            other.join("SKILL.md"),
+            "---\nname: other-skill\ndescription: Another skill\n---\n",
+        )
+        .unwrap();
+        adapt_installed_skill(AgentFramework::Codex, &other, &ctx).unwrap();
+
+        let content = fs::read_to_string(tmp.path().join("AGENTS.md")).unwrap();
+        assert!(content.contains("- `test-skill`:"));
+        assert!(content.contains("- `other-skill`: Another skill"));
+    }
+
+    #[test]
+    fn test_kiro_writes_steering_file() {
+        let tmp = TempDir::new().unwrap();
+        let skill = install_fixture(tmp.path(), AgentFramework::Kiro.project_skills_dir());
+
+        let generated =
+            adapt_installed_skill(AgentFramework::Kiro, &skill, &project_ctx(tmp.path())).unwrap();
+        let steering = tmp.path().join(".kiro/steering/test-skill.md");
+        assert_eq!(generated, vec![steering.clone()]);
+
+        let content = fs::read_to_string(steering).unwrap();
+        assert!(content.starts_with("---\ninclusion: manual\n"));
+        assert!(!content.contains("\nname:"));
+        assert!(content.contains("#[[file:.kiro/skills/test-skill/SKILL.md]]"));
+    }
+
+    #[test]
+    fn test_kiro_steering_quotes_description_and_sanitizes_name() {
+        let tmp = TempDir::new().unwrap();
+        let skill = tmp.path().join(".kiro/skills/odd");
+        fs::create_dir_all(&skill).unwrap();
+        fs::write(
+            skill.join("SKILL.md"),
+            "---\nname: ../odd/skill\ndescription: - [Use]: when \"asked\" # always\n---\n",
+        )
+        .unwrap();
+
+        let generated =
+            adapt_installed_skill(AgentFramework::Kiro, &skill, &project_ctx(tmp.path())).unwrap();
+        assert_eq!(
+            generated,
+            vec![tmp.path().join(".kiro/steering/-odd-skill.md")]
+        );
+        let content = fs::read_to_string(&generated[0]).unwrap();
+        assert!(content.contains("\ndescription: \"- [Use]: when \\\"asked\\\" # always\"\n"));
+
+        assert_eq!(yaml_string("a\\b\nc"), "\"a\\\\b\\nc\"");
+        assert_eq!(file_stem("..."), "skill");
+    }
+
+    #[test]
+    fn test_missing_skill_md_fails() {
+        let tmp = TempDir::new().unwrap();
+        let result = adapt_installed_skill(
+            AgentFramework::Kiro,
+            &tmp.path().join("missing"),
+            &project_ctx(tmp.path()),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -106,6 +106,54 @@ pub fn parse_agent_flag(value: Option<&str>) -> anyhow::Result<AgentTarget> {
     }
 }
 
+/// Non-agent files and directories that mark a project root: the project config,
+/// the install lockfile, and the VCS root.
+pub const PROJECT_ROOT_MARKERS: &[&str] = &["skills.json", "skills.lock", ".git"];
+
+/// Whether a directory contains any agent or project root marker.
+fn has_project_marker(dir: &Path) -> bool {
+    PROJECT_ROOT_MARKERS.iter().any(|m| dir.join(m).exists())
+        || ALL_FRAMEWORKS.iter().any(|agent| {
+            agent
+                .project_dir_markers()
+                .iter()
+                .any(|d| dir.join(d).is_dir())
+                || agent
+                    .project_file_markers()
+                    .iter()
+                    .any(|f| dir.join(f).exists())
+        })
+}
+
+/// Find the project root by walking up from `start`.
+///
+/// Returns the nearest ancestor (including `start` itself) that contains an agent
+/// marker, `skills.json`, `skills.lock` or `.git`. The home directory is never
+/// treated as a project root, since global agent markers live there. Falls back
+/// to `start` when no marker is found.
+pub fn find_project_root(start: &Path) -> PathBuf {
+    let home = dirs::home_dir();
+    for dir in start.ancestors() {
+        if home.as_deref() == Some(dir) {
+            break;
+        }
+        if has_project_marker(dir) {
+            return dir.to_path_buf();
+        }
+    }
+    start.to_path_buf()
+}
+
+/// Join a project-relative path onto the project root, keeping it relative when
+/// the root is the current directory.
+fn project_path(project_root: &Path, relative: &str) -> PathBuf {
+    if project_root == Path::new(".") {
+        PathBuf::from(relative)
+    } else {
+        project_root.join(relative)
+    }
+}
+
 /// Detect which agent frameworks are configured in a project directory.
 pub fn detect_project_agents(project_root: &Path) -> Vec<AgentFramework> {
     let mut agents: Vec<AgentFramework> = ALL_FRAMEWORKS
@@ -151,8 +199,19 @@ pub fn detect_global_agents() -> Vec<AgentFramework> {
     agents
 }
 
+/// An install destination, tagged with the agent it belongs to when known.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstallTarget {
+    /// Agent the directory belongs to. `None` for an explicit `--install-dir`
+    /// without a specific `--agent`.
+    pub agent: Option<AgentFramework>,
+    pub dir: PathBuf,
+}
+
 /// Resolve installation directories based on target, explicit dir, and global flag.
 ///
+/// Project-level directories are resolved against `project_root`.
+///
 /// Priority:
 /// 1. If `explicit_dir` is Some, return just that path (overrides everything)
 /// 2. If target is Specific, return that agent's dir
@@ -164,29 +223,57 @@ pub fn resolve_install_dirs(
     global: bool,
     project_root: &Path,
 ) -> Vec<PathBuf> {
+    resolve_install_targets(target, explicit_dir, global, project_root)
+        .into_iter()
+        .map(|t| t.dir)
+        .collect()
+}
+
+/// Resolve installation targets, keeping track of which agent each directory
+/// belongs to so agent-specific adapters can run after install.
+///
+/// Follows the same priority as [`resolve_install_dirs`]. An explicit directory
+/// is only tagged with an agent when `--agent` names a specific one.
+pub fn resolve_install_targets(
+    target: &AgentTarget,
+    explicit_dir: Option<&Path>,
+    global: bool,
+    project_root: &Path,
+) -> Vec<InstallTarget> {
     // Explicit dir overrides everything
     if let Some(dir) = explicit_dir {
-        return vec![dir.to_path_buf()];
+        let agent = match target {
+            AgentTarget::Specific(agent) => Some(*agent),
+            _ => None,
+        };
+        return vec![InstallTarget {
+            agent,
+            dir: dir.to_path_buf(),
+        }];
     }
 
-    let agent_to_dir = |agent: &AgentFramework| -> PathBuf {
-        if global {
+    let agent_to_target = |agent: &AgentFramework| -> InstallTarget {
+        let dir = if global {
             agent.global_skills_dir()
         } else {
-            PathBuf::from(agent.project_skills_dir())
+            project_path(project_root, agent.project_skills_dir())
+        };
+        InstallTarget {
+            agent: Some(*agent),
+            dir,
         }
     };
 
     match target {
-        AgentTarget::Specific(agent) => vec![agent_to_dir(agent)],
-        AgentTarget::All => ALL_FRAMEWORKS.iter().map(agent_to_dir).collect(),
+        AgentTarget::Specific(agent) => vec![agent_to_target(agent)],
+        AgentTarget::All => ALL_FRAMEWORKS.iter().map(agent_to_target).collect(),
         AgentTarget::Auto => {
             let agents = if global {
                 detect_global_agents()
             } else {
                 detect_project_agents(project_root)
             };
-            agents.iter().map(agent_to_dir).collect()
+            agents.iter().map(agent_to_target).collect()
         }
     }
 }
@@ -383,6 +470,90 @@ mod tests {
         assert_eq!(dirs[3], PathBuf::from(".kiro/skills"));
     }
 
+    #[test]
+    fn test_resolve_targets_tags_agents() {
+        let targets = resolve_install_targets(&AgentTarget::All, None, false, Path::new("."));
+        let agents: Vec<_> = targets.iter().map(|t| t.agent).collect();
+        assert_eq!(
+            agents,
+            ALL_FRAMEWORKS.iter().copied().map(Some).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_resolve_targets_explicit_dir_keeps_specific_agent() {
+        let explicit = PathBuf::from("/custom/path");
+        let targets = resolve_install_targets(
+            &AgentTarget::Specific(AgentFramework::Codex),
+            Some(&explicit),
+            false,
+            Path::new("."),
+        );
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].agent, Some(AgentFramework::Codex));
+
+        let targets =
+            resolve_install_targets(&AgentTarget::Auto, Some(&explicit), false, Path::new("."));
+        assert_eq!(targets[0].agent, None);
+    }
+
+    #[test]
+    fn test_resolve_project_dirs_under_root() {
+        let tmp = TempDir::new().unwrap();
+        let dirs = resolve_install_dirs(
+            &AgentTarget::Specific(AgentFramework::Claude),
+            None,
+            false,
+            tmp.path(),
+        );
+        assert_eq!(dirs, vec![tmp.path().join(".claude/skills")]);
+    }
+
+    #[test]
+    fn test_find_project_root_walks_up_to_agent_marker() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".claude")).unwrap();
+        let nested = tmp.path().join("src/components");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(find_project_root(&nested), tmp.path());
+    }
+
+    #[test]
+    fn test_find_project_root_project_markers() {
+        for marker in PROJECT_ROOT_MARKERS {
+            let tmp = TempDir::new().unwrap();
+            std::fs::write(tmp.path().join(marker), "").unwrap();
+            let nested = tmp.path().join("a/b");
+            std::fs::create_dir_all(&nested).unwrap();
+
+            assert_eq!(find_project_root(&nested), tmp.path(), "marker {}", marker);
+        }
+    }
+
+    #[test]
+    fn test_find_project_root_nearest_wins() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".git")).unwrap();
+        let sub = tmp.path().join("packages/app");
+        std::fs::create_dir_all(sub.join(".kiro")).unwrap();
+        let nested = sub.join("src");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(find_project_root(&nested), sub);
+    }
+
+    #[test]
+    fn test_find_project_root_falls_back_to_start() {
+        let tmp = TempDir::new().unwrap();
+        let nested = tmp.path().join("no/markers");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        // Temp dirs normally have no markers above them; if one does, the
+        // result is still an ancestor of the start directory.
+        assert!(nested.starts_with(find_project_root(&nested)));
+    }
+
     #[test]
     fn test_agent_project_skills_dirs() {
         assert_eq!(
@@ -0,0 +1,491 @@
+//! Local cache of skills downloaded from remote repositories, with LRU eviction.
+//!
+//! Cached packages live under `cache/` in the local repository, apart from
+//! skills published there with `sb local publish`, and are tracked in
+//! `cache/cache_index.json` together with when each was last used.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::config::LocalRepositoryConfig;
+use crate::local_storage::LocalStorageClient;
+use crate::storage::{update_json_object, StorageOperations};
+
+/// Storage key of the cache index, relative to the local repository.
+pub const CACHE_INDEX_KEY: &str = "cache/cache_index.json";
+
+/// A cached skill version.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CacheEntry {
+    pub name: String,
+    pub version: String,
+    /// Size of the package in bytes.
+    pub size: u64,
+    pub cached_at: DateTime<Utc>,
+    /// When the entry was last stored or read.
+    pub last_accessed: DateTime<Utc>,
+}
+
+impl CacheEntry {
+    /// Storage key of the cached package.
+    pub fn key(&self) -> String {
+        cache_key(&self.name, &self.version)
+    }
+}
+
+/// The cache index: every cached skill version.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CacheIndex {
+    pub entries: Vec<CacheEntry>,
+}
+
+impl CacheIndex {
+    fn find_mut(&mut self, name: &str, version: &str) -> Option<&mut CacheEntry> {
+        self.entries
+            .iter_mut()
+            .find(|e| e.name == name && e.version == version)
+    }
+
+    /// Total size of the cached packages in bytes.
+    pub fn total_size(&self) -> u64 {
+        self.entries.iter().map(|e| e.size).sum()
+    }
+}
+
+/// Storage key of a cached package.
+pub fn cache_key(name: &str, version: &str) -> String {
+    format!("cache/skills/{}/{}/{}.skill", name, version, name)
+}
+
+/// Limits on what the cache keeps. `None` means no limit.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CachePolicy {
+    /// Maximum total size in bytes.
+    pub max_size: Option<u64>,
+    /// Entries not used for this long are evicted.
+    pub max_age: Option<Duration>,
+    /// Most recently used versions to keep per skill.
+    pub keep_versions: Option<usize>,
+}
+
+impl CachePolicy {
+    /// The limits set in a `local` config section.
+    pub fn from_config(local: &LocalRepositoryConfig) -> Self {
+        Self {
+            max_size: local.max_size_mb.map(|mb| mb.saturating_mul(1024 * 1024)),
+            max_age: local.max_age_days.map(|days| {
+                i64::try_from(days)
+                    .ok()
+                    .and_then(Duration::try_days)
+                    .unwrap_or(Duration::MAX)
+            }),
+            keep_versions: local.keep_versions,
+        }
+    }
+
+    /// Whether any limit is set.
+    pub fn is_limited(&self) -> bool {
+        self.max_size.is_some() || self.max_age.is_some() || self.keep_versions.is_some()
+    }
+
+    /// Entries to evict from `index` at time `now`, least recently used first
+    /// within each rule: too old, then beyond `keep_versions`, then over
+    /// `max_size`.
+    pub fn evictions(&self, index: &CacheIndex, now: DateTime<Utc>) -> Vec<CacheEntry> {
+        let mut kept = index.entries.clone();
+        kept.sort_by_key(|e| e.last_accessed);
+        let mut evicted = Vec::new();
+
+        if let Some(max_age) = self.max_age {
+            let (old, recent): (Vec<_>, Vec<_>) = kept
+                .into_iter()
+                .partition(|e| now.signed_duration_since(e.last_accessed) > max_age);
+            evicted.extend(old);
+            kept = recent;
+        }
+
+        if let Some(keep) = self.keep_versions {
+            let mut counts: HashMap<String, usize> = HashMap::new();
+            for entry in &kept {
+                *counts.entry(entry.name.clone()).or_default() += 1;
+            }
+            let (over, within): (Vec<_>, Vec<_>) = kept.into_iter().partition(|e| {
+                let count = counts.get_mut(&e.name).expect("counted above");
+                let evict = *count > keep;
+                if evict {
+                    *count -= 1;
+                }
+                evict
+            });
+            evicted.extend(over);
+            kept = within;
+        }
+
+        if let Some(max_size) = self.max_size {
+            let mut total: u64 = kept.iter().map(|e| e.size).sum();
+            for entry in kept {
+                if total <= max_size {
+                    break;
+                }
+                total -= entry.size;
+                evicted.push(entry);
+            }
+        }
+
+        evicted
+    }
+}
+
+/// Result of pruning the cache.
+#[derive(Debug, Default)]
+pub struct PruneReport {
+    pub evicted: Vec<CacheEntry>,
+    /// Entries left in the cache.
+    pub kept: usize,
+    /// Size of the entries left, in bytes.
+    pub kept_size: u64,
+}
+
+impl PruneReport {
+    /// Bytes freed by the evictions.
+    pub fn freed(&self) -> u64 {
+        self.evicted.iter().map(|e| e.size).sum()
+    }
+}
+
+/// The download cache in a local repository.
+pub struct SkillCache {
+    storage: LocalStorageClient,
+    policy: CachePolicy,
+}
+
+impl From<LocalStorageClient> for SkillCache {
+    /// A cache without limits.
+    fn from(storage: LocalStorageClient) -> Self {
+        Self::new(storage, CachePolicy::default())
+    }
+}
+
+impl SkillCache {
+    pub fn new(storage: LocalStorageClient, policy: CachePolicy) -> Self {
+        Self { storage, policy }
+    }
+
+    /// The cache index, or an empty one if nothing has been cached.
+    pub fn index(&self) -> Result<CacheIndex> {
+        match self.storage.head_object(CACHE_INDEX_KEY)? {
+            None => Ok(CacheIndex::default()),
+            Some(_) => serde_json::from_slice(&self.storage.get_object(CACHE_INDEX_KEY)?)
+                .context("Failed to parse cache index"),
+        }
+    }
+
+    /// Read, change and write back the cache index, retrying if another
+    /// process wrote it in the meantime.
+    fn update<F>(&self, mut mutate: F) -> Result<CacheIndex>
+    where
+        F: FnMut(&mut CacheIndex),
+    {
+        update_json_object(&self.storage, CACHE_INDEX_KEY, "cache index", |index| {
+            mutate(index);
+            Ok(())
+        })
+    }
+
+    /// Read a cached package, recording the access. `None` on a miss.
+    pub fn get(&self, name: &str, version: &str) -> Result<Option<Vec<u8>>> {
+        let key = cache_key(name, version);
+        if !self.storage.object_exists(&key)? {
+            return Ok(None);
+        }
+        let data = self.storage.get_object(&key)?;
+        let now = Utc::now();
+        let size = data.len() as u64;
+        self.update(|index| match index.find_mut(name, version) {
+            Some(entry) => entry.last_accessed = now,
+            None => index.entries.push(CacheEntry {
+                name: name.to_string(),
+                version: version.to_string(),
+                size,
+                cached_at: now,
+                last_accessed: now,
+            }),
+        })?;
+        Ok(Some(data))
+    }
+
+    /// Cache a package, then evict whatever the policy no longer allows.
+    pub fn put(&self, name: &str, version: &str, data: &[u8]) -> Result<()> {
+        self.storage.put_object(&cache_key(name, version), data)?;
+        let now = Utc::now();
+        self.update(|index| {
+            let entry = CacheEntry {
+                name: name.to_string(),
+                version: version.to_string(),
+                size: data.len() as u64,
+                cached_at: now,
+                last_accessed: now,
+            };
+            match index.find_mut(name, version) {
+                Some(existing) => *existing = entry,
+                None => index.entries.push(entry),
+            }
+        })?;
+        if self.policy.is_limited() {
+            self.prune(false)?;
+        }
+        Ok(())
+    }
+
+    /// Remove one cached version, or every version of a skill.
+    pub fn remove(&self, name: &str, version: Option<&str>) -> Result<()> {
+        let removed: Vec<CacheEntry> = self
+            .index()?
+            .entries
+            .into_iter()
+            .filter(|e| e.name == name && version.is_none_or(|v| e.version == v))
+            .collect();
+        self.delete_entries(&removed)
+    }
+
+    /// Remove every cached package.
+    pub fn clear(&self) -> Result<usize> {
+        let entries = self.index()?.entries;
+        self.delete_entries(&entries)?;
+        // Packages the index lost track of
+        for key in self.storage.list_objects("cache/skills/")? {
+            self.storage.delete_object(&key)?;
+        }
+        Ok(entries.len())
+    }
+
+    fn delete_entries(&self, entries: &[CacheEntry]) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let keys: Vec<String> = entries.iter().map(|e| e.key()).collect();
+        for key in &keys {
+            self.storage.delete_object(key)?;
+        }
+        self.update(|index| index.entries.retain(|e| !keys.contains(&e.key())))?;
+        Ok(())
+    }
+
+    /// Evict entries the policy doesn't allow, or with `dry_run` just report
+    /// them.
+    ///
+    /// Index entries whose package is gone are dropped, and packages missing
+    /// from the index are added to it, dated by their modification time.
+    pub fn prune(&self, dry_run: bool) -> Result<PruneReport> {
+        let stored = self.storage.list_objects("cache/skills/")?;
+        let mut index = self.index()?;
+        self.reconcile(&mut index, &stored);
+        let evicted = self.policy.evictions(&index, Utc::now());
+        let evicted_keys: Vec<String> = evicted.iter().map(|e| e.key()).collect();
+        index.entries.retain(|e| !evicted_keys.contains(&e.key()));
+
+        if !dry_run {
+            for key in &evicted_keys {
+                self.storage.delete_object(key)?;
+            }
+            self.update(|index| {
+                self.reconcile(index, &stored);
+                index.entries.retain(|e| !evicted_keys.contains(&e.key()));
+            })?;
+        }
+        Ok(PruneReport {
+            kept: index.entries.len(),
+            kept_size: index.total_size(),
+            evicted,
+        })
+    }
+
+    /// Bring `index` in line with the `stored` packages.
+    fn reconcile(&self, index: &mut CacheIndex, stored: &[String]) {
+        index.entries.retain(|e| stored.contains(&e.key()));
+        let known: Vec<String> = index.entries.iter().map(|e| e.key()).collect();
+        for key in stored.iter().filter(|key| !known.contains(key)) {
+            let Some((name, version)) = parse_cache_key(key) else {
+                continue;
+            };
+            let Ok(metadata) = std::fs::metadata(self.storage.root().join(key)) else {
+                continue;
+            };
+            let modified: DateTime<Utc> = metadata
+                .modified()
+                .map(DateTime::from)
+                .unwrap_or_else(|_| Utc::now());
+            index.entries.push(CacheEntry {
+                name: name.to_string(),
+                version: version.to_string(),
+                size: metadata.len(),
+                cached_at: modified,
+                last_accessed: modified,
+            });
+        }
+    }
+}
+
+/// Parse a cache key `cache/skills/<name>/<version>/<name>.skill`.
+fn parse_cache_key(key: &str) -> Option<(&str, &str)> {
+    match key.split('/').collect::<Vec<_>>().as_slice() {
+        ["cache", "skills", name, version, file] if *file == format!("{}.skill", name) => {
+            Some((name, version))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(name: &str, version: &str, size: u64, days_ago: i64) -> CacheEntry {
+        let at = Utc::now() - Duration::days(days_ago);
+        CacheEntry {
+            name: name.to_string(),
+            version: version.to_string(),
+            size,
+            cached_at: at,
+            last_accessed: at,
+        }
+    }
+
+    fn evicted(policy: &CachePolicy, entries: Vec<CacheEntry>) -> Vec<String> {
+        let index = CacheIndex { entries };
+        policy
+            .evictions(&index, Utc::now())
+            .iter()
+            .map(|e| format!("{}@{}", e.name, e.version))
+            .collect()
+    }
+
+    fn sample() -> Vec<CacheEntry> {
+        vec![
+            entry("a", "1.0.0", 100, 40),
+            entry("a", "1.1.0", 100, 5),
+            entry("a", "1.2.0", 100, 1),
+            entry("b", "2.0.0", 300, 10),
+        ]
+    }
+
+    #[test]
+    fn test_unlimited_policy_evicts_nothing() {
+        assert!(evicted(&CachePolicy::default(), sample()).is_empty());
+    }
+
+    #[test]
+    fn test_max_age_evicts_unused_entries() {
+        let policy = CachePolicy {
+            max_age: Some(Duration::days(7)),
+            ..Default::default()
+        };
+        assert_eq!(evicted(&policy, sample()), vec!["a@1.0.0", "b@2.0.0"]);
+    }
+
+    #[test]
+    fn test_keep_versions_keeps_most_recently_used() {
+        let policy = CachePolicy {
+            keep_versions: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(evicted(&policy, sample()), vec!["a@1.0.0", "a@1.1.0"]);
+    }
+
+    #[test]
+    fn test_max_size_evicts_least_recently_used_first() {
+        let policy = CachePolicy {
+            max_size: Some(300),
+            ..Default::default()
+        };
+        assert_eq!(evicted(&policy, sample()), vec!["a@1.0.0", "b@2.0.0"]);
+    }
+
+    #[test]
+    fn test_policy_from_config() {
+        let policy = CachePolicy::from_config(&LocalRepositoryConfig {
+            max_size_mb: Some(2),
+            max_age_days: Some(u64::MAX),
+            ..Default::default()
+        });
+        assert_eq!(policy.max_size, Some(2 * 1024 * 1024));
+        assert_eq!(policy.max_age, Some(Duration::MAX));
+        assert!(policy.is_limited());
+        assert!(!CachePolicy::from_config(&LocalRepositoryConfig::default()).is_limited());
+    }
+
+    #[test]
+    fn test_hits_refresh_access_time() {
+        let tmp = TempDir::new().unwrap();
+        let cache = SkillCache::from(LocalStorageClient::new(tmp.path()).unwrap());
+        assert_eq!(cache.get("a", "1.0.0").unwrap(), None);
+
+        cache.put("a", "1.0.0", b"one").unwrap();
+        let stored = cache.index().unwrap().entries[0].clone();
+        assert_eq!(stored.size, 3);
+
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert_eq!(cache.get("a", "1.0.0").unwrap().unwrap(), b"one");
+        let hit = cache.index().unwrap().entries[0].clone();
+        assert!(hit.last_accessed > stored.last_accessed);
+        assert_eq!(hit.cached_at, stored.cached_at);
+    }
+
+    #[test]
+    fn test_put_enforces_policy() {
+        let tmp = TempDir::new().unwrap();
+        let cache = SkillCache::new(
+            LocalStorageClient::new(tmp.path()).unwrap(),
+            CachePolicy {
+                keep_versions: Some(2),
+                ..Default::default()
+            },
+        );
+        for version in ["1.0.0", "1.1.0", "1.2.0"] {
+            cache.put("a", version, b"data").unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        let mut versions: Vec<String> = cache
+            .index()
+            .unwrap()
+            .entries
+            .into_iter()
+            .map(|e| e.version)
+            .collect();
+        versions.sort();
+        assert_eq!(versions, vec!["1.1.0", "1.2.0"]);
+        assert!(!tmp.path().join(cache_key("a", "1.0.0")).exists());
+    }
+
+    #[test]
+    fn test_prune_tracks_untracked_packages_and_forgets_missing_ones() {
+        let tmp = TempDir::new().unwrap();
+        let storage = LocalStorageClient::new(tmp.path()).unwrap();
+        storage
+            .put_object(&cache_key("a", "1.0.0"), b"abc")
+            .unwrap();
+        let cache = SkillCache::from(storage);
+        cache.put("b", "1.0.0", b"b").unwrap();
+        std::fs::remove_file(tmp.path().join(cache_key("b", "1.0.0"))).unwrap();
+
+        let report = cache.prune(false).unwrap();
+        assert!(report.evicted.is_empty());
+        assert_eq!((report.kept, report.kept_size), (1, 3));
+        let entries = cache.index().unwrap().entries;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "a");
+    }
+
+    #[test]
+    fn test_parse_cache_key() {
+        assert_eq!(
+            parse_cache_key("cache/skills/a/1.0.0/a.skill"),
+            Some(("a", "1.0.0"))
+        );
+        assert_eq!(parse_cache_key("skills/a/1.0.0/a.skill"), None);
+        assert_eq!(parse_cache_key("cache/cache_index.json"), None);
+    }
+}
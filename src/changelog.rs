@@ -0,0 +1,447 @@
+//! Reading and generating [Keep a Changelog](https://keepachangelog.com)
+//! sections.
+
+use regex::Regex;
+use std::collections::BTreeMap;
+
+use crate::index::compare_semver;
+use crate::validate::parse_frontmatter;
+
+/// One version's section of a changelog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub version: String,
+    pub date: Option<String>,
+    /// The section as written, from its `##` heading up to the next one.
+    pub text: String,
+}
+
+/// Split a changelog into its version sections, in file order.
+///
+/// Headings look like `## [1.2.0] - 2025-01-20`, `## 1.2.0` or `## v1.2.0`.
+/// An `Unreleased` section and anything before the first version heading are
+/// skipped.
+pub fn parse_sections(text: &str) -> Vec<Section> {
+    let heading = Regex::new(r"^##\s+\[?v?([^\]\s]+)\]?(?:\s+-\s+(\S.*))?\s*$").unwrap();
+    let mut sections: Vec<Section> = Vec::new();
+    let mut current: Option<Section> = None;
+
+    for line in text.lines() {
+        if line.starts_with("## ") {
+            sections.extend(current.take());
+            current = heading
+                .captures(line)
+                .filter(|c| !c[1].eq_ignore_ascii_case("unreleased"))
+                .map(|c| Section {
+                    version: c[1].to_string(),
+                    date: c.get(2).map(|d| d.as_str().trim().to_string()),
+                    text: String::new(),
+                });
+        }
+        if let Some(section) = current.as_mut() {
+            section.text.push_str(line);
+            section.text.push('\n');
+        }
+    }
+    sections.extend(current);
+    for section in &mut sections {
+        section.text = section.text.trim_end().to_string();
+    }
+    sections
+}
+
+/// The section for `version`, if the changelog has one.
+pub fn find_section(text: &str, version: &str) -> Option<Section> {
+    let version = version.trim_start_matches('v');
+    parse_sections(text)
+        .into_iter()
+        .find(|s| s.version == version)
+}
+
+/// Versions in `versions` newer than `since` (if given) and no newer than
+/// `until`, newest first.
+pub fn versions_between<'a>(
+    versions: impl IntoIterator<Item = &'a str>,
+    since: Option<&str>,
+    until: &str,
+) -> Vec<&'a str> {
+    let mut selected: Vec<&str> = versions
+        .into_iter()
+        .filter(|v| compare_semver(v, until).is_le())
+        .filter(|v| since.is_none_or(|since| compare_semver(v, since).is_gt()))
+        .collect();
+    selected.sort_by(|a, b| compare_semver(b, a));
+    selected
+}
+
+/// Add `section` to a changelog above its newest version section, keeping any
+/// title and introduction first.
+pub fn prepend_section(changelog: &str, section: &str) -> String {
+    let section = section.trim_end();
+    let first = changelog
+        .match_indices("## ")
+        .map(|(i, _)| i)
+        .find(|&i| i == 0 || changelog[..i].ends_with('\n'));
+    match first {
+        Some(i) => format!("{}{}\n\n{}", &changelog[..i], section, &changelog[i..]),
+        None if changelog.trim().is_empty() => format!("{}\n", section),
+        None => format!("{}\n\n{}\n", changelog.trim_end(), section),
+    }
+}
+
+/// How a skill's files changed between two versions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SkillDiff {
+    /// Paths relative to the skill directory, sorted.
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<String>,
+    /// How many reference docs the old version had.
+    pub old_references: usize,
+    pub description_changed: bool,
+    /// Whether SKILL.md changed apart from its frontmatter.
+    pub instructions_changed: bool,
+}
+
+/// SKILL.md without its `version` line, which every release changes.
+fn without_version(data: &[u8]) -> Vec<u8> {
+    String::from_utf8_lossy(data)
+        .lines()
+        .filter(|line| !line.starts_with("version:"))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .into_bytes()
+}
+
+fn is_reference(path: &str) -> bool {
+    path.starts_with("references/")
+}
+
+/// SKILL.md split into its description and the text after the frontmatter.
+fn split_skill_md(data: &[u8]) -> (Option<String>, String) {
+    let text = String::from_utf8_lossy(data);
+    let description = parse_frontmatter(&text).ok().and_then(|f| f.description);
+    let body = text
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---").map(|(_, body)| body))
+        .unwrap_or(&text);
+    (description, body.trim().to_string())
+}
+
+impl SkillDiff {
+    /// Compare the files of an old and a new version, keyed by path.
+    pub fn between(old: &BTreeMap<String, Vec<u8>>, new: &BTreeMap<String, Vec<u8>>) -> Self {
+        let mut diff = Self {
+            old_references: old.keys().filter(|p| is_reference(p)).count(),
+            ..Default::default()
+        };
+        for (path, data) in new {
+            match old.get(path) {
+                None => diff.added.push(path.clone()),
+                Some(old_data) if path == "SKILL.md" => {
+                    if without_version(old_data) != without_version(data) {
+                        diff.modified.push(path.clone());
+                    }
+                }
+                Some(old_data) if old_data != data => diff.modified.push(path.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .keys()
+            .filter(|p| !new.contains_key(*p))
+            .cloned()
+            .collect();
+
+        if let (Some(old_md), Some(new_md)) = (old.get("SKILL.md"), new.get("SKILL.md")) {
+            let (old_description, old_body) = split_skill_md(old_md);
+            let (new_description, new_body) = split_skill_md(new_md);
+            diff.description_changed = old_description != new_description;
+            diff.instructions_changed = old_body != new_body;
+        }
+        diff
+    }
+
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    /// Render the changes as a changelog section headed `## [heading] - date`.
+    pub fn to_section(&self, heading: &str, date: &str) -> String {
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        let mut removed = Vec::new();
+
+        for path in &self.added {
+            match path.strip_prefix("references/") {
+                Some(doc) => added.push(format!("New reference: `{}`", doc)),
+                None => added.push(format!("Added `{}`", path)),
+            }
+        }
+        if self.description_changed {
+            changed.push("Updated the skill description in SKILL.md".to_string());
+        }
+        if self.instructions_changed {
+            changed.push("Updated SKILL.md instructions".to_string());
+        }
+        if self.modified.iter().any(|p| p == "SKILL.md")
+            && !self.description_changed
+            && !self.instructions_changed
+        {
+            changed.push("Updated SKILL.md frontmatter".to_string());
+        }
+        let modified_references: Vec<&String> =
+            self.modified.iter().filter(|p| is_reference(p)).collect();
+        if modified_references.len() > 1 && modified_references.len() == self.old_references {
+            changed.push(format!(
+                "Updated all {} reference docs",
+                modified_references.len()
+            ));
+        } else {
+            for path in &modified_references {
+                changed.push(format!(
+                    "Updated reference: `{}`",
+                    &path["references/".len()..]
+                ));
+            }
+        }
+        for path in &self.modified {
+            if !is_reference(path) && path != "SKILL.md" {
+                changed.push(format!("Updated `{}`", path));
+            }
+        }
+        for path in &self.removed {
+            match path.strip_prefix("references/") {
+                Some(doc) => removed.push(format!("Removed reference: `{}`", doc)),
+                None => removed.push(format!("Removed `{}`", path)),
+            }
+        }
+        if self.is_empty() {
+            changed.push("Republished without changes to the skill's files".to_string());
+        }
+
+        let mut section = format!("## [{}] - {}\n", heading, date);
+        for (title, items) in [("Added", added), ("Changed", changed), ("Removed", removed)] {
+            if items.is_empty() {
+                continue;
+            }
+            section.push_str(&format!("\n### {}\n\n", title));
+            for item in items {
+                section.push_str(&format!("- {}\n", item));
+            }
+        }
+        section
+    }
+}
+
+/// Section for a first release, which has nothing to compare against.
+pub fn initial_section(heading: &str, date: &str, files: &BTreeMap<String, Vec<u8>>) -> String {
+    let references = files.keys().filter(|p| is_reference(p)).count();
+    format!(
+        "## [{}] - {}\n\n### Added\n\n- Initial release with {} reference doc{}\n",
+        heading,
+        date,
+        references,
+        if references == 1 { "" } else { "s" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "# Changelog
+
+Intro text.
+
+## [Unreleased]
+
+- Not yet
+
+## [1.1.0] - 2025-02-01
+
+### Added
+
+- Kbd component
+
+## 1.0.1
+
+- Fixed links
+
+## [1.0.0] - 2025-01-20
+
+- First release
+";
+
+    #[test]
+    fn test_parse_sections() {
+        let sections = parse_sections(CHANGELOG);
+        let versions: Vec<&str> = sections.iter().map(|s| s.version.as_str()).collect();
+        assert_eq!(versions, vec!["1.1.0", "1.0.1", "1.0.0"]);
+        assert_eq!(sections[0].date.as_deref(), Some("2025-02-01"));
+        assert_eq!(
+            sections[0].text,
+            "## [1.1.0] - 2025-02-01\n\n### Added\n\n- Kbd component"
+        );
+        assert_eq!(sections[1].date, None);
+        assert_eq!(
+            sections[2].text,
+            "## [1.0.0] - 2025-01-20\n\n- First release"
+        );
+    }
+
+    #[test]
+    fn test_find_section() {
+        assert_eq!(
+            find_section(CHANGELOG, "v1.0.1").unwrap().text,
+            "## 1.0.1\n\n- Fixed links"
+        );
+        assert!(find_section(CHANGELOG, "2.0.0").is_none());
+        assert!(find_section("Just some notes", "1.0.0").is_none());
+    }
+
+    fn files(entries: &[(&str, &str)]) -> BTreeMap<String, Vec<u8>> {
+        entries
+            .iter()
+            .map(|(path, data)| (path.to_string(), data.as_bytes().to_vec()))
+            .collect()
+    }
+
+    const SKILL_MD: &str = "---\nname: ui\ndescription: Old\n---\n\n# UI\n";
+
+    #[test]
+    fn test_diff_lists_added_removed_and_modified_files() {
+        let old = files(&[
+            ("SKILL.md", SKILL_MD),
+            ("references/button.md", "a"),
+            ("references/form.md", "b"),
+            ("references/old.md", "c"),
+        ]);
+        let new = files(&[
+            ("SKILL.md", "---\nname: ui\ndescription: New\n---\n\n# UI\n"),
+            ("references/button.md", "a2"),
+            ("references/form.md", "b"),
+            ("references/kbd.md", "d"),
+            ("scripts/check.sh", "e"),
+        ]);
+        let diff = SkillDiff::between(&old, &new);
+        assert_eq!(diff.added, vec!["references/kbd.md", "scripts/check.sh"]);
+        assert_eq!(diff.removed, vec!["references/old.md"]);
+        assert_eq!(diff.modified, vec!["SKILL.md", "references/button.md"]);
+        assert!(diff.description_changed);
+        assert!(!diff.instructions_changed);
+
+        assert_eq!(
+            diff.to_section("1.1.0", "2025-02-01"),
+            "## [1.1.0] - 2025-02-01
+
+### Added
+
+- New reference: `kbd.md`
+- Added `scripts/check.sh`
+
+### Changed
+
+- Updated the skill description in SKILL.md
+- Updated reference: `button.md`
+
+### Removed
+
+- Removed reference: `old.md`
+"
+        );
+    }
+
+    #[test]
+    fn test_diff_summarizes_updates_to_every_reference() {
+        let old = files(&[
+            ("SKILL.md", SKILL_MD),
+            ("references/a.md", "a"),
+            ("references/b.md", "b"),
+        ]);
+        let new = files(&[
+            (
+                "SKILL.md",
+                "---\nname: ui\ndescription: Old\n---\n\n# UI v2\n",
+            ),
+            ("references/a.md", "a2"),
+            ("references/b.md", "b2"),
+        ]);
+        let section = SkillDiff::between(&old, &new).to_section("Unreleased", "2025-02-01");
+        assert!(section.contains("- Updated SKILL.md instructions\n"));
+        assert!(section.contains("- Updated all 2 reference docs\n"));
+        assert!(!section.contains("### Added"));
+
+        let tagged = files(&[
+            (
+                "SKILL.md",
+                "---\nname: ui\ndescription: Old\ntags: [ui]\n---\n\n# UI\n",
+            ),
+            ("references/a.md", "a"),
+            ("references/b.md", "b"),
+        ]);
+        assert!(SkillDiff::between(&old, &tagged)
+            .to_section("1.0.1", "2025-02-01")
+            .contains("- Updated SKILL.md frontmatter\n"));
+
+        // Releases only change the version line, which doesn't count
+        let released = files(&[
+            (
+                "SKILL.md",
+                "---\nname: ui\nversion: 1.0.1\ndescription: Old\n---\n\n# UI\n",
+            ),
+            ("references/a.md", "a"),
+            ("references/b.md", "b"),
+        ]);
+        let unchanged = SkillDiff::between(&old, &released);
+        assert!(unchanged.is_empty());
+        assert!(unchanged
+            .to_section("1.0.1", "2025-02-01")
+            .contains("- Republished without changes"));
+    }
+
+    #[test]
+    fn test_generated_section_parses_back() {
+        let new = files(&[("SKILL.md", SKILL_MD), ("references/a.md", "a")]);
+        let section = initial_section("1.0.0", "2025-01-20", &new);
+        let parsed = find_section(&section, "1.0.0").unwrap();
+        assert_eq!(parsed.date.as_deref(), Some("2025-01-20"));
+        assert!(parsed
+            .text
+            .ends_with("- Initial release with 1 reference doc"));
+    }
+
+    #[test]
+    fn test_prepend_section() {
+        let section = "## [1.2.0] - 2025-03-01\n\n- New\n";
+        let updated = prepend_section(CHANGELOG, section);
+        assert!(updated.starts_with("# Changelog\n\nIntro text.\n\n## [1.2.0]"));
+        assert!(updated.contains("- New\n\n## [Unreleased]"));
+        let versions: Vec<String> = parse_sections(&updated)
+            .into_iter()
+            .map(|s| s.version)
+            .collect();
+        assert_eq!(versions, vec!["1.2.0", "1.1.0", "1.0.1", "1.0.0"]);
+
+        assert_eq!(prepend_section("", section), section);
+        assert_eq!(
+            prepend_section("# Changelog\n", section),
+            format!("# Changelog\n\n{}", section)
+        );
+    }
+
+    #[test]
+    fn test_versions_between() {
+        let versions = ["1.0.0", "1.0.1", "1.1.0", "1.10.0", "2.0.0"];
+        assert_eq!(
+            versions_between(versions, Some("1.0.0"), "1.10.0"),
+            vec!["1.10.0", "1.1.0", "1.0.1"]
+        );
+        assert_eq!(
+            versions_between(versions, None, "1.0.1"),
+            vec!["1.0.1", "1.0.0"]
+        );
+        assert!(versions_between(versions, Some("2.0.0"), "2.0.0").is_empty());
+    }
+}
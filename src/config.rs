@@ -2,6 +2,8 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -46,7 +48,7 @@ impl SkillConfig {
 }
 
 /// Local repository configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct LocalRepositoryConfig {
     /// Path to the local repository directory. Defaults to $HOME/.skill-builder/local/.
     #[serde(default)]
@@ -55,12 +57,24 @@ pub struct LocalRepositoryConfig {
     /// Whether to use this as a cache for the remote repository.
     #[serde(default)]
     pub cache: bool,
+
+    /// Maximum total size of cached downloads, in MiB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size_mb: Option<u64>,
+
+    /// Evict cached downloads not used for this many days.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u64>,
+
+    /// Most recently used versions of each skill to keep cached.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_versions: Option<usize>,
 }
 
 /// Repository configuration for S3-compatible skill storage.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RepositoryConfig {
-    /// Display name for the repository.
+    /// Display name for the repository. Required for entries in `repositories`.
     #[serde(default)]
     pub name: Option<String>,
 
@@ -79,12 +93,85 @@ pub struct RepositoryConfig {
     /// Custom endpoint URL for S3-compatible providers.
     #[serde(default)]
     pub endpoint: Option<String>,
+
+    /// Repository URL, used instead of S3 when set: `https://…` for a static
+    /// HTTP repository or `git+<url>` for a git repository.
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Branch holding the skills in a git repository (defaults to "main").
+    #[serde(default)]
+    pub branch: Option<String>,
+
+    /// AWS credentials profile to use instead of the default chain.
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Position in the install cascade; lower values are tried first.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Disallow uploads and deletes through `sb`.
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+impl Default for RepositoryConfig {
+    fn default() -> Self {
+        Self {
+            name: None,
+            local: None,
+            bucket_name: None,
+            region: default_region(),
+            endpoint: None,
+            url: None,
+            branch: None,
+            profile: None,
+            priority: 0,
+            read_only: false,
+        }
+    }
 }
 
 impl RepositoryConfig {
-    /// Whether a remote S3 repository is configured.
+    /// Name shown in listings: the configured name, else the bucket name.
+    pub fn display_name(&self) -> &str {
+        self.name
+            .as_deref()
+            .or(self.bucket_name.as_deref())
+            .unwrap_or("default")
+    }
+
+    /// Whether a remote repository (S3 bucket or URL) is configured.
     pub fn has_remote(&self) -> bool {
-        self.bucket_name.is_some()
+        self.bucket_name.is_some() || self.url.is_some()
+    }
+
+    /// Storage backend selected by `url`, falling back to S3.
+    pub fn backend(&self) -> RepositoryBackend<'_> {
+        match self.url.as_deref() {
+            Some(url) if url.starts_with("git+") => RepositoryBackend::Git(url),
+            Some(url) if url.starts_with("oci://") => RepositoryBackend::Oci(url),
+            Some(url) => RepositoryBackend::Http(url),
+            None => RepositoryBackend::S3,
+        }
+    }
+
+    /// Whether `sb` must not write to this repository: flagged `read_only`, or
+    /// served over plain HTTP.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only || matches!(self.backend(), RepositoryBackend::Http(_))
+    }
+
+    /// Whether reaching the repository's storage needs the network. Only git
+    /// repositories on this machine don't.
+    pub fn uses_network(&self) -> bool {
+        match self.backend() {
+            RepositoryBackend::Git(url) => {
+                !crate::git_storage::is_local_remote(url.strip_prefix("git+").unwrap_or(url))
+            }
+            _ => true,
+        }
     }
 
     /// Whether a local repository is configured.
@@ -111,6 +198,82 @@ impl RepositoryConfig {
     }
 }
 
+/// Where a repository stores its objects.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RepositoryBackend<'a> {
+    /// S3-compatible bucket named by `bucket_name`.
+    S3,
+    /// Read-only static HTTP(S) host.
+    Http(&'a str),
+    /// Git repository, as a `git+<url>` URL.
+    Git(&'a str),
+    /// OCI registry namespace, as an `oci://<registry>/<namespace>` URL.
+    Oci(&'a str),
+}
+
+/// Kind of host serving skill releases, which sets the default download URLs
+/// and authentication header.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseHostKind {
+    /// GitHub or GitHub Enterprise.
+    #[default]
+    Github,
+    Gitlab,
+    /// Gitea or Forgejo.
+    Gitea,
+}
+
+impl ReleaseHostKind {
+    /// Display name for the host.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Github => "GitHub",
+            Self::Gitlab => "GitLab",
+            Self::Gitea => "Gitea",
+        }
+    }
+}
+
+/// Release host `sb install` falls back to after the repositories.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ReleasesConfig {
+    /// Kind of host (defaults to "github").
+    #[serde(default)]
+    pub kind: ReleaseHostKind,
+
+    /// Web URL of the host, e.g. `https://gitlab.example.com` (defaults to
+    /// `https://github.com`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+
+    /// Project whose releases hold the skills, e.g. `group/skills`. Defaults to
+    /// the official skills repository on github.com.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+
+    /// Download URL of a released package, overriding the host's default.
+    /// Placeholders: `{base_url}`, `{repo}`, `{repo_encoded}`, `{tag}`,
+    /// `{version}` and `{skill}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_template: Option<String>,
+
+    /// Download URL of the latest release's package, with the same
+    /// placeholders except `{tag}` and `{version}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_url_template: Option<String>,
+
+    /// Environment variable holding the access token (defaults to
+    /// `GITHUB_TOKEN`/`GH_TOKEN`, `GITLAB_TOKEN` or `GITEA_TOKEN`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+
+    /// Header carrying the token (defaults to `PRIVATE-TOKEN` on GitLab and
+    /// `Authorization` elsewhere).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_header: Option<String>,
+}
+
 fn default_region() -> String {
     "us-east-1".to_string()
 }
@@ -145,6 +308,15 @@ pub struct Config {
     /// Optional repository configuration for S3-compatible storage.
     #[serde(default)]
     pub repository: Option<RepositoryConfig>,
+
+    /// Additional named repositories, used alongside `repository`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub repositories: Vec<RepositoryConfig>,
+
+    /// Release host to install from after the repositories (defaults to
+    /// GitHub releases).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub releases: Option<ReleasesConfig>,
 }
 
 impl Config {
@@ -159,7 +331,11 @@ impl Config {
 
     /// Parse configuration from a JSON string.
     pub fn parse(content: &str) -> Result<Self> {
-        serde_json::from_str(content).context("Failed to parse config JSON")
+        let config: Self = serde_json::from_str(content).context("Failed to parse config JSON")?;
+        if config.repositories.iter().any(|r| r.name.is_none()) {
+            anyhow::bail!("Every entry in 'repositories' needs a 'name'");
+        }
+        Ok(config)
     }
 
     /// Find a skill by name.
@@ -172,6 +348,76 @@ impl Config {
         self.skills.iter().map(|s| s.name.as_str()).collect()
     }
 
+    /// All configured repositories in cascade order: ascending `priority`, with
+    /// `repository` ahead of `repositories` entries of equal priority.
+    pub fn all_repositories(&self) -> Vec<&RepositoryConfig> {
+        let mut repos: Vec<&RepositoryConfig> = self
+            .repository
+            .iter()
+            .chain(self.repositories.iter())
+            .collect();
+        repos.sort_by_key(|r| r.priority);
+        repos
+    }
+
+    /// The `local` section of the first repository that has one.
+    pub fn local_repository(&self) -> Option<&LocalRepositoryConfig> {
+        self.all_repositories()
+            .into_iter()
+            .find_map(|r| r.local.as_ref())
+    }
+
+    /// The local repository's path: that of the first repository with a
+    /// `local` section, or the default location.
+    pub fn local_repo_path(&self) -> PathBuf {
+        self.all_repositories()
+            .into_iter()
+            .find(|r| r.has_local())
+            .map(|r| r.local_repo_path())
+            .unwrap_or_else(default_local_repo_path)
+    }
+
+    /// Find a repository by name.
+    pub fn find_repository(&self, name: &str) -> Result<&RepositoryConfig> {
+        let repos = self.all_repositories();
+        if let Some(repo) = repos.iter().find(|r| r.display_name() == name) {
+            return Ok(repo);
+        }
+        let names: Vec<&str> = repos.iter().map(|r| r.display_name()).collect();
+        match crate::suggest::did_you_mean(name, names.iter().copied()) {
+            Some(suggestion) => anyhow::bail!(
+                "No repository named '{}' in config (did you mean '{}'?)",
+                name,
+                suggestion
+            ),
+            None if names.is_empty() => anyhow::bail!(
+                "No repositories configured. Add a 'repository' or 'repositories' section to use repo commands."
+            ),
+            None => anyhow::bail!(
+                "No repository named '{}' in config. Configured: {}",
+                name,
+                names.join(", ")
+            ),
+        }
+    }
+
+    /// The repository to publish to: the named one, or the first writable
+    /// repository in cascade order.
+    pub fn write_repository(&self, name: Option<&str>) -> Result<&RepositoryConfig> {
+        let repo = match name {
+            Some(name) => self.find_repository(name)?,
+            None => self
+                .all_repositories()
+                .into_iter()
+                .find(|r| !r.is_read_only() && r.has_remote())
+                .context("No writable remote repository configured")?,
+        };
+        if repo.is_read_only() {
+            anyhow::bail!("Repository '{}' is read-only", repo.display_name());
+        }
+        Ok(repo)
+    }
+
     /// Merge another config into this one. Skills merge by name (other wins),
     /// repository replaces entirely if present in other.
     pub fn merge(&mut self, other: &Config) {
@@ -188,30 +434,329 @@ impl Config {
         if other.repository.is_some() {
             self.repository = other.repository.clone();
         }
+
+        if other.releases.is_some() {
+            self.releases = other.releases.clone();
+        }
+
+        // Named repositories merge by name, like skills
+        for other_repo in &other.repositories {
+            if let Some(pos) = self
+                .repositories
+                .iter()
+                .position(|r| r.name == other_repo.name)
+            {
+                self.repositories[pos] = other_repo.clone();
+            } else {
+                self.repositories.push(other_repo.clone());
+            }
+        }
     }
 
-    /// Load config with fallback hierarchy:
-    /// CLI --config flag → Project skills.json (if exists) → Global config (if exists) → Built-in defaults
+    /// Load the layered config, discovering the project root from the cwd.
+    ///
+    /// See [`Config::load_layered`] for the layer order.
     pub fn load_with_fallback(config_path: Option<&Path>) -> Result<Self> {
-        // If explicit config path provided, load it directly
+        let cwd = std::env::current_dir().context("Failed to determine current directory")?;
+        let project_root = crate::agent::find_project_root(&cwd);
+        Ok(Self::load_layered(config_path, &project_root)?.config)
+    }
+
+    /// Load config by merging layers, later layers overriding earlier ones field by field:
+    /// built-in defaults → global config → project `skills.json` → `--config` file →
+    /// `SB_*` environment variables.
+    ///
+    /// Skills merge by name; repository settings merge per field.
+    pub fn load_layered(config_path: Option<&Path>, project_root: &Path) -> Result<LayeredConfig> {
+        let mut layered = LayeredConfig::new();
+
+        let global = global_config_path();
+        if global.exists() {
+            layered.apply_file(ConfigLayer::Global, &global)?;
+        }
+
+        let project = project_root.join(PROJECT_CONFIG_FILE);
+        if project.exists() {
+            layered.apply_file(ConfigLayer::Project, &project)?;
+        }
+
         if let Some(path) = config_path {
-            return Self::load(path);
+            layered.apply_file(ConfigLayer::Explicit, path)?;
         }
 
-        // Try project-local skills.json
-        let project_config = Path::new("skills.json");
-        if project_config.exists() {
-            return Self::load(project_config);
+        layered.apply_env(std::env::vars())?;
+        layered.finish()?;
+        Ok(layered)
+    }
+}
+
+/// File name of the project-level config.
+pub const PROJECT_CONFIG_FILE: &str = "skills.json";
+
+/// Environment variables that override config values, with the path each one sets.
+pub const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("SB_REPOSITORY_NAME", "repository.name"),
+    ("SB_BUCKET_NAME", "repository.bucket_name"),
+    ("SB_REGION", "repository.region"),
+    ("SB_ENDPOINT", "repository.endpoint"),
+    ("SB_REPOSITORY_URL", "repository.url"),
+    ("SB_LOCAL_PATH", "repository.local.path"),
+    ("SB_LOCAL_CACHE", "repository.local.cache"),
+    ("SB_RELEASES_KIND", "releases.kind"),
+    ("SB_RELEASES_URL", "releases.base_url"),
+    ("SB_RELEASES_REPO", "releases.repo"),
+];
+
+/// A configuration layer, in increasing order of precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigLayer {
+    Default,
+    Global,
+    Project,
+    Explicit,
+    Env,
+}
+
+impl ConfigLayer {
+    /// Display name for the layer.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Global => "global",
+            Self::Project => "project",
+            Self::Explicit => "--config",
+            Self::Env => "env",
         }
+    }
+}
 
-        // Try global config
-        let global = global_config_path();
-        if global.exists() {
-            return Self::load(&global);
+/// Where an effective config value came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigOrigin {
+    pub layer: ConfigLayer,
+    /// File path or environment variable that set the value.
+    pub source: String,
+}
+
+/// The effective configuration together with the origin of every value.
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    /// The merged configuration.
+    pub config: Config,
+    /// Origin of each leaf value, keyed by path such as `repository.bucket_name`
+    /// or `skills[my-skill].llms_txt_url`.
+    pub origins: BTreeMap<String, ConfigOrigin>,
+    merged: Value,
+}
+
+impl Default for LayeredConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LayeredConfig {
+    /// Start from the built-in defaults.
+    pub fn new() -> Self {
+        Self {
+            config: Config::default(),
+            origins: BTreeMap::new(),
+            merged: Value::Object(Map::new()),
         }
+    }
 
-        // Return defaults
-        Ok(Config::default())
+    /// Merge a config file on top of the current layers.
+    pub fn apply_file(&mut self, layer: ConfigLayer, path: &Path) -> Result<()> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        // Parse as a full config first so errors point at the offending file
+        Config::parse(&content).with_context(|| format!("Invalid config: {}", path.display()))?;
+        let value: Value = serde_json::from_str(&content).context("Failed to parse config JSON")?;
+        self.apply_value(layer, &path.display().to_string(), &value);
+        Ok(())
+    }
+
+    /// Merge a raw JSON value on top of the current layers.
+    pub fn apply_value(&mut self, layer: ConfigLayer, source: &str, value: &Value) {
+        let origin = ConfigOrigin {
+            layer,
+            source: source.to_string(),
+        };
+        merge_value(&mut self.merged, value, "", &origin, &mut self.origins);
+    }
+
+    /// Apply `SB_*` overrides from the given environment variables.
+    pub fn apply_env<I: IntoIterator<Item = (String, String)>>(&mut self, vars: I) -> Result<()> {
+        for (key, raw) in vars {
+            let Some((_, path)) = ENV_OVERRIDES.iter().find(|(var, _)| *var == key) else {
+                continue;
+            };
+            let value = if path.ends_with(".cache") {
+                Value::Bool(parse_env_bool(&key, &raw)?)
+            } else {
+                Value::String(raw)
+            };
+
+            let mut overlay = value;
+            for segment in path.rsplit('.') {
+                let mut map = Map::new();
+                map.insert(segment.to_string(), overlay);
+                overlay = Value::Object(map);
+            }
+            self.apply_value(ConfigLayer::Env, &key, &overlay);
+        }
+        Ok(())
+    }
+
+    /// Deserialize the merged layers into the effective config.
+    pub fn finish(&mut self) -> Result<()> {
+        self.config =
+            serde_json::from_value(self.merged.clone()).context("Failed to merge config layers")?;
+        Ok(())
+    }
+
+    /// Every effective leaf value with its origin, in path order.
+    ///
+    /// Values no layer set explicitly are attributed to the built-in defaults.
+    pub fn entries(&self) -> Vec<(String, Value, ConfigOrigin)> {
+        let effective = serde_json::to_value(&self.config).unwrap_or(Value::Null);
+        let mut leaves = Vec::new();
+        collect_leaves(&effective, "", &mut leaves);
+
+        leaves
+            .into_iter()
+            .map(|(path, value)| {
+                let origin = self.origins.get(&path).cloned().unwrap_or(ConfigOrigin {
+                    layer: ConfigLayer::Default,
+                    source: "built-in".to_string(),
+                });
+                (path, value, origin)
+            })
+            .collect()
+    }
+}
+
+fn parse_env_bool(key: &str, raw: &str) -> Result<bool> {
+    match raw.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" => Ok(true),
+        "0" | "false" | "no" | "" => Ok(false),
+        _ => anyhow::bail!("Invalid boolean for {}: '{}'", key, raw),
+    }
+}
+
+fn join_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// Root arrays whose entries merge by `name` rather than position.
+const NAMED_LISTS: &[&str] = &["skills", "repositories"];
+
+/// Path segment for an entry of a named list, e.g. `skills[my-skill]`.
+fn named_path(list: &str, entry: &Value) -> Option<String> {
+    entry
+        .get("name")
+        .and_then(|n| n.as_str())
+        .map(|n| format!("{}[{}]", list, n))
+}
+
+/// Merge `overlay` into `base`, recording the origin of every leaf it sets.
+///
+/// Objects merge per key, the root `skills` and `repositories` arrays merge by
+/// name (an entry from a later layer replaces the earlier one), and `null`
+/// leaves the lower layer's value in place.
+fn merge_value(
+    base: &mut Value,
+    overlay: &Value,
+    path: &str,
+    origin: &ConfigOrigin,
+    origins: &mut BTreeMap<String, ConfigOrigin>,
+) {
+    match overlay {
+        Value::Null => {}
+        Value::Object(overlay_map) => {
+            if !base.is_object() {
+                *base = Value::Object(Map::new());
+            }
+            let base_map = base.as_object_mut().expect("base is an object");
+            for (key, value) in overlay_map {
+                let child_path = join_path(path, key);
+                if path.is_empty() && NAMED_LISTS.contains(&key.as_str()) {
+                    merge_named(base_map, key, value, origin, origins);
+                    continue;
+                }
+                let entry = base_map.entry(key.clone()).or_insert(Value::Null);
+                merge_value(entry, value, &child_path, origin, origins);
+            }
+        }
+        _ => {
+            origins.retain(|p, _| !(p.starts_with(path) && p[path.len()..].starts_with('.')));
+            origins.insert(path.to_string(), origin.clone());
+            *base = overlay.clone();
+        }
+    }
+}
+
+fn merge_named(
+    base_map: &mut Map<String, Value>,
+    list: &str,
+    overlay: &Value,
+    origin: &ConfigOrigin,
+    origins: &mut BTreeMap<String, ConfigOrigin>,
+) {
+    let Some(overlay_entries) = overlay.as_array() else {
+        return;
+    };
+    let base_entries = base_map
+        .entry(list.to_string())
+        .or_insert_with(|| Value::Array(Vec::new()));
+    if !base_entries.is_array() {
+        *base_entries = Value::Array(Vec::new());
+    }
+    let base_entries = base_entries.as_array_mut().expect("list is an array");
+
+    for entry in overlay_entries {
+        let Some(path) = named_path(list, entry) else {
+            continue;
+        };
+        origins.retain(|p, _| !p.starts_with(&format!("{}.", path)));
+        let mut leaves = Vec::new();
+        collect_leaves(entry, &path, &mut leaves);
+        for (leaf, _) in leaves {
+            origins.insert(leaf, origin.clone());
+        }
+
+        match base_entries
+            .iter()
+            .position(|e| e.get("name") == entry.get("name"))
+        {
+            Some(pos) => base_entries[pos] = entry.clone(),
+            None => base_entries.push(entry.clone()),
+        }
+    }
+}
+
+/// Flatten a JSON value into `(path, leaf)` pairs using the same paths as the origin map.
+fn collect_leaves(value: &Value, path: &str, leaves: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                if path.is_empty() && NAMED_LISTS.contains(&key.as_str()) {
+                    for entry in child.as_array().into_iter().flatten() {
+                        if let Some(entry_path) = named_path(key, entry) {
+                            collect_leaves(entry, &entry_path, leaves);
+                        }
+                    }
+                    continue;
+                }
+                collect_leaves(child, &join_path(path, key), leaves);
+            }
+        }
+        Value::Null => {}
+        _ => leaves.push((path.to_string(), value.clone())),
     }
 }
 
@@ -581,4 +1126,321 @@ mod tests {
         let path = global_config_path();
         assert!(path.to_string_lossy().contains("skills.config.json"));
     }
+
+    fn layered(layers: &[(ConfigLayer, &str)]) -> LayeredConfig {
+        let mut layered = LayeredConfig::new();
+        for (layer, json) in layers {
+            let value: Value = serde_json::from_str(json).unwrap();
+            layered.apply_value(*layer, layer.name(), &value);
+        }
+        layered.finish().unwrap();
+        layered
+    }
+
+    #[test]
+    fn test_layered_repository_merges_per_field() {
+        let layered = layered(&[
+            (
+                ConfigLayer::Global,
+                r#"{"repository": {"bucket_name": "company", "region": "eu-west-1"}}"#,
+            ),
+            (
+                ConfigLayer::Project,
+                r#"{"skills": [{"name": "a", "llms_txt_url": "https://a.com/llms.txt"}],
+                    "repository": {"local": {"cache": true}}}"#,
+            ),
+        ]);
+
+        let repo = layered.config.repository.unwrap();
+        assert_eq!(repo.bucket_name.as_deref(), Some("company"));
+        assert_eq!(repo.region, "eu-west-1");
+        assert!(repo.local_is_cache());
+        assert_eq!(layered.config.skills.len(), 1);
+    }
+
+    #[test]
+    fn test_layered_later_layer_wins() {
+        let layered = layered(&[
+            (
+                ConfigLayer::Global,
+                r#"{"repository": {"bucket_name": "global"}}"#,
+            ),
+            (
+                ConfigLayer::Project,
+                r#"{"repository": {"bucket_name": "project"}}"#,
+            ),
+            (
+                ConfigLayer::Explicit,
+                r#"{"repository": {"endpoint": "https://s3.local"}}"#,
+            ),
+        ]);
+
+        let repo = layered.config.repository.as_ref().unwrap();
+        assert_eq!(repo.bucket_name.as_deref(), Some("project"));
+        assert_eq!(repo.endpoint.as_deref(), Some("https://s3.local"));
+        assert_eq!(
+            layered.origins["repository.bucket_name"].layer,
+            ConfigLayer::Project
+        );
+        assert_eq!(
+            layered.origins["repository.endpoint"].layer,
+            ConfigLayer::Explicit
+        );
+    }
+
+    #[test]
+    fn test_layered_null_does_not_override() {
+        let layered = layered(&[
+            (
+                ConfigLayer::Global,
+                r#"{"repository": {"endpoint": "https://s3.local"}}"#,
+            ),
+            (
+                ConfigLayer::Project,
+                r#"{"repository": {"endpoint": null}}"#,
+            ),
+        ]);
+        assert_eq!(
+            layered.config.repository.unwrap().endpoint.as_deref(),
+            Some("https://s3.local")
+        );
+    }
+
+    #[test]
+    fn test_layered_skills_merge_by_name() {
+        let layered = layered(&[
+            (
+                ConfigLayer::Global,
+                r#"{"skills": [
+                    {"name": "a", "llms_txt_url": "https://a.com/llms.txt", "description": "old"},
+                    {"name": "b", "llms_txt_url": "https://b.com/llms.txt"}
+                ]}"#,
+            ),
+            (
+                ConfigLayer::Project,
+                r#"{"skills": [{"name": "a", "llms_txt_url": "https://a.com/llms.txt", "description": "new"}]}"#,
+            ),
+        ]);
+
+        assert_eq!(layered.config.skills.len(), 2);
+        assert_eq!(layered.config.find_skill("a").unwrap().description, "new");
+        assert_eq!(
+            layered.origins["skills[a].description"].layer,
+            ConfigLayer::Project
+        );
+        assert_eq!(
+            layered.origins["skills[b].llms_txt_url"].layer,
+            ConfigLayer::Global
+        );
+    }
+
+    #[test]
+    fn test_layered_env_overrides() {
+        let mut layered = LayeredConfig::new();
+        let value: Value =
+            serde_json::from_str(r#"{"repository": {"bucket_name": "file"}}"#).unwrap();
+        layered.apply_value(ConfigLayer::Project, "skills.json", &value);
+        layered
+            .apply_env(vec![
+                ("SB_BUCKET_NAME".to_string(), "env-bucket".to_string()),
+                ("SB_LOCAL_CACHE".to_string(), "1".to_string()),
+                ("SB_AGENT_OUTPUT".to_string(), "1".to_string()),
+            ])
+            .unwrap();
+        layered.finish().unwrap();
+
+        let repo = layered.config.repository.as_ref().unwrap();
+        assert_eq!(repo.bucket_name.as_deref(), Some("env-bucket"));
+        assert!(repo.local_is_cache());
+        assert_eq!(
+            layered.origins["repository.bucket_name"],
+            ConfigOrigin {
+                layer: ConfigLayer::Env,
+                source: "SB_BUCKET_NAME".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_layered_env_invalid_bool() {
+        let mut layered = LayeredConfig::new();
+        let result = layered.apply_env(vec![("SB_LOCAL_CACHE".to_string(), "maybe".to_string())]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_layered_entries_attribute_defaults() {
+        let layered = layered(&[(
+            ConfigLayer::Global,
+            r#"{"repository": {"bucket_name": "b"}}"#,
+        )]);
+
+        let entries = layered.entries();
+        let region = entries
+            .iter()
+            .find(|(path, _, _)| path == "repository.region")
+            .unwrap();
+        assert_eq!(region.1, Value::String("us-east-1".to_string()));
+        assert_eq!(region.2.layer, ConfigLayer::Default);
+
+        let bucket = entries
+            .iter()
+            .find(|(path, _, _)| path == "repository.bucket_name")
+            .unwrap();
+        assert_eq!(bucket.2.layer, ConfigLayer::Global);
+    }
+
+    #[test]
+    fn test_load_layered_reads_project_and_explicit() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join(PROJECT_CONFIG_FILE),
+            r#"{"skills": [{"name": "p", "llms_txt_url": "https://p.com/llms.txt"}]}"#,
+        )
+        .unwrap();
+        let explicit = tmp.path().join("extra.json");
+        fs::write(
+            &explicit,
+            r#"{"skills": [{"name": "e", "llms_txt_url": "https://e.com/llms.txt"}]}"#,
+        )
+        .unwrap();
+
+        let layered = Config::load_layered(Some(&explicit), tmp.path()).unwrap();
+        assert!(layered.config.find_skill("p").is_some());
+        assert!(layered.config.find_skill("e").is_some());
+        assert_eq!(
+            layered.origins["skills[e].name"].source,
+            explicit.display().to_string()
+        );
+    }
+
+    #[test]
+    fn test_load_layered_invalid_file_names_path() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let bad = tmp.path().join("bad.json");
+        fs::write(&bad, r#"{"skills": [{"name": "x"}]}"#).unwrap();
+
+        let err = Config::load_layered(Some(&bad), tmp.path()).unwrap_err();
+        assert!(format!("{:#}", err).contains("bad.json"));
+    }
+
+    #[test]
+    fn test_all_repositories_in_priority_order() {
+        let config = Config::parse(
+            r#"{
+                "repository": {"name": "company", "bucket_name": "company-skills", "priority": 1},
+                "repositories": [
+                    {"name": "community", "bucket_name": "community-skills", "priority": 5, "read_only": true},
+                    {"name": "team", "bucket_name": "team-skills", "profile": "team"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let names: Vec<&str> = config
+            .all_repositories()
+            .iter()
+            .map(|r| r.display_name())
+            .collect();
+        assert_eq!(names, vec!["team", "company", "community"]);
+        assert_eq!(
+            config.find_repository("team").unwrap().profile.as_deref(),
+            Some("team")
+        );
+    }
+
+    #[test]
+    fn test_find_repository_suggests_name() {
+        let config =
+            Config::parse(r#"{"repositories": [{"name": "community", "bucket_name": "c"}]}"#)
+                .unwrap();
+        let err = config.find_repository("comunity").unwrap_err();
+        assert!(err.to_string().contains("did you mean 'community'"));
+        assert!(Config::default().find_repository("any").is_err());
+    }
+
+    #[test]
+    fn test_repository_backend_from_url() {
+        let repo = |url: &str| RepositoryConfig {
+            url: Some(url.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            repo("oci://ghcr.io/acme/skills").backend(),
+            RepositoryBackend::Oci("oci://ghcr.io/acme/skills")
+        );
+        assert_eq!(
+            repo("git+https://example.com/s.git").backend(),
+            RepositoryBackend::Git("git+https://example.com/s.git")
+        );
+        assert!(!repo("oci://ghcr.io/acme/skills").is_read_only());
+        assert!(repo("https://example.com").is_read_only());
+    }
+
+    #[test]
+    fn test_uses_network() {
+        let repo = |url: &str| RepositoryConfig {
+            url: Some(url.to_string()),
+            ..Default::default()
+        };
+        assert!(!repo("git+file:///srv/skills.git").uses_network());
+        assert!(!repo("git+/srv/skills.git").uses_network());
+        assert!(repo("git+https://example.com/s.git").uses_network());
+        assert!(repo("git+git@github.com:acme/skills.git").uses_network());
+        assert!(repo("https://example.com").uses_network());
+        assert!(RepositoryConfig::default().uses_network());
+    }
+
+    #[test]
+    fn test_write_repository_skips_read_only() {
+        let config = Config::parse(
+            r#"{"repositories": [
+                {"name": "public", "bucket_name": "p", "read_only": true},
+                {"name": "private", "bucket_name": "q", "priority": 1}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.write_repository(None).unwrap().display_name(),
+            "private"
+        );
+        let err = config.write_repository(Some("public")).unwrap_err();
+        assert!(err.to_string().contains("read-only"));
+    }
+
+    #[test]
+    fn test_repositories_require_name() {
+        let err = Config::parse(r#"{"repositories": [{"bucket_name": "b"}]}"#).unwrap_err();
+        assert!(err.to_string().contains("needs a 'name'"));
+    }
+
+    #[test]
+    fn test_layered_repositories_merge_by_name() {
+        let layered = layered(&[
+            (
+                ConfigLayer::Global,
+                r#"{"repositories": [
+                    {"name": "company", "bucket_name": "company-skills"},
+                    {"name": "community", "bucket_name": "community-skills"}
+                ]}"#,
+            ),
+            (
+                ConfigLayer::Project,
+                r#"{"repositories": [{"name": "company", "bucket_name": "other", "priority": -1}]}"#,
+            ),
+        ]);
+
+        let repos = layered.config.all_repositories();
+        assert_eq!(repos.len(), 2);
+        assert_eq!(repos[0].bucket_name.as_deref(), Some("other"));
+        assert_eq!(
+            layered.origins["repositories[company].bucket_name"].layer,
+            ConfigLayer::Project
+        );
+        assert_eq!(
+            layered.origins["repositories[community].bucket_name"].layer,
+            ConfigLayer::Global
+        );
+    }
 }
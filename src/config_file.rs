@@ -0,0 +1,638 @@
+//! Validation and in-place editing of config files.
+//!
+//! Validation walks the file against the published JSON Schema
+//! (`schema/skills.schema.json`), so the schema is the single list of accepted
+//! keys. Edits go through [`crate::json_doc`] to keep formatting and key order.
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::config::{Config, SkillConfig};
+use crate::json_doc::{self, JsonNode, NodeKind};
+use crate::suggest::did_you_mean;
+
+/// JSON Schema for `skills.json` and `skills.config.json`.
+pub const CONFIG_SCHEMA: &str = include_str!("../schema/skills.schema.json");
+
+/// A problem found while validating a config file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    /// 1-based line number.
+    pub line: usize,
+    /// Config path of the offending value, e.g. `skills[foo].llms_txt_url`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "line {}: {}", self.line, self.message)
+        } else {
+            write!(f, "line {}: {}: {}", self.line, self.path, self.message)
+        }
+    }
+}
+
+/// Validate config file contents against the schema.
+///
+/// Reports unknown keys, type mismatches, invalid URLs and duplicate skill or
+/// repository names.
+/// Returns an error if the text is not valid JSON.
+pub fn validate_config_text(text: &str) -> Result<Vec<ConfigIssue>> {
+    let root = json_doc::parse(text)?;
+    let schema: Value = serde_json::from_str(CONFIG_SCHEMA).context("Invalid built-in schema")?;
+
+    let mut validator = Validator {
+        schema: &schema,
+        issues: Vec::new(),
+    };
+    validator.check(&root, &schema, "");
+    validator.check_duplicate_names(&root);
+
+    let mut issues = validator.issues;
+    issues.sort_by_key(|i| i.line);
+    Ok(issues)
+}
+
+struct Validator<'a> {
+    schema: &'a Value,
+    issues: Vec<ConfigIssue>,
+}
+
+impl<'a> Validator<'a> {
+    /// Follow a local `$ref` such as `#/$defs/SkillConfig`.
+    fn resolve(&self, schema: &'a Value) -> &'a Value {
+        match schema.get("$ref").and_then(|r| r.as_str()) {
+            Some(reference) => reference
+                .strip_prefix("#/")
+                .map(|p| {
+                    p.split('/').fold(self.schema, |s, segment| {
+                        s.get(segment).unwrap_or(&Value::Null)
+                    })
+                })
+                .unwrap_or(&Value::Null),
+            None => schema,
+        }
+    }
+
+    fn issue(&mut self, line: usize, path: &str, message: String) {
+        self.issues.push(ConfigIssue {
+            line,
+            path: path.to_string(),
+            message,
+        });
+    }
+
+    fn check(&mut self, node: &JsonNode, schema: &'a Value, path: &str) {
+        // `required` next to a `$ref` adds to the referenced definition's list
+        let extra_required = schema
+            .get("required")
+            .filter(|_| schema.get("$ref").is_some());
+        let schema = self.resolve(schema);
+
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(t)) => vec![t.as_str()],
+            Some(Value::Array(ts)) => ts.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| matches_type(node, t)) {
+            let expected = types.join(" or ");
+            let found = node.type_name();
+            self.issue(
+                node.line,
+                path,
+                format!("expected {}, found {}", expected, found),
+            );
+            return;
+        }
+
+        match &node.kind {
+            NodeKind::Object(members) => {
+                let properties = schema.get("properties").and_then(|p| p.as_object());
+                let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+
+                for member in members {
+                    let child_path = join_path(path, &member.key);
+                    match properties.and_then(|p| p.get(&member.key)) {
+                        Some(child) => self.check(&member.value, child, &child_path),
+                        None if closed => {
+                            let known = properties
+                                .map(|p| p.keys().map(|k| k.as_str()).collect::<Vec<_>>())
+                                .unwrap_or_default();
+                            let message = match did_you_mean(&member.key, known) {
+                                Some(s) => {
+                                    format!("unknown key '{}' (did you mean '{}'?)", member.key, s)
+                                }
+                                None => format!("unknown key '{}'", member.key),
+                            };
+                            self.issue(member.key_line, path, message);
+                        }
+                        None => {}
+                    }
+                }
+
+                let required = [schema.get("required"), extra_required]
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|r| r.as_array())
+                    .flatten();
+                for key in required.filter_map(|k| k.as_str()) {
+                    if node.member(key).is_none() {
+                        self.issue(node.line, path, format!("missing required key '{}'", key));
+                    }
+                }
+            }
+            NodeKind::Array(elements) => {
+                if let Some(items) = schema.get("items") {
+                    for (i, element) in elements.iter().enumerate() {
+                        let label = element
+                            .member("name")
+                            .and_then(|m| m.value.as_str())
+                            .map(|n| n.to_string())
+                            .unwrap_or_else(|| i.to_string());
+                        self.check(element, items, &format!("{}[{}]", path, label));
+                    }
+                }
+            }
+            NodeKind::String(s) if schema.get("format").and_then(|f| f.as_str()) == Some("uri") => {
+                // `x-schemes` widens the default http/https to other URL schemes
+                let schemes: Vec<&str> = schema
+                    .get("x-schemes")
+                    .and_then(|v| v.as_array())
+                    .map(|a| a.iter().filter_map(|v| v.as_str()).collect())
+                    .unwrap_or_else(|| vec!["http", "https"]);
+                if let Err(e) = check_url(s, &schemes) {
+                    self.issue(node.line, path, e);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Report entries of the `skills` and `repositories` lists that reuse a name.
+    fn check_duplicate_names(&mut self, root: &JsonNode) {
+        for (list, kind) in [("skills", "skill"), ("repositories", "repository")] {
+            let Some(entries) = root.member(list).and_then(|m| m.value.elements()) else {
+                continue;
+            };
+
+            let mut seen: HashMap<&str, usize> = HashMap::new();
+            for entry in entries {
+                let Some(name) = entry.member("name").and_then(|m| m.value.as_str()) else {
+                    continue;
+                };
+                match seen.get(name) {
+                    Some(first) => {
+                        let message = format!(
+                            "duplicate {} name '{}' (first defined on line {})",
+                            kind, name, first
+                        );
+                        self.issue(entry.line, list, message);
+                    }
+                    None => {
+                        seen.insert(name, entry.line);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn matches_type(node: &JsonNode, ty: &str) -> bool {
+    match (&node.kind, ty) {
+        (NodeKind::Object(_), "object")
+        | (NodeKind::Array(_), "array")
+        | (NodeKind::String(_), "string")
+        | (NodeKind::Number(_), "number")
+        | (NodeKind::Bool(_), "boolean")
+        | (NodeKind::Null, "null") => true,
+        (NodeKind::Number(n), "integer") => !n.contains(['.', 'e', 'E']),
+        _ => false,
+    }
+}
+
+fn check_url(s: &str, schemes: &[&str]) -> std::result::Result<(), String> {
+    match url::Url::parse(s) {
+        Ok(url) if schemes.contains(&url.scheme()) => Ok(()),
+        Ok(url) => Err(format!(
+            "URL '{}' must use {}, not '{}'",
+            s,
+            schemes.join(" or "),
+            url.scheme()
+        )),
+        Err(e) => Err(format!("invalid URL '{}': {}", s, e)),
+    }
+}
+
+fn join_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+/// A segment of a config path such as `skills[foo].description`.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    /// Select an array element by its `name` field.
+    Named(String),
+}
+
+fn parse_path(path: &str) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut rest = path;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after
+                .find(']')
+                .with_context(|| format!("Unclosed '[' in config path '{}'", path))?;
+            segments.push(Segment::Named(after[..end].to_string()));
+            rest = &after[end + 1..];
+        } else {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            if end == 0 {
+                bail!("Invalid config path '{}'", path);
+            }
+            segments.push(Segment::Key(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+        rest = rest.strip_prefix('.').unwrap_or(rest);
+    }
+    if segments.is_empty() {
+        bail!("Config path must not be empty");
+    }
+    Ok(segments)
+}
+
+/// Wrap `value` in nested objects for the key segments from `start` on.
+fn nest(segments: &[Segment], start: usize, value: Value) -> Result<Value> {
+    segments[start..]
+        .iter()
+        .enumerate()
+        .rev()
+        .try_fold(value, |acc, (i, segment)| match segment {
+            Segment::Key(key) => Ok(Value::Object([(key.clone(), acc)].into_iter().collect())),
+            Segment::Named(name) => Err(no_entry(&segments[..start + i], name)),
+        })
+}
+
+/// Interpret a `config set` value: JSON literals, arrays and objects are used
+/// as-is and anything else is treated as a plain string.
+pub fn parse_set_value(raw: &str) -> Value {
+    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
+
+/// Set the value at `path`, creating missing objects along the way.
+pub fn set_value(text: &str, path: &str, value: Value) -> Result<String> {
+    let segments = parse_path(path)?;
+    let root = json_doc::parse(text)?;
+    let mut node = &root;
+
+    for (i, segment) in segments.iter().enumerate() {
+        match (segment, &node.kind) {
+            (Segment::Key(_), NodeKind::Null) => {
+                return json_doc::replace_value(text, node, &nest(&segments, i, value)?);
+            }
+            (Segment::Key(key), NodeKind::Object(_)) => match node.member(key) {
+                Some(member) => node = &member.value,
+                None => {
+                    let nested = nest(&segments, i + 1, value)?;
+                    return json_doc::insert_member(text, node, key, &nested);
+                }
+            },
+            (Segment::Named(name), NodeKind::Array(elements)) => {
+                node = elements
+                    .iter()
+                    .find(|e| e.member("name").and_then(|m| m.value.as_str()) == Some(name))
+                    .ok_or_else(|| no_entry(&segments[..i], name))?;
+            }
+            _ => bail!(
+                "Cannot set '{}': {} is not {}",
+                path,
+                segments_label(&segments[..i]),
+                match segment {
+                    Segment::Key(_) => "an object",
+                    Segment::Named(_) => "an array",
+                }
+            ),
+        }
+    }
+
+    json_doc::replace_value(text, node, &value)
+}
+
+fn segments_label(segments: &[Segment]) -> String {
+    if segments.is_empty() {
+        return "the config root".to_string();
+    }
+    format!("'{}'", segments_path(segments))
+}
+
+fn segments_path(segments: &[Segment]) -> String {
+    let mut label = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Key(key) => label = join_path(&label, key),
+            Segment::Named(name) => label = format!("{}[{}]", label, name),
+        }
+    }
+    label
+}
+
+/// Error for a `[name]` segment matching no element of the array before it.
+fn no_entry(before: &[Segment], name: &str) -> anyhow::Error {
+    let array = if before.is_empty() {
+        "the config root".to_string()
+    } else {
+        segments_path(before)
+    };
+    anyhow::anyhow!("No entry named '{}' in {}", name, array)
+}
+
+/// Skill entry as written by `config add-skill`, omitting unset fields.
+#[derive(Serialize)]
+struct NewSkill<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    description: &'a str,
+    llms_txt_url: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_url: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path_prefix: Option<&'a str>,
+}
+
+/// Append a skill to the `skills` array, creating it if needed.
+pub fn add_skill(text: &str, skill: &SkillConfig) -> Result<String> {
+    let root = json_doc::parse(text)?;
+    if root.members().is_none() {
+        bail!("Config root must be a JSON object");
+    }
+
+    let entry = NewSkill {
+        name: &skill.name,
+        description: &skill.description,
+        llms_txt_url: &skill.llms_txt_url,
+        base_url: skill.base_url.as_deref(),
+        path_prefix: skill.path_prefix.as_deref(),
+    };
+
+    match root.member("skills") {
+        Some(member) => {
+            let elements = member
+                .value
+                .elements()
+                .context("'skills' must be an array")?;
+            if elements
+                .iter()
+                .any(|e| e.member("name").and_then(|m| m.value.as_str()) == Some(&skill.name))
+            {
+                bail!("Skill '{}' already exists in config", skill.name);
+            }
+            json_doc::push_element(text, &member.value, &entry)
+        }
+        None => json_doc::insert_member(text, &root, "skills", &[entry]),
+    }
+}
+
+/// Remove the skill with the given name from the `skills` array.
+pub fn remove_skill(text: &str, name: &str) -> Result<String> {
+    let root = json_doc::parse(text)?;
+    let skills = root
+        .member("skills")
+        .map(|m| &m.value)
+        .with_context(|| format!("Skill '{}' not found in config", name))?;
+    let index = skills
+        .elements()
+        .context("'skills' must be an array")?
+        .iter()
+        .position(|e| e.member("name").and_then(|m| m.value.as_str()) == Some(name))
+        .with_context(|| format!("Skill '{}' not found in config", name))?;
+
+    json_doc::remove_element(text, skills, index)
+}
+
+/// Check edited config text before it is written: it must still parse as a
+/// [`Config`] and pass schema validation.
+pub fn check_edited(text: &str) -> Result<()> {
+    let issues = validate_config_text(text)?;
+    if !issues.is_empty() {
+        let lines: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+        bail!(
+            "Edit would make the config invalid:\n  {}",
+            lines.join("\n  ")
+        );
+    }
+    Config::parse(text)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{LocalRepositoryConfig, ReleaseHostKind, ReleasesConfig, RepositoryConfig};
+
+    const CONFIG: &str = r#"{
+  "skills": [
+    {
+      "name": "alpha",
+      "llms_url": "https://alpha.dev/llms.txt"
+    },
+    {
+      "name": "beta",
+      "llms_txt_url": "not a url"
+    },
+    {
+      "name": "alpha",
+      "llms_txt_url": "ftp://alpha.dev/llms.txt"
+    }
+  ],
+  "repository": {
+    "bucketName": "skills",
+    "local": { "cache": "yes" }
+  }
+}
+"#;
+
+    fn messages(text: &str) -> Vec<String> {
+        validate_config_text(text)
+            .unwrap()
+            .iter()
+            .map(|i| i.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_schema_covers_config_fields() {
+        let config = Config {
+            skills: vec![SkillConfig {
+                name: "s".to_string(),
+                description: "d".to_string(),
+                llms_txt_url: "https://example.com/llms.txt".to_string(),
+                base_url: Some("https://example.com".to_string()),
+                path_prefix: Some("/docs".to_string()),
+            }],
+            repository: Some(RepositoryConfig {
+                name: Some("r".to_string()),
+                local: Some(LocalRepositoryConfig {
+                    path: Some("/tmp/r".to_string()),
+                    cache: true,
+                    max_size_mb: Some(512),
+                    max_age_days: Some(30),
+                    keep_versions: Some(3),
+                }),
+                bucket_name: Some("b".to_string()),
+                region: "us-east-1".to_string(),
+                endpoint: Some("https://s3.example.com".to_string()),
+                url: Some("git+https://example.com/skills.git".to_string()),
+                branch: Some("skills".to_string()),
+                profile: Some("work".to_string()),
+                priority: 1,
+                read_only: true,
+            }),
+            repositories: vec![RepositoryConfig {
+                name: Some("community".to_string()),
+                ..Default::default()
+            }],
+            releases: Some(ReleasesConfig {
+                kind: ReleaseHostKind::Gitlab,
+                base_url: Some("https://gitlab.example.com".to_string()),
+                repo: Some("group/skills".to_string()),
+                url_template: Some("{base_url}/{repo}/{tag}/{skill}.skill".to_string()),
+                latest_url_template: Some("{base_url}/{repo}/latest/{skill}.skill".to_string()),
+                token_env: Some("CI_JOB_TOKEN".to_string()),
+                auth_header: Some("JOB-TOKEN".to_string()),
+            }),
+        };
+        let text = serde_json::to_string_pretty(&config).unwrap();
+        assert_eq!(validate_config_text(&text).unwrap(), Vec::new());
+        assert_eq!(validate_config_text("{}").unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn test_validate_reports_issues_with_lines() {
+        let messages = messages(CONFIG);
+        assert_eq!(
+            messages,
+            vec![
+                "line 3: skills[alpha]: missing required key 'llms_txt_url'",
+                "line 5: skills[alpha]: unknown key 'llms_url' (did you mean 'llms_txt_url'?)",
+                "line 9: skills[beta].llms_txt_url: invalid URL 'not a url': relative URL without a base",
+                "line 11: skills: duplicate skill name 'alpha' (first defined on line 3)",
+                "line 13: skills[alpha].llms_txt_url: URL 'ftp://alpha.dev/llms.txt' must use http or https, not 'ftp'",
+                "line 17: repository: unknown key 'bucketName' (did you mean 'bucket_name'?)",
+                "line 18: repository.local.cache: expected boolean, found string",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_malformed_json() {
+        let err = validate_config_text("{\n  \"skills\": [\n}").unwrap_err();
+        assert!(err.to_string().contains("line 3"));
+    }
+
+    #[test]
+    fn test_set_value_existing_and_new_keys() {
+        let text = "{\n    \"repository\": {\n        \"bucket_name\": \"old\"\n    }\n}\n";
+
+        let edited = set_value(text, "repository.bucket_name", parse_set_value("new")).unwrap();
+        assert_eq!(edited, text.replace("old", "new"));
+
+        let edited = set_value(text, "repository.local.cache", parse_set_value("true")).unwrap();
+        assert_eq!(
+            edited,
+            "{\n    \"repository\": {\n        \"bucket_name\": \"old\",\n        \"local\": {\n            \"cache\": true\n        }\n    }\n}\n"
+        );
+        check_edited(&edited).unwrap();
+    }
+
+    #[test]
+    fn test_set_value_replaces_null_and_selects_skills() {
+        let text = r#"{"skills": [{"name": "a", "llms_txt_url": "https://a.dev/llms.txt"}], "repository": null}"#;
+
+        let edited = set_value(text, "repository.bucket_name", parse_set_value("b")).unwrap();
+        let config = Config::parse(&edited).unwrap();
+        assert_eq!(config.repository.unwrap().bucket_name.as_deref(), Some("b"));
+
+        let edited = set_value(text, "skills[a].description", parse_set_value("Docs")).unwrap();
+        assert!(edited.starts_with(
+            r#"{"skills": [{"name": "a", "llms_txt_url": "https://a.dev/llms.txt", "description": "Docs"}]"#
+        ));
+
+        let err = set_value(text, "skills[missing].description", Value::Null).unwrap_err();
+        assert_eq!(err.to_string(), "No entry named 'missing' in skills");
+        assert!(set_value(text, "skills.name", Value::Null).is_err());
+
+        let text = r#"{"repositories": [{"name": "dev"}]}"#;
+        let err =
+            set_value(text, "repositories[prod].bucket_name", parse_set_value("b")).unwrap_err();
+        assert_eq!(err.to_string(), "No entry named 'prod' in repositories");
+        let err = set_value("{}", "repositories[prod].bucket_name", Value::Null).unwrap_err();
+        assert_eq!(err.to_string(), "No entry named 'prod' in repositories");
+    }
+
+    #[test]
+    fn test_check_edited_rejects_unknown_keys() {
+        let edited = set_value("{}", "repository.bucketName", parse_set_value("b")).unwrap();
+        let err = check_edited(&edited).unwrap_err();
+        assert!(err.to_string().contains("did you mean 'bucket_name'"));
+    }
+
+    #[test]
+    fn test_add_and_remove_skill() {
+        let text = "{\n  \"skills\": [\n    {\n      \"name\": \"a\",\n      \"llms_txt_url\": \"https://a.dev/llms.txt\"\n    }\n  ]\n}\n";
+        let skill = SkillConfig {
+            name: "b".to_string(),
+            description: String::new(),
+            llms_txt_url: "https://b.dev/llms.txt".to_string(),
+            base_url: None,
+            path_prefix: None,
+        };
+
+        let added = add_skill(text, &skill).unwrap();
+        assert!(added.contains(
+            "    },\n    {\n      \"name\": \"b\",\n      \"llms_txt_url\": \"https://b.dev/llms.txt\"\n    }\n  ]"
+        ));
+        assert!(add_skill(&added, &skill).is_err());
+
+        let removed = remove_skill(&added, "b").unwrap();
+        assert_eq!(removed, text);
+        assert!(remove_skill(text, "missing").is_err());
+    }
+
+    #[test]
+    fn test_add_skill_creates_skills_array() {
+        let skill = SkillConfig {
+            name: "a".to_string(),
+            description: "Docs".to_string(),
+            llms_txt_url: "https://a.dev/llms.txt".to_string(),
+            base_url: None,
+            path_prefix: None,
+        };
+        let added = add_skill("{\n  \"repository\": null\n}\n", &skill).unwrap();
+        let config = Config::parse(&added).unwrap();
+        assert_eq!(config.skills[0].description, "Docs");
+        check_edited(&added).unwrap();
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("skills[my.skill].base_url").unwrap(),
+            vec![
+                Segment::Key("skills".to_string()),
+                Segment::Named("my.skill".to_string()),
+                Segment::Key("base_url".to_string()),
+            ]
+        );
+        assert!(parse_path("").is_err());
+        assert!(parse_path("skills[oops").is_err());
+    }
+}
@@ -0,0 +1,268 @@
+//! Install skills straight from a package URL or a git ref, bypassing the
+//! repositories and release hosts.
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::git_storage::{is_local_remote, run_git, workdir_name};
+use crate::install::download_package;
+use crate::lockfile::LockedSkill;
+use crate::output::Output;
+use crate::package::{package_skill_to_memory, read_skill_frontmatter};
+use crate::validate::parse_frontmatter;
+
+/// Where a direct install comes from, parsed from the `sb install` argument.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DirectSource {
+    /// A .skill package at an `http://` or `https://` URL.
+    Url(String),
+    /// A skill directory in a git repository, as
+    /// `git+<url>[#<ref>][:<path>]`.
+    Git {
+        remote: String,
+        /// Branch, tag or commit; the remote's default branch if `None`.
+        reference: Option<String>,
+        /// Skill directory in the repository; its root if `None`.
+        path: Option<String>,
+    },
+}
+
+impl DirectSource {
+    /// Parse an install argument. Returns `None` for a skill name.
+    pub fn parse(spec: &str) -> Result<Option<Self>> {
+        if spec.starts_with("http://") || spec.starts_with("https://") {
+            url::Url::parse(spec).with_context(|| format!("Invalid URL: {}", spec))?;
+            return Ok(Some(Self::Url(spec.to_string())));
+        }
+        let Some(rest) = spec.strip_prefix("git+") else {
+            return Ok(None);
+        };
+        let (remote, fragment) = rest.split_once('#').unwrap_or((rest, ""));
+        if remote.is_empty() {
+            anyhow::bail!("Git source '{}' has no repository URL", spec);
+        }
+        // Refs can't contain ':', so the first one starts the path
+        let (reference, path) = fragment.split_once(':').unwrap_or((fragment, ""));
+        // Anything starting with '-' would reach git as an option
+        if remote.starts_with('-') || reference.starts_with('-') {
+            anyhow::bail!("Git source '{}' has an invalid repository or ref", spec);
+        }
+        if path.split('/').any(|c| c == "..") {
+            anyhow::bail!("Git source '{}' has a path outside the repository", spec);
+        }
+        let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        Ok(Some(Self::Git {
+            remote: remote.to_string(),
+            reference: non_empty(reference),
+            path: non_empty(path.trim_matches('/')),
+        }))
+    }
+}
+
+impl fmt::Display for DirectSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Url(url) => write!(f, "{}", url),
+            Self::Git {
+                remote,
+                reference,
+                path,
+            } => {
+                write!(f, "git+{}", remote)?;
+                if reference.is_some() || path.is_some() {
+                    write!(f, "#{}", reference.as_deref().unwrap_or_default())?;
+                }
+                if let Some(path) = path {
+                    write!(f, ":{}", path)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A skill package fetched from a [`DirectSource`].
+#[derive(Debug)]
+pub struct FetchedSkill {
+    /// Bytes of the .skill package.
+    pub data: Vec<u8>,
+    /// Commit a git source's ref resolved to.
+    pub commit: Option<String>,
+}
+
+impl FetchedSkill {
+    /// The lockfile entry for this package, installed from `source`.
+    pub fn locked(&self, source: &DirectSource) -> LockedSkill {
+        LockedSkill {
+            version: read_skill_frontmatter(&self.data)
+                .ok()
+                .and_then(|f| f.version),
+            source: source.to_string(),
+            commit: self.commit.clone(),
+            sha256: hex::encode(Sha256::digest(&self.data)),
+        }
+    }
+}
+
+/// Download or check out the skill `source` points at, as a .skill package.
+pub fn fetch(source: &DirectSource, output: &Output) -> Result<FetchedSkill> {
+    match source {
+        DirectSource::Url(url) => Ok(FetchedSkill {
+            data: download_package(url, output)?,
+            commit: None,
+        }),
+        DirectSource::Git {
+            remote,
+            reference,
+            path,
+        } => {
+            if !is_local_remote(remote) {
+                crate::offline::ensure_online(format!("git repository {}", remote))?;
+            }
+            let workdir = tempfile::Builder::new()
+                .prefix(&format!("sb-git-{}-", workdir_name(remote)))
+                .tempdir()
+                .context("Failed to create a temporary directory")?;
+            fetch_git(
+                remote,
+                reference.as_deref(),
+                path.as_deref(),
+                workdir.path(),
+                output,
+            )
+        }
+    }
+}
+
+/// Fetch `reference` of `remote` into `workdir`, then validate and package
+/// the skill at `path` in it.
+fn fetch_git(
+    remote: &str,
+    reference: Option<&str>,
+    path: Option<&str>,
+    workdir: &Path,
+    output: &Output,
+) -> Result<FetchedSkill> {
+    let reference = reference.unwrap_or("HEAD");
+    output.step(&format!("Fetching {} from {}", reference, remote));
+    let git = |args: &[&str]| run_git(Some(workdir), args);
+    git(&["init", "--quiet"])?;
+
+    // Branches and tags fetch shallowly; commits may need the full history,
+    // since not every server serves unadvertised objects
+    if git(&["fetch", "--quiet", "--depth", "1", "--", remote, reference]).is_ok() {
+        git(&["checkout", "--quiet", "FETCH_HEAD"])?;
+    } else {
+        git(&[
+            "fetch",
+            "--quiet",
+            "--",
+            remote,
+            "+refs/heads/*:refs/remotes/origin/*",
+            "+refs/tags/*:refs/tags/*",
+        ])?;
+        git(&["checkout", "--quiet", reference, "--"])
+            .with_context(|| format!("No ref '{}' in {}", reference, remote))?;
+    }
+    let commit = git(&["rev-parse", "HEAD"])?.trim().to_string();
+    output.step(&format!("Checked out {}", &commit[..commit.len().min(12)]));
+
+    let skill_dir = match path {
+        Some(path) => workdir.join(path),
+        None => workdir.to_path_buf(),
+    };
+    let skill_md = skill_dir.join("SKILL.md");
+    let content = fs::read_to_string(&skill_md).with_context(|| {
+        format!(
+            "No SKILL.md at '{}' in {} at {}",
+            path.unwrap_or("/"),
+            remote,
+            reference
+        )
+    })?;
+    // The package is named after the skill, not the checkout directory
+    let name = parse_frontmatter(&content)
+        .ok()
+        .and_then(|f| f.name)
+        .filter(|n| !n.is_empty())
+        .or_else(|| {
+            path.and_then(|p| p.rsplit('/').next())
+                .map(|n| n.to_string())
+        })
+        .context("SKILL.md has no name")?;
+
+    let data = package_skill_to_memory(&skill_dir, &name, output)?;
+    Ok(FetchedSkill {
+        data,
+        commit: Some(commit),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(remote: &str, reference: Option<&str>, path: Option<&str>) -> DirectSource {
+        DirectSource::Git {
+            remote: remote.to_string(),
+            reference: reference.map(|r| r.to_string()),
+            path: path.map(|p| p.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_sources() {
+        assert_eq!(DirectSource::parse("my-skill").unwrap(), None);
+        assert_eq!(
+            DirectSource::parse("https://example.com/a/foo.skill").unwrap(),
+            Some(DirectSource::Url(
+                "https://example.com/a/foo.skill".to_string()
+            ))
+        );
+
+        let cases = [
+            (
+                "git+https://example.com/repo.git",
+                git("https://example.com/repo.git", None, None),
+            ),
+            (
+                "git+https://example.com/repo.git#v1.2.0",
+                git("https://example.com/repo.git", Some("v1.2.0"), None),
+            ),
+            (
+                "git+https://example.com/repo.git#main:skills/foo",
+                git(
+                    "https://example.com/repo.git",
+                    Some("main"),
+                    Some("skills/foo"),
+                ),
+            ),
+            (
+                "git+ssh://git@example.com:2222/repo.git#:skills/foo",
+                git(
+                    "ssh://git@example.com:2222/repo.git",
+                    None,
+                    Some("skills/foo"),
+                ),
+            ),
+        ];
+        for (spec, expected) in cases {
+            let parsed = DirectSource::parse(spec).unwrap().unwrap();
+            assert_eq!(parsed, expected);
+            assert_eq!(parsed.to_string(), spec);
+        }
+
+        assert!(DirectSource::parse("git+#main").is_err());
+        for spec in [
+            "git+--upload-pack=touch /tmp/pwned",
+            "git+file:///srv/repo.git#--upload-pack=touch /tmp/pwned",
+            "git+file:///srv/repo.git#main:../../..",
+            "git+file:///srv/repo.git#:skills/../../etc",
+        ] {
+            assert!(DirectSource::parse(spec).is_err(), "{}", spec);
+        }
+    }
+}
@@ -14,6 +14,7 @@ use crate::output::Output;
 
 /// HTTP client with reasonable defaults.
 fn create_client() -> Result<Client> {
+    crate::offline::ensure_online("llms.txt documentation downloads")?;
     Client::builder()
         .timeout(Duration::from_secs(60))
         .user_agent("sb/1.0")
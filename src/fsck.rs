@@ -0,0 +1,323 @@
+//! Repository consistency checks and index rebuilds.
+
+use anyhow::{Context, Result};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use crate::index::{compare_semver, update_index, IndexEntry, SkillsIndex, INDEX_KEY};
+use crate::package::read_skill_frontmatter;
+use crate::storage::{content_etag, StorageOperations};
+
+/// A problem found by [`check`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue {
+    /// `skills_index.json` is missing although packages exist.
+    MissingIndex,
+    /// `skills_index.json` exists but can't be parsed.
+    UnreadableIndex(String),
+    /// An index entry points at a key that doesn't exist.
+    MissingObject {
+        skill: String,
+        version: String,
+        key: String,
+    },
+    /// A package's content doesn't match the checksum in the index.
+    ChecksumMismatch {
+        key: String,
+        expected: String,
+        actual: String,
+    },
+    /// A package that isn't a readable .skill archive.
+    UnreadablePackage { key: String, error: String },
+    /// An object that no index entry accounts for.
+    OrphanObject { key: String },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::MissingIndex => write!(f, "{} is missing", INDEX_KEY),
+            Issue::UnreadableIndex(e) => write!(f, "{} is unreadable: {}", INDEX_KEY, e),
+            Issue::MissingObject {
+                skill,
+                version,
+                key,
+            } => write!(f, "{} v{} points at missing object {}", skill, version, key),
+            Issue::ChecksumMismatch {
+                key,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Checksum mismatch for {}: index has {}, object is {}",
+                key, expected, actual
+            ),
+            Issue::UnreadablePackage { key, error } => {
+                write!(f, "Unreadable package {}: {}", key, error)
+            }
+            Issue::OrphanObject { key } => write!(f, "Orphan object not in index: {}", key),
+        }
+    }
+}
+
+/// Result of a consistency check.
+#[derive(Debug, Default)]
+pub struct FsckReport {
+    pub issues: Vec<Issue>,
+    /// Number of packages whose content was read and verified.
+    pub packages_checked: usize,
+}
+
+impl FsckReport {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Parse a package key `skills/<name>/<version>/<name>.skill`.
+fn parse_package_key(key: &str) -> Option<(&str, &str)> {
+    match key.split('/').collect::<Vec<_>>().as_slice() {
+        ["skills", name, version, file] if *file == format!("{}.skill", name) => {
+            Some((name, version))
+        }
+        _ => None,
+    }
+}
+
+/// Keys that belong to an indexed version besides its package.
+fn companion_keys(name: &str, version: &str) -> [String; 2] {
+    [
+        format!("skills/{}/{}/CHANGELOG.md", name, version),
+        format!("source/{}/{}/{}-source.zip", name, version, name),
+    ]
+}
+
+/// All objects under `skills/` and `source/`.
+fn list_repository_objects<S: StorageOperations>(client: &S) -> Result<BTreeSet<String>> {
+    let mut keys = BTreeSet::new();
+    for prefix in ["skills/", "source/"] {
+        keys.extend(
+            client
+                .list_objects(prefix)
+                .with_context(|| format!("Failed to list objects under {}", prefix))?,
+        );
+    }
+    Ok(keys)
+}
+
+/// Read the stored index, or `None` if there isn't one.
+fn read_index<S: StorageOperations>(client: &S) -> Result<Option<Result<SkillsIndex>>> {
+    if client.head_object(INDEX_KEY)?.is_none() {
+        return Ok(None);
+    }
+    let data = client.get_object(INDEX_KEY)?;
+    Ok(Some(
+        serde_json::from_slice(&data).context("Failed to parse skills index"),
+    ))
+}
+
+/// Check that the index and the stored objects agree.
+///
+/// Every indexed package is read to verify its checksum (where the index
+/// records one) and that it is a valid .skill archive.
+pub fn check<S: StorageOperations>(client: &S) -> Result<FsckReport> {
+    let objects = list_repository_objects(client)?;
+    let mut report = FsckReport::default();
+
+    let index = match read_index(client)? {
+        Some(Ok(index)) => index,
+        Some(Err(e)) => {
+            report
+                .issues
+                .push(Issue::UnreadableIndex(format!("{:#}", e)));
+            SkillsIndex::new()
+        }
+        None => {
+            if objects.iter().any(|k| parse_package_key(k).is_some()) {
+                report.issues.push(Issue::MissingIndex);
+            }
+            SkillsIndex::new()
+        }
+    };
+
+    let mut accounted = BTreeSet::new();
+    for entry in &index.skills {
+        let mut versions: Vec<_> = entry.versions.iter().collect();
+        versions.sort();
+        for (version, key) in versions {
+            accounted.insert(key.clone());
+            accounted.extend(companion_keys(&entry.name, version));
+            if !objects.contains(key) && !client.object_exists(key)? {
+                report.issues.push(Issue::MissingObject {
+                    skill: entry.name.clone(),
+                    version: version.clone(),
+                    key: key.clone(),
+                });
+                continue;
+            }
+
+            let data = client.get_object(key)?;
+            report.packages_checked += 1;
+            if let Some(expected) = entry.checksums.get(version) {
+                let actual = content_etag(&data);
+                if *expected != actual {
+                    report.issues.push(Issue::ChecksumMismatch {
+                        key: key.clone(),
+                        expected: expected.clone(),
+                        actual,
+                    });
+                }
+            }
+            if let Err(e) = read_skill_frontmatter(&data) {
+                report.issues.push(Issue::UnreadablePackage {
+                    key: key.clone(),
+                    error: format!("{:#}", e),
+                });
+            }
+        }
+    }
+
+    // Without a usable index every object would be an orphan; the index issue says it all
+    let index_usable = !matches!(
+        report.issues.first(),
+        Some(Issue::MissingIndex | Issue::UnreadableIndex(_))
+    );
+    for key in objects.difference(&accounted).filter(|_| index_usable) {
+        report.issues.push(Issue::OrphanObject { key: key.clone() });
+    }
+    Ok(report)
+}
+
+/// Result of rebuilding an index.
+#[derive(Debug)]
+pub struct Rebuild {
+    pub index: SkillsIndex,
+    /// Packages left out of the index, and why.
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Build an index from the packages in the repository.
+///
+/// Names, descriptions, tags, authors and homepages come from each package's
+/// SKILL.md; other fields, such as `llms_txt_url`, are kept from `previous`
+/// when it has the skill.
+/// Checksums are recomputed, except that a checksum in `previous` that no
+/// longer matches is kept, so `fsck` goes on reporting the damaged package.
+pub fn rebuild_index<S: StorageOperations>(client: &S, previous: &SkillsIndex) -> Result<Rebuild> {
+    let mut index = SkillsIndex::new();
+    let mut skipped = Vec::new();
+    let mut descriptions: HashMap<String, String> = HashMap::new();
+
+    for key in client.list_objects("skills/")? {
+        let Some((name, version)) = parse_package_key(&key) else {
+            continue;
+        };
+        let data = client.get_object(&key)?;
+        let frontmatter = match read_skill_frontmatter(&data) {
+            Ok(f) => f,
+            Err(e) => {
+                skipped.push((key.clone(), format!("{:#}", e)));
+                continue;
+            }
+        };
+        if frontmatter.name.as_deref() != Some(name) {
+            skipped.push((
+                key.clone(),
+                format!(
+                    "SKILL.md names the skill '{}'",
+                    frontmatter.name.as_deref().unwrap_or_default()
+                ),
+            ));
+            continue;
+        }
+
+        let old = previous.find_skill(name);
+        if index.find_skill(name).is_none() {
+            let mut entry = old.cloned().unwrap_or_else(|| IndexEntry {
+                name: name.to_string(),
+                ..Default::default()
+            });
+            entry.versions.clear();
+            entry.checksums.clear();
+            entry.published.clear();
+            entry.yanked.clear();
+            index.skills.push(entry);
+        }
+        // The newest package's description wins
+        let latest = descriptions
+            .get(name)
+            .is_none_or(|v| compare_semver(version, v).is_ge());
+        let entry = index.find_skill_mut(name).expect("entry was just added");
+        if latest {
+            entry.tags = frontmatter.tags;
+            entry.author = frontmatter.author;
+            entry.homepage = frontmatter.homepage;
+            if let Some(description) = frontmatter.description {
+                entry.description = description;
+            }
+            descriptions.insert(name.to_string(), version.to_string());
+        }
+
+        let actual = content_etag(&data);
+        let recorded = old.and_then(|e| e.checksums.get(version));
+        entry.versions.insert(version.to_string(), key.clone());
+        entry
+            .checksums
+            .insert(version.to_string(), recorded.cloned().unwrap_or(actual));
+        if let Some(published) = old.and_then(|e| e.published.get(version)) {
+            entry
+                .published
+                .insert(version.to_string(), published.clone());
+        }
+        if let Some(reason) = old.and_then(|e| e.yanked.get(version)) {
+            entry.yanked.insert(version.to_string(), reason.clone());
+        }
+    }
+
+    index.skills.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Rebuild { index, skipped })
+}
+
+/// Rebuild the index from the repository's packages and save it.
+///
+/// The rebuild runs inside the index update, so a package published while it
+/// runs either makes the update retry or is indexed by its own publish.
+pub fn reindex<S: StorageOperations>(client: &S) -> Result<Rebuild> {
+    let mut skipped = Vec::new();
+    let index = update_index(client, |index| {
+        let rebuild = rebuild_index(client, index)?;
+        *index = rebuild.index;
+        skipped = rebuild.skipped;
+        Ok(())
+    })?;
+    Ok(Rebuild { index, skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_package_key() {
+        assert_eq!(
+            parse_package_key("skills/foo/1.0.0/foo.skill"),
+            Some(("foo", "1.0.0"))
+        );
+        assert_eq!(parse_package_key("skills/foo/1.0.0/CHANGELOG.md"), None);
+        assert_eq!(parse_package_key("skills/foo/1.0.0/bar.skill"), None);
+        assert_eq!(parse_package_key("source/foo/1.0.0/foo-source.zip"), None);
+    }
+
+    #[test]
+    fn test_issue_display() {
+        let issue = Issue::MissingObject {
+            skill: "foo".to_string(),
+            version: "1.0.0".to_string(),
+            key: "skills/foo/1.0.0/foo.skill".to_string(),
+        };
+        assert_eq!(
+            issue.to_string(),
+            "foo v1.0.0 points at missing object skills/foo/1.0.0/foo.skill"
+        );
+    }
+}
@@ -0,0 +1,337 @@
+//! Git implementation of StorageOperations.
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::process::Command;
+
+use crate::index::INDEX_KEY;
+use crate::local_storage::LocalStorageClient;
+use crate::storage::{ObjectMetadata, PreconditionFailed, PutCondition, StorageOperations};
+
+/// Default branch used when a git repository config doesn't name one.
+pub const DEFAULT_BRANCH: &str = "main";
+
+/// Storage client that keeps objects as files on a branch of a git repository.
+///
+/// Objects are read from a local clone, which is synced with the remote when the
+/// client is created and before each write. Every `put_object` and
+/// `delete_object` becomes a commit that is pushed straight away.
+pub struct GitStorageClient {
+    remote: String,
+    branch: String,
+    files: LocalStorageClient,
+}
+
+impl GitStorageClient {
+    /// Open `remote` (any URL or path `git clone` accepts) using a clone in `workdir`.
+    pub fn new(remote: &str, branch: &str, workdir: &Path) -> Result<Self> {
+        if !is_local_remote(remote) {
+            crate::offline::ensure_online(format!("git repository {}", remote))?;
+        }
+        let client = Self {
+            remote: remote.to_string(),
+            branch: branch.to_string(),
+            files: LocalStorageClient::with_dir(workdir),
+        };
+        client.sync()?;
+        Ok(client)
+    }
+
+    /// Open a repository configured as `git+<url>`, cloning it under
+    /// `$HOME/.skill-builder/git/`.
+    pub fn from_url(url: &str, branch: Option<&str>) -> Result<Self> {
+        let remote = url
+            .strip_prefix("git+")
+            .with_context(|| format!("Git repository URL must start with 'git+': {}", url))?;
+        let workdir = crate::config::global_config_dir()
+            .join("git")
+            .join(workdir_name(remote));
+        Self::new(remote, branch.unwrap_or(DEFAULT_BRANCH), &workdir)
+    }
+
+    fn workdir(&self) -> &Path {
+        self.files.root()
+    }
+
+    /// Run git in the working copy, returning stdout.
+    fn git(&self, args: &[&str]) -> Result<String> {
+        run_git(Some(self.workdir()), args)
+    }
+
+    /// Bring the working copy up to date with the remote branch, cloning it first
+    /// if needed. An empty remote or a missing branch leaves an unborn branch
+    /// that the first commit creates.
+    fn sync(&self) -> Result<()> {
+        if !self.workdir().join(".git").exists() {
+            std::fs::create_dir_all(self.workdir()).with_context(|| {
+                format!("Failed to create directory: {}", self.workdir().display())
+            })?;
+            self.git(&["init", "--quiet"])?;
+            self.git(&["remote", "add", "origin", &self.remote])?;
+        }
+
+        self.git(&["fetch", "--quiet", "origin"])?;
+        let remote_ref = format!("origin/{}", self.branch);
+        if self
+            .git(&["rev-parse", "--verify", "--quiet", &remote_ref])
+            .is_ok()
+        {
+            self.git(&["checkout", "--quiet", "-B", &self.branch, &remote_ref])?;
+            self.git(&["reset", "--quiet", "--hard", &remote_ref])?;
+        } else if self
+            .git(&["rev-parse", "--verify", "--quiet", "HEAD"])
+            .is_err()
+        {
+            let head = format!("refs/heads/{}", self.branch);
+            self.git(&["symbolic-ref", "HEAD", &head])?;
+        }
+        Ok(())
+    }
+
+    /// Commit staged changes and push them.
+    ///
+    /// If the remote moved in the meantime, an unconditional write is rebased
+    /// onto it once. A conditional write of `conditional_key` is never merged:
+    /// the commit is dropped and [`PreconditionFailed`] returned, so the caller
+    /// can read the new state and try again.
+    fn commit_and_push(&self, message: &str, conditional_key: Option<&str>) -> Result<()> {
+        if self.git(&["diff", "--cached", "--quiet"]).is_ok() {
+            return Ok(());
+        }
+
+        let remote_ref = format!("origin/{}", self.branch);
+        let base = self.remote_head(&remote_ref);
+
+        let mut args = identity_args(self.workdir());
+        args.extend(["commit", "--quiet", "-m", message].map(String::from));
+        let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        self.git(&args)?;
+
+        let refspec = format!("HEAD:refs/heads/{}", self.branch);
+        let Err(push_err) = self.git(&["push", "--quiet", "origin", &refspec]) else {
+            return Ok(());
+        };
+
+        let Some(key) = conditional_key else {
+            if let Err(e) = self.git(&["pull", "--quiet", "--rebase", "origin", &self.branch]) {
+                self.discard_local_changes()?;
+                return Err(e.context("Failed to rebase onto the git repository"));
+            }
+            return self
+                .git(&["push", "--quiet", "origin", &refspec])
+                .map(|_| ())
+                .context("Failed to push to git repository");
+        };
+
+        self.discard_local_changes()?;
+        if self.remote_head(&remote_ref) == base {
+            // Nobody else pushed, so the push failed for some other reason
+            return Err(push_err.context("Failed to push to git repository"));
+        }
+        Err(PreconditionFailed {
+            key: key.to_string(),
+        }
+        .into())
+    }
+
+    /// The commit a remote-tracking ref points at, if the remote branch exists.
+    fn remote_head(&self, remote_ref: &str) -> Option<String> {
+        self.git(&["rev-parse", "--verify", "--quiet", remote_ref])
+            .ok()
+            .map(|s| s.trim().to_string())
+    }
+
+    /// Abort any rebase in progress and reset the working copy to the remote.
+    fn discard_local_changes(&self) -> Result<()> {
+        let git_dir = self.workdir().join(".git");
+        if git_dir.join("rebase-merge").exists() || git_dir.join("rebase-apply").exists() {
+            self.git(&["rebase", "--abort"])?;
+        }
+        self.sync()
+    }
+}
+
+impl StorageOperations for GitStorageClient {
+    fn put_object(&self, key: &str, data: &[u8]) -> Result<()> {
+        self.sync()?;
+        self.files.put_object(key, data)?;
+        self.git(&["add", "--", key])?;
+        self.commit_and_push(&commit_message("put", key), None)
+    }
+
+    fn get_object(&self, key: &str) -> Result<Vec<u8>> {
+        self.files.get_object(key)
+    }
+
+    fn delete_object(&self, key: &str) -> Result<()> {
+        self.sync()?;
+        if !self.files.object_exists(key)? {
+            return Ok(());
+        }
+        self.git(&["rm", "--quiet", "--", key])?;
+        self.commit_and_push(&commit_message("delete", key), None)
+    }
+
+    fn list_objects(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = self.files.list_objects(prefix)?;
+        keys.retain(|k| !k.starts_with(".git/") && k != ".git");
+        Ok(keys)
+    }
+
+    fn object_exists(&self, key: &str) -> Result<bool> {
+        self.files.object_exists(key)
+    }
+
+    fn head_object(&self, key: &str) -> Result<Option<ObjectMetadata>> {
+        self.files.head_object(key)
+    }
+
+    fn get_object_with_metadata(&self, key: &str) -> Result<(Vec<u8>, ObjectMetadata)> {
+        self.files.get_object_with_metadata(key)
+    }
+
+    /// Checks the condition against the freshly synced branch. A push that
+    /// still races another writer is rejected, and the working copy is reset
+    /// to the remote rather than merging the two versions of the object.
+    fn put_object_if(
+        &self,
+        key: &str,
+        data: &[u8],
+        condition: &PutCondition,
+    ) -> Result<ObjectMetadata> {
+        self.sync()?;
+        let metadata = self.files.put_object_if(key, data, condition)?;
+        self.git(&["add", "--", key])?;
+        self.commit_and_push(&commit_message("put", key), Some(key))?;
+        Ok(metadata)
+    }
+}
+
+/// Whether a remote is on this machine: a `file://` URL or a plain path.
+pub fn is_local_remote(remote: &str) -> bool {
+    remote.starts_with("file://") || (!remote.contains("://") && !remote.contains(':'))
+}
+
+/// Run git in `dir` (or the current directory), returning stdout.
+pub fn run_git(dir: Option<&Path>, args: &[&str]) -> Result<String> {
+    let mut command = Command::new("git");
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    let out = command
+        .args(args)
+        .output()
+        .context("Failed to run git. Is it installed and on PATH?")?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).to_string())
+}
+
+/// Fallback commit identity for machines without a configured git user.
+fn identity_args(workdir: &Path) -> Vec<String> {
+    let configured = |key: &str| {
+        run_git(Some(workdir), &["config", key])
+            .map(|v| !v.trim().is_empty())
+            .unwrap_or(false)
+    };
+    let mut args = Vec::new();
+    if !configured("user.name") {
+        args.extend(["-c".to_string(), "user.name=skill-builder".to_string()]);
+    }
+    if !configured("user.email") {
+        args.extend([
+            "-c".to_string(),
+            "user.email=skill-builder@localhost".to_string(),
+        ]);
+    }
+    args
+}
+
+/// Describe a change to `key` the way a person would, e.g. "publish foo 1.2.0".
+fn commit_message(action: &str, key: &str) -> String {
+    let parts: Vec<&str> = key.split('/').collect();
+    match (action, parts.as_slice()) {
+        (_, [index]) if *index == INDEX_KEY => "update skills index".to_string(),
+        ("put", ["skills", name, version, file]) if file.ends_with(".skill") => {
+            format!("publish {} {}", name, version)
+        }
+        ("delete", ["skills", name, version, file]) if file.ends_with(".skill") => {
+            format!("delete {} {}", name, version)
+        }
+        ("put", ["skills", name, version, "CHANGELOG.md"]) => {
+            format!("add changelog for {} {}", name, version)
+        }
+        ("delete", ["skills", name, version, "CHANGELOG.md"]) => {
+            format!("remove changelog for {} {}", name, version)
+        }
+        ("put", ["source", name, version, _]) => {
+            format!("add source archive for {} {}", name, version)
+        }
+        ("delete", ["source", name, version, _]) => {
+            format!("remove source archive for {} {}", name, version)
+        }
+        ("put", _) => format!("update {}", key),
+        _ => format!("delete {}", key),
+    }
+}
+
+/// Directory name for a remote's clone, readable and unique per URL: the
+/// sanitized URL loses punctuation, so a short hash of the URL tells apart
+/// remotes like `a/b` and `a-b`.
+pub fn workdir_name(remote: &str) -> String {
+    let readable = remote
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>();
+    let hash = hex::encode(Sha256::digest(remote.as_bytes()));
+    format!("{}-{}", readable.trim_matches('-'), &hash[..8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_messages() {
+        assert_eq!(
+            commit_message("put", "skills/foo/1.2.0/foo.skill"),
+            "publish foo 1.2.0"
+        );
+        assert_eq!(
+            commit_message("delete", "skills/foo/1.2.0/foo.skill"),
+            "delete foo 1.2.0"
+        );
+        assert_eq!(
+            commit_message("put", "skills/foo/1.2.0/CHANGELOG.md"),
+            "add changelog for foo 1.2.0"
+        );
+        assert_eq!(
+            commit_message("put", "source/foo/1.2.0/foo-source.zip"),
+            "add source archive for foo 1.2.0"
+        );
+        assert_eq!(
+            commit_message("put", "skills_index.json"),
+            "update skills index"
+        );
+        assert_eq!(commit_message("put", "other/file"), "update other/file");
+    }
+
+    #[test]
+    fn test_workdir_name() {
+        let name = workdir_name("https://github.com/acme/skills.git");
+        assert!(name.starts_with("https---github-com-acme-skills-git-"));
+        assert_eq!(name.len(), "https---github-com-acme-skills-git-".len() + 8);
+        assert_ne!(workdir_name("/srv/a/b"), workdir_name("/srv/a-b"));
+    }
+
+    #[test]
+    fn test_from_url_requires_git_prefix() {
+        assert!(GitStorageClient::from_url("https://example.com/repo.git", None).is_err());
+    }
+}
@@ -0,0 +1,677 @@
+//! GitHub REST API client for GitHub Releases: publishing skills, and reading
+//! them back as a read-only repository.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cell::OnceCell;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::index::{IndexEntry, SkillsIndex, INDEX_KEY};
+use crate::output::Output;
+use crate::storage::{content_type_for_key, ObjectMetadata, StorageOperations};
+
+/// Environment variables read for a GitHub token, in order.
+pub const TOKEN_ENVS: &[&str] = &["GITHUB_TOKEN", "GH_TOKEN"];
+
+/// Releases read per page, the most the API allows.
+const PER_PAGE: usize = 100;
+
+/// Pages of releases read at most, so a huge repository can't stall `sb`.
+const MAX_PAGES: usize = 10;
+
+/// Longest `Retry-After` that is waited out rather than reported.
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(10);
+
+/// Signature file extensions uploaded next to a package, e.g. `foo.skill.sig`.
+pub const SIGNATURE_EXTENSIONS: &[&str] = &["sig", "asc", "minisig"];
+
+/// The GitHub token from the environment, if one is set.
+pub fn github_token() -> Option<String> {
+    TOKEN_ENVS
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|t| !t.is_empty()))
+}
+
+/// A GitHub release, as returned by the releases API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    #[serde(default)]
+    pub id: u64,
+    pub tag_name: String,
+    #[serde(default)]
+    pub html_url: String,
+    /// Release notes.
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Drafts are only visible to collaborators and have no download URLs.
+    #[serde(default)]
+    pub draft: bool,
+    /// When the release was published, in RFC 3339.
+    #[serde(default)]
+    pub published_at: Option<String>,
+    /// Hypermedia URL assets are uploaded to, e.g. `.../assets{?name,label}`.
+    #[serde(default)]
+    pub upload_url: String,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+/// A file attached to a GitHub release.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseAsset {
+    #[serde(default)]
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub size: u64,
+    /// API URL of the asset, which also serves private repositories' assets.
+    #[serde(default)]
+    pub url: String,
+    pub browser_download_url: String,
+}
+
+impl Release {
+    /// The version a release is for: its tag without a leading `v`.
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+}
+
+/// Fields sent when creating or editing a release.
+#[derive(Debug, Default, Serialize)]
+struct ReleaseFields<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tag_name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_commitish: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<&'a str>,
+}
+
+/// Minimal client for the GitHub releases API.
+pub struct GitHubClient {
+    api_url: String,
+    token: Option<String>,
+    client: Client,
+}
+
+impl GitHubClient {
+    /// Create a client for the API at `api_url`, authenticating with `token`
+    /// if given.
+    pub fn new(api_url: &str, token: Option<String>) -> Result<Self> {
+        crate::offline::ensure_online("GitHub")?;
+        let client = Client::builder()
+            .timeout(Duration::from_secs(120))
+            .user_agent("sb/1.0")
+            .build()
+            .context("Failed to create HTTP client")?;
+        Ok(Self {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            token,
+            client,
+        })
+    }
+
+    /// Create a client for [`crate::install::github_api_url`] with the token
+    /// from [`github_token`].
+    pub fn from_env() -> Result<Self> {
+        Self::new(&crate::install::github_api_url(), github_token())
+    }
+
+    /// Whether requests are authenticated.
+    pub fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    fn request(&self, method: reqwest::Method, url: &str) -> RequestBuilder {
+        self.request_accepting(method, url, "application/vnd.github+json")
+    }
+
+    fn request_accepting(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+        accept: &str,
+    ) -> RequestBuilder {
+        let request = self
+            .client
+            .request(method, url)
+            .header("Accept", accept)
+            .header("X-GitHub-Api-Version", "2022-11-28");
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Send a request, waiting out a short `Retry-After` once. Responses
+    /// other than rate limits are returned as they are.
+    fn execute(&self, request: RequestBuilder, what: &str) -> Result<Response> {
+        let retry = request.try_clone();
+        let response = request
+            .send()
+            .with_context(|| format!("Failed to reach GitHub {}", what))?;
+        let wait = retry_after(&response).filter(|wait| *wait <= MAX_RETRY_WAIT);
+        match (wait, retry) {
+            (Some(wait), Some(retry)) => {
+                std::thread::sleep(wait);
+                retry
+                    .send()
+                    .with_context(|| format!("Failed to reach GitHub {}", what))
+            }
+            _ => Ok(response),
+        }
+    }
+
+    fn send(&self, request: RequestBuilder, what: &str) -> Result<Response> {
+        let response = self.execute(request, what)?;
+        if response.status().is_success() {
+            return Ok(response);
+        }
+        Err(api_error(response, what, self.has_token()))
+    }
+
+    fn send_json<T: DeserializeOwned>(&self, request: RequestBuilder, what: &str) -> Result<T> {
+        let body = self
+            .send(request, what)?
+            .text()
+            .context("Failed to read response body")?;
+        serde_json::from_str(&body).with_context(|| format!("Invalid GitHub response {}", what))
+    }
+
+    /// The release for `tag`, or `None` if there isn't one.
+    pub fn release_by_tag(&self, repo: &str, tag: &str) -> Result<Option<Release>> {
+        let url = format!("{}/repos/{}/releases/tags/{}", self.api_url, repo, tag);
+        let what = format!("looking up release {} of {}", tag, repo);
+        let response = self.execute(self.request(reqwest::Method::GET, &url), &what)?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(response, &what, self.has_token()));
+        }
+        let body = response.text().context("Failed to read response body")?;
+        serde_json::from_str(&body)
+            .map(Some)
+            .with_context(|| format!("Invalid GitHub response {}", what))
+    }
+
+    /// Published releases of `repo`, newest first.
+    pub fn list_releases(&self, repo: &str) -> Result<Vec<Release>> {
+        let what = format!("listing releases of {}", repo);
+        let mut releases = Vec::new();
+        for page in 1..=MAX_PAGES {
+            let url = format!(
+                "{}/repos/{}/releases?per_page={}&page={}",
+                self.api_url, repo, PER_PAGE, page
+            );
+            let response = self.execute(self.request(reqwest::Method::GET, &url), &what)?;
+            if response.status() == StatusCode::NOT_FOUND {
+                let hint = if self.has_token() {
+                    ""
+                } else {
+                    "; set GITHUB_TOKEN if it is private"
+                };
+                anyhow::bail!("GitHub repository {} not found{}", repo, hint);
+            }
+            if !response.status().is_success() {
+                return Err(api_error(response, &what, self.has_token()));
+            }
+            let body = response.text().context("Failed to read response body")?;
+            let batch: Vec<Release> = serde_json::from_str(&body)
+                .with_context(|| format!("Invalid GitHub response {}", what))?;
+            let last = batch.len() < PER_PAGE;
+            releases.extend(batch.into_iter().filter(|r| !r.draft));
+            if last {
+                break;
+            }
+        }
+        Ok(releases)
+    }
+
+    /// Download a release asset. Uses the asset's API URL when authenticated,
+    /// so assets of private repositories can be read too.
+    pub fn download_asset(&self, asset: &ReleaseAsset) -> Result<Vec<u8>> {
+        let request = if self.has_token() && !asset.url.is_empty() {
+            self.request_accepting(reqwest::Method::GET, &asset.url, "application/octet-stream")
+        } else {
+            self.client.get(&asset.browser_download_url)
+        };
+        let response = self.send(request, &format!("downloading {}", asset.name))?;
+        Ok(response
+            .bytes()
+            .with_context(|| format!("Failed to read {}", asset.name))?
+            .to_vec())
+    }
+
+    fn create_release(&self, repo: &str, fields: &ReleaseFields) -> Result<Release> {
+        let url = format!("{}/repos/{}/releases", self.api_url, repo);
+        let request = self
+            .request(reqwest::Method::POST, &url)
+            .header("Content-Type", "application/json")
+            .body(serde_json::to_vec(fields)?);
+        self.send_json(request, &format!("creating a release of {}", repo))
+    }
+
+    fn update_release(&self, repo: &str, id: u64, fields: &ReleaseFields) -> Result<Release> {
+        let url = format!("{}/repos/{}/releases/{}", self.api_url, repo, id);
+        let request = self
+            .request(reqwest::Method::PATCH, &url)
+            .header("Content-Type", "application/json")
+            .body(serde_json::to_vec(fields)?);
+        self.send_json(request, &format!("updating a release of {}", repo))
+    }
+
+    fn delete_asset(&self, repo: &str, asset: &ReleaseAsset) -> Result<()> {
+        let url = format!(
+            "{}/repos/{}/releases/assets/{}",
+            self.api_url, repo, asset.id
+        );
+        self.send(
+            self.request(reqwest::Method::DELETE, &url),
+            &format!("deleting {}", asset.name),
+        )?;
+        Ok(())
+    }
+
+    fn upload_asset(&self, release: &Release, asset: &Asset) -> Result<ReleaseAsset> {
+        // Drop the `{?name,label}` URI template suffix
+        let base = release.upload_url.split('{').next().unwrap_or_default();
+        let mut url = url::Url::parse(base)
+            .with_context(|| format!("Invalid upload URL: {}", release.upload_url))?;
+        url.query_pairs_mut().append_pair("name", &asset.name);
+        let request = self
+            .request(reqwest::Method::POST, url.as_str())
+            .header("Content-Type", asset.content_type)
+            .body(asset.data.clone());
+        self.send_json(request, &format!("uploading {}", asset.name))
+    }
+}
+
+/// How long GitHub asks to wait before retrying: `Retry-After` on a 403 or
+/// 429 response.
+fn retry_after(response: &Response) -> Option<Duration> {
+    if !matches!(
+        response.status(),
+        StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+    ) {
+        return None;
+    }
+    let seconds = response.headers().get("retry-after")?.to_str().ok()?;
+    seconds.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// When the primary rate limit resets, if the response says it is used up.
+fn rate_limit_reset(response: &Response) -> Option<DateTime<Utc>> {
+    let header = |name: &str| response.headers().get(name)?.to_str().ok();
+    if header("x-ratelimit-remaining")? != "0" {
+        return None;
+    }
+    let reset = header("x-ratelimit-reset")?.parse().ok()?;
+    DateTime::from_timestamp(reset, 0)
+}
+
+/// Error for an unsuccessful API response, with GitHub's message if it sent one.
+fn api_error(response: Response, what: &str, authenticated: bool) -> anyhow::Error {
+    #[derive(Deserialize)]
+    struct ApiMessage {
+        message: String,
+    }
+
+    let status = response.status();
+    let limited = matches!(
+        status,
+        StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+    );
+    if limited {
+        let hint = if authenticated {
+            ""
+        } else {
+            "; set GITHUB_TOKEN for a higher limit"
+        };
+        if let Some(reset) = rate_limit_reset(&response) {
+            return anyhow::anyhow!(
+                "GitHub API rate limit exceeded {}; it resets at {}{}",
+                what,
+                reset.format("%H:%M:%S UTC"),
+                hint
+            );
+        }
+        if let Some(wait) = retry_after(&response) {
+            return anyhow::anyhow!(
+                "GitHub API rate limit exceeded {}; retry in {}s{}",
+                what,
+                wait.as_secs(),
+                hint
+            );
+        }
+    }
+    let message = response
+        .text()
+        .ok()
+        .and_then(|body| serde_json::from_str::<ApiMessage>(&body).ok())
+        .map(|m| format!(": {}", m.message))
+        .unwrap_or_default();
+    anyhow::anyhow!("HTTP {} {}{}", status, what, message)
+}
+
+/// A file to attach to a release.
+struct Asset {
+    name: String,
+    content_type: &'static str,
+    data: Vec<u8>,
+}
+
+/// What to publish with [`publish_release`].
+pub struct PublishParams<'a> {
+    /// Repository as `owner/repo`.
+    pub repo: &'a str,
+    pub skill: &'a str,
+    pub version: &'a str,
+    pub skill_file: &'a Path,
+    /// Detached signature of the package, uploaded as `<skill>.skill.<ext>`.
+    pub signature: Option<&'a Path>,
+    /// Release notes; replace the notes of an existing release.
+    pub notes: Option<&'a str>,
+    /// Commit or branch to tag when the tag doesn't exist yet.
+    pub target: Option<&'a str>,
+    /// Replace the skill's assets if the release already has them.
+    pub force: bool,
+}
+
+/// A signature file next to `skill_file`, e.g. `foo.skill.sig`.
+pub fn find_signature(skill_file: &Path) -> Option<PathBuf> {
+    SIGNATURE_EXTENSIONS
+        .iter()
+        .map(|ext| PathBuf::from(format!("{}.{}", skill_file.display(), ext)))
+        .find(|path| path.is_file())
+}
+
+/// Create or update the `v<version>` release of `params.repo` and upload the
+/// package, a `<skill>.skill.sha256` checksum file and the signature.
+///
+/// Several skills can share a release; only this skill's assets are touched.
+pub fn publish_release(
+    client: &GitHubClient,
+    params: &PublishParams,
+    output: &Output,
+) -> Result<Release> {
+    if !client.has_token() {
+        anyhow::bail!(
+            "Publishing to GitHub needs a token: set {}",
+            TOKEN_ENVS.join(" or ")
+        );
+    }
+
+    let package_name = format!("{}.skill", params.skill);
+    let data = std::fs::read(params.skill_file)
+        .with_context(|| format!("Failed to read {}", params.skill_file.display()))?;
+    let checksum = format!("{}  {}\n", hex::encode(Sha256::digest(&data)), package_name);
+    let mut assets = vec![
+        Asset {
+            name: package_name.clone(),
+            content_type: "application/zip",
+            data,
+        },
+        Asset {
+            name: format!("{}.sha256", package_name),
+            content_type: "text/plain",
+            data: checksum.into_bytes(),
+        },
+    ];
+    if let Some(signature) = params.signature {
+        let ext = signature
+            .extension()
+            .and_then(|e| e.to_str())
+            .filter(|e| SIGNATURE_EXTENSIONS.contains(e))
+            .unwrap_or("sig");
+        assets.push(Asset {
+            name: format!("{}.{}", package_name, ext),
+            content_type: "application/octet-stream",
+            data: std::fs::read(signature)
+                .with_context(|| format!("Failed to read {}", signature.display()))?,
+        });
+    }
+
+    let tag = format!("v{}", params.version);
+    let release = match client.release_by_tag(params.repo, &tag)? {
+        Some(release) => {
+            if !params.force && release.assets.iter().any(|a| a.name == package_name) {
+                anyhow::bail!(
+                    "Release {} of {} already has {}; use --force to replace it",
+                    tag,
+                    params.repo,
+                    package_name
+                );
+            }
+            output.step(&format!("Updating release {}", tag));
+            match params.notes {
+                Some(notes) => client.update_release(
+                    params.repo,
+                    release.id,
+                    &ReleaseFields {
+                        body: Some(notes),
+                        ..Default::default()
+                    },
+                )?,
+                None => release,
+            }
+        }
+        None => {
+            let name = format!("{} {}", params.skill, tag);
+            let release = client.create_release(
+                params.repo,
+                &ReleaseFields {
+                    tag_name: Some(&tag),
+                    target_commitish: params.target,
+                    name: Some(&name),
+                    body: params.notes,
+                },
+            )?;
+            output.step(&format!("Created release {}", tag));
+            release
+        }
+    };
+
+    for asset in &assets {
+        for existing in release.assets.iter().filter(|a| a.name == asset.name) {
+            client.delete_asset(params.repo, existing)?;
+        }
+        let pb = output.spinner(&format!("Uploading {}", asset.name));
+        let uploaded = client.upload_asset(&release, asset);
+        pb.finish_and_clear();
+        output.step(&format!("Uploaded: {}", uploaded?.name));
+    }
+    Ok(release)
+}
+
+/// Read-only storage over the GitHub releases of a repository.
+///
+/// Every `.skill` asset becomes a version of the skill it is named after,
+/// stored under the usual `skills/<name>/<version>/<name>.skill` key, and a
+/// release's notes are served as that version's `CHANGELOG.md`. Releases are
+/// listed once, on first use.
+pub struct GitHubReleaseStorage {
+    client: GitHubClient,
+    repo: String,
+    releases: OnceCell<Vec<Release>>,
+}
+
+impl GitHubReleaseStorage {
+    /// Storage for the releases of `repo` (`owner/repo`).
+    pub fn new(client: GitHubClient, repo: &str) -> Self {
+        Self {
+            client,
+            repo: repo.to_string(),
+            releases: OnceCell::new(),
+        }
+    }
+
+    /// Repository as `owner/repo`.
+    pub fn repo(&self) -> &str {
+        &self.repo
+    }
+
+    fn releases(&self) -> Result<&[Release]> {
+        if let Some(releases) = self.releases.get() {
+            return Ok(releases);
+        }
+        let releases = self.client.list_releases(&self.repo)?;
+        Ok(self.releases.get_or_init(|| releases))
+    }
+
+    /// The index of every skill on the releases.
+    pub fn index(&self) -> Result<SkillsIndex> {
+        let mut index = SkillsIndex::new();
+        for release in self.releases()? {
+            let version = release.version();
+            for asset in &release.assets {
+                let Some(name) = asset.name.strip_suffix(".skill") else {
+                    continue;
+                };
+                if index.find_skill(name).is_none() {
+                    index.skills.push(IndexEntry {
+                        name: name.to_string(),
+                        ..Default::default()
+                    });
+                }
+                let entry = index.find_skill_mut(name).expect("entry was just added");
+                entry.versions.insert(
+                    version.to_string(),
+                    format!("skills/{}/{}/{}.skill", name, version, name),
+                );
+                if let Some(published) = &release.published_at {
+                    entry
+                        .published
+                        .insert(version.to_string(), published.clone());
+                }
+            }
+        }
+        index.skills.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(index)
+    }
+
+    /// What `key` refers to: a package asset or a version's release notes.
+    fn locate(&self, key: &str) -> Result<Option<ReleaseObject<'_>>> {
+        let Some([name, version, file]) = key
+            .strip_prefix("skills/")
+            .map(|rest| rest.splitn(3, '/').collect::<Vec<_>>())
+            .and_then(|parts| <[&str; 3]>::try_from(parts).ok())
+        else {
+            return Ok(None);
+        };
+        let Some(release) = self.releases()?.iter().find(|r| {
+            r.version() == version && r.assets.iter().any(|a| a.name == format!("{}.skill", name))
+        }) else {
+            return Ok(None);
+        };
+        Ok(match file {
+            "CHANGELOG.md" => release
+                .body
+                .as_deref()
+                .filter(|body| !body.trim().is_empty())
+                .map(|body| ReleaseObject::Notes(release, body)),
+            _ => release
+                .assets
+                .iter()
+                .find(|a| a.name == file)
+                .map(|asset| ReleaseObject::Asset(release, asset)),
+        })
+    }
+
+    fn read_only(&self) -> anyhow::Error {
+        anyhow::anyhow!(
+            "GitHub releases of {} are read-only; use `sb github publish`",
+            self.repo
+        )
+    }
+}
+
+/// An object of [`GitHubReleaseStorage`].
+enum ReleaseObject<'a> {
+    Asset(&'a Release, &'a ReleaseAsset),
+    Notes(&'a Release, &'a str),
+}
+
+impl StorageOperations for GitHubReleaseStorage {
+    fn put_object(&self, _key: &str, _data: &[u8]) -> Result<()> {
+        Err(self.read_only())
+    }
+
+    fn get_object(&self, key: &str) -> Result<Vec<u8>> {
+        if key == INDEX_KEY {
+            return Ok(serde_json::to_vec_pretty(&self.index()?)?);
+        }
+        match self.locate(key)? {
+            Some(ReleaseObject::Asset(_, asset)) => self.client.download_asset(asset),
+            Some(ReleaseObject::Notes(_, body)) => Ok(body.as_bytes().to_vec()),
+            None => anyhow::bail!("Object not found: {}", key),
+        }
+    }
+
+    fn delete_object(&self, _key: &str) -> Result<()> {
+        Err(self.read_only())
+    }
+
+    fn list_objects(&self, prefix: &str) -> Result<Vec<String>> {
+        let index = self.index()?;
+        let mut keys: Vec<String> = std::iter::once(INDEX_KEY.to_string())
+            .chain(
+                index
+                    .skills
+                    .iter()
+                    .flat_map(|entry| entry.versions.values().cloned()),
+            )
+            .filter(|key| key.starts_with(prefix))
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn object_exists(&self, key: &str) -> Result<bool> {
+        Ok(key == INDEX_KEY || self.locate(key)?.is_some())
+    }
+
+    fn head_object(&self, key: &str) -> Result<Option<ObjectMetadata>> {
+        if key == INDEX_KEY {
+            let data = self.get_object(key)?;
+            return Ok(Some(ObjectMetadata::for_data(key, &data)));
+        }
+        let (release, size, etag) = match self.locate(key)? {
+            Some(ReleaseObject::Asset(release, asset)) => {
+                (release, asset.size, format!("asset-{}", asset.id))
+            }
+            Some(ReleaseObject::Notes(release, body)) => (
+                release,
+                body.len() as u64,
+                crate::storage::content_etag(body.as_bytes()),
+            ),
+            None => return Ok(None),
+        };
+        Ok(Some(ObjectMetadata {
+            size,
+            etag,
+            last_modified: release
+                .published_at
+                .as_deref()
+                .and_then(|p| DateTime::parse_from_rfc3339(p).ok())
+                .map(|p| p.with_timezone(&Utc)),
+            content_type: Some(content_type_for_key(key).to_string()),
+        }))
+    }
+}
+
+/// List the skills published as `.skill` assets on a repository's GitHub
+/// releases, or on [`crate::install::DEFAULT_REPO`]'s.
+pub fn list_release_skills(repo: Option<&str>) -> Result<SkillsIndex> {
+    let repo = repo.unwrap_or(crate::install::DEFAULT_REPO);
+    GitHubReleaseStorage::new(GitHubClient::from_env()?, repo).index()
+}
@@ -0,0 +1,155 @@
+//! Read-only HTTP(S) implementation of StorageOperations.
+
+use anyhow::{Context, Result};
+use reqwest::blocking::Client;
+use reqwest::StatusCode;
+use std::time::Duration;
+
+use crate::index::{SkillsIndex, INDEX_KEY};
+use crate::storage::StorageOperations;
+
+/// Storage client that reads a repository served by any static web host.
+///
+/// Keys map to URLs under the base URL, so `skills/foo/1.0.0/foo.skill` is fetched
+/// from `{base_url}/skills/foo/1.0.0/foo.skill`. Writes are rejected, and listings
+/// are derived from `skills_index.json` because plain HTTP has no directory listing.
+pub struct HttpStorageClient {
+    base_url: String,
+    client: Client,
+}
+
+impl HttpStorageClient {
+    /// Create a client for the repository at `base_url`.
+    pub fn new(base_url: &str) -> Result<Self> {
+        let url = url::Url::parse(base_url)
+            .with_context(|| format!("Invalid repository URL: {}", base_url))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            anyhow::bail!("Repository URL must use http or https: {}", base_url);
+        }
+        crate::offline::ensure_online(format!("HTTP repository {}", base_url))?;
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(60))
+            .user_agent("sb/1.0")
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+        })
+    }
+
+    /// Get the repository base URL.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn key_to_url(&self, key: &str) -> String {
+        format!("{}/{}", self.base_url, key.trim_start_matches('/'))
+    }
+
+    /// Fetch `key`, or `None` if the server answers 404.
+    fn fetch(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let url = self.key_to_url(key);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .with_context(|| format!("Failed to fetch {}", url))?;
+
+        match response.status() {
+            status if status.is_success() => Ok(Some(
+                response
+                    .bytes()
+                    .with_context(|| format!("Failed to read response from {}", url))?
+                    .to_vec(),
+            )),
+            StatusCode::NOT_FOUND => Ok(None),
+            status => anyhow::bail!("HTTP {} fetching {}", status, url),
+        }
+    }
+
+    fn read_only(&self) -> anyhow::Error {
+        anyhow::anyhow!("HTTP repository is read-only: {}", self.base_url)
+    }
+}
+
+impl StorageOperations for HttpStorageClient {
+    fn put_object(&self, _key: &str, _data: &[u8]) -> Result<()> {
+        Err(self.read_only())
+    }
+
+    fn get_object(&self, key: &str) -> Result<Vec<u8>> {
+        self.fetch(key)?
+            .with_context(|| format!("Object not found: {}", key))
+    }
+
+    fn delete_object(&self, _key: &str) -> Result<()> {
+        Err(self.read_only())
+    }
+
+    fn list_objects(&self, prefix: &str) -> Result<Vec<String>> {
+        // No index is an empty repository; a server error is still an error
+        let Some(data) = self.fetch(INDEX_KEY)? else {
+            return Ok(Vec::new());
+        };
+        let index: SkillsIndex =
+            serde_json::from_slice(&data).context("Failed to parse skills index")?;
+
+        let mut keys: Vec<String> = std::iter::once(INDEX_KEY.to_string())
+            .chain(
+                index
+                    .skills
+                    .iter()
+                    .flat_map(|entry| entry.versions.values().cloned()),
+            )
+            .filter(|key| key.starts_with(prefix))
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn object_exists(&self, key: &str) -> Result<bool> {
+        let url = self.key_to_url(key);
+        let response = self
+            .client
+            .head(&url)
+            .send()
+            .with_context(|| format!("Failed to fetch {}", url))?;
+
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            StatusCode::NOT_FOUND => Ok(false),
+            status => anyhow::bail!("HTTP {} checking {}", status, url),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_non_http_urls() {
+        assert!(HttpStorageClient::new("ftp://example.com/skills").is_err());
+        assert!(HttpStorageClient::new("not a url").is_err());
+    }
+
+    #[test]
+    fn test_key_to_url_trims_slashes() {
+        let client = HttpStorageClient::new("https://example.com/skills/").unwrap();
+        assert_eq!(client.base_url(), "https://example.com/skills");
+        assert_eq!(
+            client.key_to_url("/skills_index.json"),
+            "https://example.com/skills/skills_index.json"
+        );
+    }
+
+    #[test]
+    fn test_writes_are_rejected() {
+        let client = HttpStorageClient::new("https://example.com").unwrap();
+        assert!(client.put_object("a", b"data").is_err());
+        assert!(client.delete_object("a").is_err());
+    }
+}
@@ -1,15 +1,41 @@
 //! Skills index management for S3 repository.
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
-use crate::storage::StorageOperations;
+use crate::storage::{update_json_object, StorageOperations};
+use crate::validate::Frontmatter;
+use crate::version_range::VersionRange;
 
-const INDEX_KEY: &str = "skills_index.json";
+/// Storage key of the skills index.
+pub const INDEX_KEY: &str = "skills_index.json";
+
+/// Error for a skill or version a repository doesn't have. Detect it with
+/// [`is_not_found`].
+#[derive(Debug)]
+pub struct NotFound {
+    pub message: String,
+}
+
+impl fmt::Display for NotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for NotFound {}
+
+/// Whether `err` means the skill or version isn't in the repository, as
+/// opposed to the repository failing to answer.
+pub fn is_not_found(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<NotFound>().is_some()
+}
 
 /// A single skill entry in the index.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct IndexEntry {
     /// Skill name.
     pub name: String,
@@ -22,6 +48,118 @@ pub struct IndexEntry {
 
     /// Map of version -> S3 location path.
     pub versions: HashMap<String, String>,
+
+    /// Map of version -> SHA-256 of the .skill package, as hex.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub checksums: HashMap<String, String>,
+
+    /// Map of version -> when it was uploaded, in RFC 3339.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub published: HashMap<String, String>,
+
+    /// Map of version -> reason, for versions withdrawn with `sb repo yank`.
+    ///
+    /// Yanked versions are never picked as the latest or to satisfy a range,
+    /// but can still be installed by exact version.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub yanked: HashMap<String, String>,
+
+    /// Search keywords from the package's SKILL.md.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Author from the package's SKILL.md.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// Homepage from the package's SKILL.md.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+
+    /// Set by `sb repo deprecate`: why the skill shouldn't be used any more,
+    /// possibly empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+
+    /// Name of the skill that supersedes this one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<String>,
+}
+
+impl IndexEntry {
+    /// Warning for a deprecated or replaced skill, or `None` if it is neither.
+    pub fn deprecation_notice(&self) -> Option<String> {
+        let replaced_by = self.replaced_by.as_deref();
+        let mut notice = match (self.deprecated.as_deref(), replaced_by) {
+            (None, None) => return None,
+            (None, Some(replacement)) => {
+                return Some(format!(
+                    "{} has been replaced by {}",
+                    self.name, replacement
+                ))
+            }
+            (Some(""), _) => format!("{} is deprecated", self.name),
+            (Some(message), _) => format!("{} is deprecated: {}", self.name, message),
+        };
+        if let Some(replacement) = replaced_by {
+            notice.push_str(&format!(" (replaced by {})", replacement));
+        }
+        Some(notice)
+    }
+
+    /// Whether a version has been yanked.
+    pub fn is_yanked(&self, version: &str) -> bool {
+        self.yanked.contains_key(version)
+    }
+
+    /// The highest version that hasn't been yanked.
+    pub fn latest_version(&self) -> Option<&str> {
+        self.versions
+            .keys()
+            .filter(|v| !self.is_yanked(v))
+            .max_by(|a, b| compare_semver(a, b))
+            .map(|s| s.as_str())
+    }
+
+    /// Resolve a requested version. A version in the index is pinned, even if
+    /// yanked; otherwise the request is read as a [`VersionRange`] and the
+    /// highest version in it that hasn't been yanked is picked. `None` asks
+    /// for the latest version.
+    pub fn resolve_version(&self, request: Option<&str>) -> Result<&str> {
+        let Some(request) = request else {
+            return self.latest_version().ok_or_else(|| {
+                let message = format!("Skill '{}' has no versions that aren't yanked", self.name);
+                NotFound { message }.into()
+            });
+        };
+        if let Some((version, _)) = self.versions.get_key_value(request) {
+            return Ok(version);
+        }
+        VersionRange::parse(request)
+            .ok()
+            .and_then(|range| {
+                self.versions
+                    .keys()
+                    .filter(|v| !self.is_yanked(v) && range.matches(v))
+                    .max_by(|a, b| compare_semver(a, b))
+            })
+            .map(|s| s.as_str())
+            .ok_or_else(|| {
+                let mut available: Vec<&str> = self
+                    .versions
+                    .keys()
+                    .filter(|v| !self.is_yanked(v))
+                    .map(String::as_str)
+                    .collect();
+                available.sort_by(|a, b| compare_semver(b, a));
+                let mut message =
+                    format!("Version '{}' not found for skill '{}'", request, self.name);
+                if !available.is_empty() {
+                    message.push_str(&format!(" (available: {})", available.join(", ")));
+                }
+                NotFound { message }.into()
+            })
+    }
 }
 
 /// The top-level skills index stored in S3.
@@ -71,11 +209,40 @@ impl SkillsIndex {
                 description: description.to_string(),
                 llms_txt_url: llms_txt_url.to_string(),
                 versions,
+                ..Default::default()
             });
             false
         }
     }
 
+    /// Record the package checksum of an indexed version.
+    pub fn set_checksum(&mut self, name: &str, version: &str, sha256: &str) {
+        if let Some(entry) = self.find_skill_mut(name) {
+            entry
+                .checksums
+                .insert(version.to_string(), sha256.to_string());
+        }
+    }
+
+    /// Record when a version was uploaded.
+    pub fn set_published(&mut self, name: &str, version: &str, at: DateTime<Utc>) {
+        if let Some(entry) = self.find_skill_mut(name) {
+            entry.published.insert(
+                version.to_string(),
+                at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            );
+        }
+    }
+
+    /// Record the tags, author and homepage from a package's SKILL.md.
+    pub fn set_metadata(&mut self, name: &str, frontmatter: &Frontmatter) {
+        if let Some(entry) = self.find_skill_mut(name) {
+            entry.tags = frontmatter.tags.clone();
+            entry.author = frontmatter.author.clone();
+            entry.homepage = frontmatter.homepage.clone();
+        }
+    }
+
     /// Remove a skill entirely. Returns true if it existed.
     pub fn remove_skill(&mut self, name: &str) -> bool {
         let len_before = self.skills.len();
@@ -88,6 +255,9 @@ impl SkillsIndex {
     pub fn remove_version(&mut self, name: &str, version: &str) -> bool {
         if let Some(entry) = self.find_skill_mut(name) {
             let existed = entry.versions.remove(version).is_some();
+            entry.checksums.remove(version);
+            entry.published.remove(version);
+            entry.yanked.remove(version);
             if entry.versions.is_empty() {
                 self.remove_skill(name);
             }
@@ -97,15 +267,11 @@ impl SkillsIndex {
         }
     }
 
-    /// Get the latest version of a skill using semantic version comparison.
+    /// Get the latest version of a skill using semantic version comparison,
+    /// skipping yanked versions.
     pub fn latest_version(&self, name: &str) -> Option<&str> {
-        self.find_skill(name).and_then(|entry| {
-            entry
-                .versions
-                .keys()
-                .max_by(|a, b| compare_semver(a, b))
-                .map(|s| s.as_str())
-        })
+        self.find_skill(name)
+            .and_then(|entry| entry.latest_version())
     }
 }
 
@@ -116,7 +282,7 @@ impl Default for SkillsIndex {
 }
 
 /// Simple semantic version comparison (major.minor.patch).
-fn compare_semver(a: &str, b: &str) -> std::cmp::Ordering {
+pub fn compare_semver(a: &str, b: &str) -> std::cmp::Ordering {
     let parse = |s: &str| -> Vec<u64> {
         s.trim_start_matches('v')
             .split('.')
@@ -145,16 +311,36 @@ pub fn load_index<S: StorageOperations>(client: &S) -> Result<SkillsIndex> {
             let json = String::from_utf8(data).context("Skills index is not valid UTF-8")?;
             serde_json::from_str(&json).context("Failed to parse skills index")
         }
-        Err(_) => Ok(SkillsIndex::new()),
+        // An unreachable repository isn't an empty one
+        Err(e) => match client.head_object(INDEX_KEY) {
+            Ok(None) => Ok(SkillsIndex::new()),
+            _ => Err(e.context("Failed to read skills index")),
+        },
     }
 }
 
 /// Save the skills index to S3.
+///
+/// This overwrites whatever is stored; use [`update_index`] to change an index
+/// other writers may be updating too.
 pub fn save_index<S: StorageOperations>(client: &S, index: &SkillsIndex) -> Result<()> {
     let json = serde_json::to_string_pretty(index).context("Failed to serialize skills index")?;
     client.put_object(INDEX_KEY, json.as_bytes())
 }
 
+/// Read the index, apply `mutate` and write it back only if nobody else wrote
+/// it in the meantime. On a conflict the index is re-read and `mutate` applied
+/// again, so concurrent publishes don't lose each other's entries.
+///
+/// Returns the index as saved.
+pub fn update_index<S, F>(client: &S, mutate: F) -> Result<SkillsIndex>
+where
+    S: StorageOperations,
+    F: FnMut(&mut SkillsIndex) -> Result<()>,
+{
+    update_json_object(client, INDEX_KEY, "skills index", mutate)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,12 +423,63 @@ mod tests {
         assert_eq!(index.latest_version("a"), Some("2.1.0"));
     }
 
+    #[test]
+    fn test_yanked_versions_are_only_resolved_by_pin() {
+        let mut index = SkillsIndex::new();
+        for v in ["1.0.0", "1.1.0", "1.2.0", "2.0.0"] {
+            index.add_or_update_skill("a", "d", "u", v, "p");
+        }
+        let entry = index.find_skill_mut("a").unwrap();
+        entry
+            .yanked
+            .insert("2.0.0".to_string(), "broken".to_string());
+        entry.yanked.insert("1.2.0".to_string(), String::new());
+
+        assert_eq!(index.latest_version("a"), Some("1.1.0"));
+        let entry = index.find_skill("a").unwrap();
+        assert_eq!(entry.resolve_version(None).unwrap(), "1.1.0");
+        assert_eq!(entry.resolve_version(Some("^1.0")).unwrap(), "1.1.0");
+        assert_eq!(entry.resolve_version(Some("2.0.0")).unwrap(), "2.0.0");
+        assert_eq!(entry.resolve_version(Some(">=1.0, <1.2")).unwrap(), "1.1.0");
+        assert!(entry
+            .resolve_version(Some("^2"))
+            .unwrap_err()
+            .to_string()
+            .contains("Version '^2' not found for skill 'a'"));
+        assert!(entry.resolve_version(Some("3.0.0")).is_err());
+    }
+
     #[test]
     fn test_latest_version_nonexistent() {
         let index = SkillsIndex::new();
         assert!(index.latest_version("nope").is_none());
     }
 
+    #[test]
+    fn test_deprecation_notice() {
+        let mut entry = IndexEntry {
+            name: "old-skill".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(entry.deprecation_notice(), None);
+        entry.replaced_by = Some("new-skill".to_string());
+        assert_eq!(
+            entry.deprecation_notice().unwrap(),
+            "old-skill has been replaced by new-skill"
+        );
+        entry.deprecated = Some("no longer maintained".to_string());
+        assert_eq!(
+            entry.deprecation_notice().unwrap(),
+            "old-skill is deprecated: no longer maintained (replaced by new-skill)"
+        );
+        entry.replaced_by = None;
+        entry.deprecated = Some(String::new());
+        assert_eq!(
+            entry.deprecation_notice().unwrap(),
+            "old-skill is deprecated"
+        );
+    }
+
     #[test]
     fn test_compare_semver() {
         assert_eq!(compare_semver("1.0.0", "1.0.0"), std::cmp::Ordering::Equal);
@@ -295,4 +532,52 @@ mod tests {
         let loaded = load_index(&client).unwrap();
         assert_eq!(loaded, index);
     }
+
+    #[test]
+    fn test_update_index_retries_interleaved_writer() {
+        use crate::s3::mock::MockS3Client;
+
+        let client = MockS3Client::new();
+        save_index(&client, &SkillsIndex::new()).unwrap();
+
+        // Writer A reads the index, then writer B publishes before A saves
+        let mut attempts = 0;
+        let saved = update_index(&client, |index| {
+            attempts += 1;
+            if attempts == 1 {
+                update_index(&client, |index| {
+                    index.add_or_update_skill("b", "B", "", "1.0.0", "skills/b/1.0.0/b.skill");
+                    Ok(())
+                })?;
+            }
+            index.add_or_update_skill("a", "A", "", "1.0.0", "skills/a/1.0.0/a.skill");
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(attempts, 2);
+        let loaded = load_index(&client).unwrap();
+        assert_eq!(loaded, saved);
+        assert!(loaded.find_skill("a").is_some());
+        assert!(loaded.find_skill("b").is_some());
+    }
+
+    #[test]
+    fn test_update_index_gives_up_under_constant_conflict() {
+        use crate::s3::mock::MockS3Client;
+
+        let client = MockS3Client::new();
+        let mut n = 0;
+        let err = update_index(&client, |_| {
+            n += 1;
+            client.put_object(
+                INDEX_KEY,
+                format!("{{\"skills\": [], \"n\": {}}}", n).as_bytes(),
+            )
+        })
+        .unwrap_err();
+
+        assert!(err.to_string().contains("Gave up"));
+        assert_eq!(n, crate::storage::UPDATE_ATTEMPTS);
+    }
 }
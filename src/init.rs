@@ -78,10 +78,9 @@ pub fn run_init(output: &Output) -> Result<()> {
             local: Some(LocalRepositoryConfig {
                 path: None, // use default
                 cache: false,
+                ..Default::default()
             }),
-            bucket_name: None,
-            region: "us-east-1".to_string(),
-            endpoint: None,
+            ..Default::default()
         });
     }
 
@@ -1,46 +1,303 @@
-//! Install skills from GitHub releases.
+//! Install skills from release hosts: GitHub (including GitHub Enterprise),
+//! GitLab or Gitea releases.
 
 use anyhow::{Context, Result};
-use reqwest::blocking::Client;
+use reqwest::blocking::{Client, Response};
+use reqwest::header::LOCATION;
+use reqwest::redirect::Policy;
+use reqwest::{StatusCode, Url};
 use std::fs::{self, File};
 use std::io::{Cursor, Read, Write};
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 use zip::ZipArchive;
 
+use crate::config::{ReleaseHostKind, ReleasesConfig};
 use crate::output::Output;
 
 /// Default repository for skill releases.
 pub const DEFAULT_REPO: &str = "antstanley/skill-builder";
 
+/// Web URL of github.com, the default release host.
+const GITHUB_URL: &str = "https://github.com";
+
 /// Default installation directory relative to current directory.
 pub const DEFAULT_INSTALL_DIR: &str = ".claude/skills";
 
-/// HTTP client with reasonable defaults.
-fn create_client() -> Result<Client> {
+/// Redirects followed when talking to a release host.
+const MAX_REDIRECTS: usize = 10;
+
+/// HTTP client with reasonable defaults, for downloading `what`.
+fn create_client(what: &str) -> Result<Client> {
+    build_client(what, Policy::default())
+}
+
+fn build_client(what: &str, redirect: Policy) -> Result<Client> {
+    crate::offline::ensure_online(what)?;
     Client::builder()
         .timeout(Duration::from_secs(60))
         .user_agent("sb/1.0")
+        .redirect(redirect)
         .build()
         .context("Failed to create HTTP client")
 }
 
 /// Get the GitHub release download URL for a skill.
 pub fn get_release_url(skill_name: &str, version: Option<&str>, repo: Option<&str>) -> String {
-    let repo = repo.unwrap_or(DEFAULT_REPO);
-
-    match version {
-        Some(v) => format!(
-            "https://github.com/{}/releases/download/v{}/{}.skill",
-            repo, v, skill_name
-        ),
-        None => format!(
-            "https://github.com/{}/releases/latest/download/{}.skill",
-            repo, skill_name
-        ),
+    let source = ReleaseSource::github(repo.unwrap_or(DEFAULT_REPO));
+    let tag = version.map(|v| format!("v{}", v));
+    source
+        .download_url(skill_name, tag.as_deref())
+        .expect("GitHub has a latest download URL")
+}
+
+/// A release host from [`ReleasesConfig`], with the project to install from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseSource {
+    pub kind: ReleaseHostKind,
+    /// Web URL of the host, without a trailing slash.
+    pub base_url: String,
+    /// Project whose releases hold the skills, e.g. `group/skills`.
+    pub repo: String,
+    url_template: Option<String>,
+    latest_url_template: Option<String>,
+    token_env: Option<String>,
+    auth_header: Option<String>,
+}
+
+impl ReleaseSource {
+    /// Resolve `config`, with `repo` (e.g. from `--repo`) overriding its
+    /// project. Only github.com has a default project.
+    pub fn new(config: &ReleasesConfig, repo: Option<&str>) -> Result<Self> {
+        let base_url = config
+            .base_url
+            .as_deref()
+            .unwrap_or(GITHUB_URL)
+            .trim_end_matches('/')
+            .to_string();
+        let repo = match repo.or(config.repo.as_deref()) {
+            Some(repo) => repo.trim_matches('/').to_string(),
+            None if config.kind == ReleaseHostKind::Github && base_url == GITHUB_URL => {
+                DEFAULT_REPO.to_string()
+            }
+            None => anyhow::bail!(
+                "No project configured for {} releases at {}; set 'releases.repo' or pass --repo",
+                config.kind.name(),
+                base_url
+            ),
+        };
+        Ok(Self {
+            kind: config.kind,
+            base_url,
+            repo,
+            url_template: config.url_template.clone(),
+            latest_url_template: config.latest_url_template.clone(),
+            token_env: config.token_env.clone(),
+            auth_header: config.auth_header.clone(),
+        })
+    }
+
+    /// The releases of `repo` on github.com.
+    pub fn github(repo: &str) -> Self {
+        Self::new(&ReleasesConfig::default(), Some(repo)).expect("GitHub needs no config")
+    }
+
+    /// Where skills are installed from, e.g. "GitLab releases of group/skills".
+    pub fn describe(&self) -> String {
+        format!("{} releases of {}", self.kind.name(), self.repo)
+    }
+
+    /// Base URL of the host's REST API. For GitHub, `SB_GITHUB_API_URL`
+    /// overrides it, and GitHub Enterprise serves it under `/api/v3`.
+    pub fn api_url(&self) -> String {
+        match self.kind {
+            ReleaseHostKind::Github if self.base_url == GITHUB_URL => github_api_url(),
+            ReleaseHostKind::Github => std::env::var("SB_GITHUB_API_URL")
+                .ok()
+                .filter(|url| !url.is_empty())
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| format!("{}/api/v3", self.base_url)),
+            ReleaseHostKind::Gitlab => format!("{}/api/v4", self.base_url),
+            ReleaseHostKind::Gitea => format!("{}/api/v1", self.base_url),
+        }
+    }
+
+    /// Download URL of `skill`'s package in the release tagged `tag`, or in
+    /// the latest release. `None` when the host has no URL for the latest
+    /// release, so its tag has to be looked up with [`Self::latest_tag`].
+    pub fn download_url(&self, skill: &str, tag: Option<&str>) -> Option<String> {
+        let template = match tag {
+            Some(_) => self.url_template.as_deref().unwrap_or(match self.kind {
+                ReleaseHostKind::Github | ReleaseHostKind::Gitea => {
+                    "{base_url}/{repo}/releases/download/{tag}/{skill}.skill"
+                }
+                ReleaseHostKind::Gitlab => {
+                    "{base_url}/{repo}/-/releases/{tag}/downloads/{skill}.skill"
+                }
+            }),
+            None => match (&self.latest_url_template, self.kind) {
+                (Some(template), _) => template.as_str(),
+                (None, ReleaseHostKind::Github) if self.url_template.is_none() => {
+                    "{base_url}/{repo}/releases/latest/download/{skill}.skill"
+                }
+                (None, _) => return None,
+            },
+        };
+        let tag = tag.unwrap_or_default();
+        Some(
+            template
+                .replace("{base_url}", &self.base_url)
+                .replace("{repo_encoded}", &self.repo.replace('/', "%2F"))
+                .replace("{repo}", &self.repo)
+                .replace("{tag}", tag)
+                .replace("{version}", tag.trim_start_matches('v'))
+                .replace("{skill}", skill),
+        )
+    }
+
+    /// API URL answering with the latest release, or a list starting with it.
+    fn latest_release_url(&self) -> String {
+        let api = self.api_url();
+        match self.kind {
+            ReleaseHostKind::Github => format!("{}/repos/{}/releases/latest", api, self.repo),
+            ReleaseHostKind::Gitlab => format!(
+                "{}/projects/{}/releases?per_page=1",
+                api,
+                self.repo.replace('/', "%2F")
+            ),
+            ReleaseHostKind::Gitea => format!(
+                "{}/repos/{}/releases?limit=1&draft=false&pre-release=false",
+                api, self.repo
+            ),
+        }
+    }
+
+    /// Tag of the latest release, from the host's API. `client` should not
+    /// follow redirects, so the token stays on the host.
+    pub fn latest_tag(&self, client: &Client) -> Result<String> {
+        let url = self.latest_release_url();
+        let response = self
+            .get(client, &url)
+            .with_context(|| format!("Failed to look up the latest release at {}", url))?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!(
+                "HTTP {} when looking up the latest release at {}{}",
+                status,
+                url,
+                self.auth_hint(status)
+            );
+        }
+        let body: serde_json::Value =
+            serde_json::from_str(&response.text().context("Failed to read response body")?)
+                .context("Failed to parse the latest release")?;
+        let release = match &body {
+            serde_json::Value::Array(releases) => releases.first(),
+            release => Some(release),
+        };
+        release
+            .and_then(|r| r.get("tag_name"))
+            .and_then(|t| t.as_str())
+            .map(|t| t.to_string())
+            .with_context(|| format!("{} has no releases", self.describe()))
+    }
+
+    /// Names of the environment variables read for a token, in order.
+    fn token_envs(&self) -> Vec<&str> {
+        match (&self.token_env, self.kind) {
+            (Some(name), _) => vec![name.as_str()],
+            (None, ReleaseHostKind::Github) => crate::github::TOKEN_ENVS.to_vec(),
+            (None, ReleaseHostKind::Gitlab) => vec!["GITLAB_TOKEN"],
+            (None, ReleaseHostKind::Gitea) => vec!["GITEA_TOKEN"],
+        }
+    }
+
+    /// The access token from the environment, if one is set.
+    pub fn token(&self) -> Option<String> {
+        self.token_envs()
+            .into_iter()
+            .find_map(|name| std::env::var(name).ok().filter(|t| !t.is_empty()))
+    }
+
+    /// Header name and value carrying the token, if one is set. An
+    /// `Authorization` header gets the host's scheme: `token` on Gitea,
+    /// `Bearer` elsewhere.
+    pub fn auth_header(&self) -> Option<(String, String)> {
+        let token = self.token()?;
+        let name = self.auth_header.clone().unwrap_or_else(|| match self.kind {
+            ReleaseHostKind::Gitlab => "PRIVATE-TOKEN".to_string(),
+            _ => "Authorization".to_string(),
+        });
+        let value = if !name.eq_ignore_ascii_case("authorization") {
+            token
+        } else if self.kind == ReleaseHostKind::Gitea {
+            format!("token {}", token)
+        } else {
+            format!("Bearer {}", token)
+        };
+        Some((name, value))
+    }
+
+    /// GET `url` with the token, following redirects by hand.
+    ///
+    /// reqwest only strips `Authorization` on a redirect to another host, but
+    /// GitLab and custom `auth_header`s carry the token elsewhere, and GitLab
+    /// asset links can redirect anywhere. So the token is dropped for good
+    /// once a redirect leaves the host `url` is on. `client` must not follow
+    /// redirects itself.
+    fn get(&self, client: &Client, url: &str) -> Result<Response> {
+        let start = Url::parse(url).with_context(|| format!("Invalid URL: {}", url))?;
+        let mut url = start.clone();
+        let mut authenticated = true;
+        for _ in 0..=MAX_REDIRECTS {
+            authenticated &= url.origin() == start.origin();
+            let mut request = client.get(url.clone());
+            if let Some((name, value)) = self.auth_header().filter(|_| authenticated) {
+                request = request.header(name, value);
+            }
+            let response = request
+                .send()
+                .with_context(|| format!("Failed to fetch {}", url))?;
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .filter(|_| response.status().is_redirection());
+            match location {
+                Some(location) => {
+                    url = url
+                        .join(location)
+                        .with_context(|| format!("Invalid redirect from {}", url))?;
+                }
+                None => return Ok(response),
+            }
+        }
+        anyhow::bail!("Too many redirects fetching {}", start)
+    }
+
+    /// Hint appended to errors that a token might fix.
+    fn auth_hint(&self, status: StatusCode) -> String {
+        let denied = matches!(
+            status,
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN | StatusCode::NOT_FOUND
+        );
+        if denied && self.token().is_none() {
+            format!("; set {} if the project is private", self.token_envs()[0])
+        } else {
+            String::new()
+        }
     }
 }
 
+/// Base URL of the GitHub API, overridable with `SB_GITHUB_API_URL`.
+pub fn github_api_url() -> String {
+    std::env::var("SB_GITHUB_API_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| "https://api.github.com".to_string())
+}
+
 /// Installation result.
 #[derive(Debug)]
 pub struct InstallResult {
@@ -62,13 +319,26 @@ fn extract_archive<R: Read + std::io::Seek>(
 
     for i in 0..archive.len() {
         let mut file = archive.by_index(i)?;
-        let name = file.name().to_string();
+        // Packages may come from anywhere: refuse entries that would land
+        // outside the skill's directory
+        let name = file
+            .enclosed_name()
+            .with_context(|| format!("Unsafe path in package: {}", file.name()))?;
+        let first = name
+            .components()
+            .next()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .with_context(|| format!("Unsafe path in package: {}", file.name()))?;
 
         if i == 0 {
-            if let Some(first) = PathBuf::from(&name).components().next() {
-                skill_name = first.as_os_str().to_string_lossy().to_string();
-                skill_path = install_dir.join(&skill_name);
-            }
+            skill_name = first;
+            skill_path = install_dir.join(&skill_name);
+        } else if first != skill_name {
+            anyhow::bail!(
+                "Package entry {} is outside the skill directory {}",
+                file.name(),
+                skill_name
+            );
         }
 
         let outpath = install_dir.join(&name);
@@ -90,37 +360,52 @@ fn extract_archive<R: Read + std::io::Seek>(
     Ok((skill_name, skill_path, files_extracted))
 }
 
-/// Download and extract a skill from GitHub releases.
+/// Download and extract a skill from the releases of `source`.
 pub fn install_skill(
     skill_name: &str,
     version: Option<&str>,
-    repo: Option<&str>,
+    source: &ReleaseSource,
     install_dir: Option<&Path>,
     output: &Output,
 ) -> Result<InstallResult> {
-    let client = create_client()?;
+    let client = build_client("release downloads", Policy::none())?;
 
     let install_dir = install_dir
         .map(|p| p.to_path_buf())
         .unwrap_or_else(|| PathBuf::from(DEFAULT_INSTALL_DIR));
 
-    let url = get_release_url(skill_name, version, repo);
-
     output.header(&format!("Installing {} skill...", skill_name));
     let ver_str = version.unwrap_or("latest");
     output.step(&format!("Version: {}", ver_str));
     output.newline();
 
+    let tag = version.map(|v| format!("v{}", v));
+    let url = match source.download_url(skill_name, tag.as_deref()) {
+        Some(url) => url,
+        None => {
+            let tag = source.latest_tag(&client)?;
+            output.step(&format!("Latest release: {}", tag));
+            source
+                .download_url(skill_name, Some(&tag))
+                .expect("every host has a tagged download URL")
+        }
+    };
+
     let pb = output.spinner(&format!("Downloading from {}", url));
 
-    let response = client
-        .get(&url)
-        .send()
+    let response = source
+        .get(&client, &url)
         .with_context(|| format!("Failed to download {}", url))?;
 
-    if !response.status().is_success() {
+    let status = response.status();
+    if !status.is_success() {
         pb.finish_and_clear();
-        anyhow::bail!("HTTP {} when downloading {}", response.status(), url);
+        anyhow::bail!(
+            "HTTP {} when downloading {}{}",
+            status,
+            url,
+            source.auth_hint(status)
+        );
     }
 
     let bytes = response.bytes().context("Failed to read response body")?;
@@ -144,6 +429,51 @@ pub fn install_skill(
     })
 }
 
+/// Download the .skill package at `url`.
+pub fn download_package(url: &str, output: &Output) -> Result<Vec<u8>> {
+    let client = create_client(url)?;
+    let pb = output.spinner(&format!("Downloading from {}", url));
+    let result = client
+        .get(url)
+        .send()
+        .with_context(|| format!("Failed to download {}", url))
+        .and_then(|response| {
+            if !response.status().is_success() {
+                anyhow::bail!("HTTP {} when downloading {}", response.status(), url);
+            }
+            Ok(response
+                .bytes()
+                .context("Failed to read response body")?
+                .to_vec())
+        });
+    pb.finish_and_clear();
+    result
+}
+
+/// Install a skill from the bytes of a .skill package.
+pub fn install_from_bytes<Q: AsRef<Path>>(
+    data: &[u8],
+    install_dir: Q,
+    output: &Output,
+) -> Result<InstallResult> {
+    let pb = output.spinner("Extracting skill");
+    let mut archive = ZipArchive::new(Cursor::new(data)).context("Invalid .skill archive")?;
+    let (skill_name, skill_path, files_extracted) =
+        extract_archive(&mut archive, install_dir.as_ref())?;
+    pb.finish_and_clear();
+
+    output.status(
+        "Installed",
+        &format!("{} to {}", skill_name, skill_path.display()),
+    );
+
+    Ok(InstallResult {
+        skill_name,
+        install_path: skill_path,
+        files_extracted,
+    })
+}
+
 /// Install a skill from a local .skill file.
 pub fn install_from_file<P: AsRef<Path>, Q: AsRef<Path>>(
     skill_file: P,
@@ -173,6 +503,50 @@ pub fn install_from_file<P: AsRef<Path>, Q: AsRef<Path>>(
     })
 }
 
+/// A skill found in an install directory.
+#[derive(Debug, PartialEq)]
+pub struct InstalledSkill {
+    /// Name from SKILL.md, or the directory name.
+    pub name: String,
+    /// Version from SKILL.md, if the package recorded one.
+    pub version: Option<String>,
+    pub path: PathBuf,
+}
+
+/// Skills installed in `install_dir`: subdirectories with a SKILL.md, sorted
+/// by name. A missing directory has none.
+pub fn installed_skills(install_dir: &Path) -> Result<Vec<InstalledSkill>> {
+    if !install_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut skills = Vec::new();
+    for dir_entry in fs::read_dir(install_dir)
+        .with_context(|| format!("Failed to read {}", install_dir.display()))?
+    {
+        let path = dir_entry?.path();
+        let Ok(content) = fs::read_to_string(path.join("SKILL.md")) else {
+            continue;
+        };
+        let frontmatter = crate::validate::parse_frontmatter(&content).unwrap_or_default();
+        let name = frontmatter
+            .name
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| {
+                path.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string()
+            });
+        skills.push(InstalledSkill {
+            name,
+            version: frontmatter.version,
+            path,
+        });
+    }
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(skills)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -211,6 +585,119 @@ mod tests {
         );
     }
 
+    fn releases(kind: ReleaseHostKind, base_url: &str) -> ReleasesConfig {
+        ReleasesConfig {
+            kind,
+            base_url: Some(base_url.to_string()),
+            repo: Some("group/sub/skills".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_release_source_urls() {
+        let ghe = ReleaseSource::new(
+            &releases(ReleaseHostKind::Github, "https://ghe.example.com/"),
+            Some("acme/skills"),
+        )
+        .unwrap();
+        assert_eq!(
+            ghe.download_url("my-skill", None).unwrap(),
+            "https://ghe.example.com/acme/skills/releases/latest/download/my-skill.skill"
+        );
+
+        let gitlab = ReleaseSource::new(
+            &releases(ReleaseHostKind::Gitlab, "https://gl.example.com"),
+            None,
+        )
+        .unwrap();
+        assert_eq!(gitlab.describe(), "GitLab releases of group/sub/skills");
+        assert_eq!(
+            gitlab.download_url("my-skill", Some("v1.0.0")).unwrap(),
+            "https://gl.example.com/group/sub/skills/-/releases/v1.0.0/downloads/my-skill.skill"
+        );
+        assert_eq!(gitlab.download_url("my-skill", None), None);
+        assert_eq!(
+            gitlab.latest_release_url(),
+            "https://gl.example.com/api/v4/projects/group%2Fsub%2Fskills/releases?per_page=1"
+        );
+
+        let gitea = ReleaseSource::new(
+            &releases(ReleaseHostKind::Gitea, "https://gitea.example.com"),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            gitea.download_url("my-skill", Some("v2.0.0")).unwrap(),
+            "https://gitea.example.com/group/sub/skills/releases/download/v2.0.0/my-skill.skill"
+        );
+
+        let custom = ReleaseSource::new(
+            &ReleasesConfig {
+                url_template: Some(
+                    "{base_url}/api/v4/projects/{repo_encoded}/packages/generic/{skill}/{version}/{skill}.skill"
+                        .to_string(),
+                ),
+                ..releases(ReleaseHostKind::Gitlab, "https://gl.example.com")
+            },
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            custom.download_url("my-skill", Some("v1.2.0")).unwrap(),
+            "https://gl.example.com/api/v4/projects/group%2Fsub%2Fskills/packages/generic/my-skill/1.2.0/my-skill.skill"
+        );
+
+        // Only github.com has a default project
+        let err = ReleaseSource::new(
+            &ReleasesConfig {
+                repo: None,
+                ..releases(ReleaseHostKind::Gitea, "https://gitea.example.com")
+            },
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("set 'releases.repo'"));
+    }
+
+    #[test]
+    fn test_release_source_auth_header() {
+        let var = "SB_TEST_INSTALL_RELEASE_TOKEN";
+        let source = |kind, auth_header: Option<&str>| {
+            ReleaseSource::new(
+                &ReleasesConfig {
+                    token_env: Some(var.to_string()),
+                    auth_header: auth_header.map(|h| h.to_string()),
+                    ..releases(kind, "https://example.com")
+                },
+                None,
+            )
+            .unwrap()
+        };
+        std::env::remove_var(var);
+        assert_eq!(source(ReleaseHostKind::Gitlab, None).auth_header(), None);
+
+        std::env::set_var(var, "secret");
+        let header = |kind, name| source(kind, name).auth_header().unwrap();
+        assert_eq!(
+            header(ReleaseHostKind::Gitlab, None),
+            ("PRIVATE-TOKEN".to_string(), "secret".to_string())
+        );
+        assert_eq!(
+            header(ReleaseHostKind::Gitea, None),
+            ("Authorization".to_string(), "token secret".to_string())
+        );
+        assert_eq!(
+            header(ReleaseHostKind::Github, None),
+            ("Authorization".to_string(), "Bearer secret".to_string())
+        );
+        assert_eq!(
+            header(ReleaseHostKind::Gitlab, Some("JOB-TOKEN")),
+            ("JOB-TOKEN".to_string(), "secret".to_string())
+        );
+        std::env::remove_var(var);
+    }
+
     #[test]
     fn test_install_from_file() {
         let out = test_output();
@@ -248,6 +735,61 @@ description: A test skill for installation testing with enough characters to pas
         assert!(result.install_path.join("references/doc.md").exists());
     }
 
+    #[test]
+    fn test_installed_skills() {
+        let temp = TempDir::new().unwrap();
+        assert!(installed_skills(&temp.path().join("missing"))
+            .unwrap()
+            .is_empty());
+
+        fs::create_dir_all(temp.path().join("b-skill")).unwrap();
+        fs::write(
+            temp.path().join("b-skill/SKILL.md"),
+            "---\nname: b-skill\nversion: 1.2.0\n---\n",
+        )
+        .unwrap();
+        fs::create_dir_all(temp.path().join("a-skill")).unwrap();
+        fs::write(temp.path().join("a-skill/SKILL.md"), "# No frontmatter").unwrap();
+        fs::create_dir_all(temp.path().join("not-a-skill")).unwrap();
+
+        let skills = installed_skills(temp.path()).unwrap();
+        let found: Vec<(&str, Option<&str>)> = skills
+            .iter()
+            .map(|s| (s.name.as_str(), s.version.as_deref()))
+            .collect();
+        assert_eq!(found, vec![("a-skill", None), ("b-skill", Some("1.2.0"))]);
+    }
+
+    /// A package with the given entries.
+    fn zip_with(entries: &[&str]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for name in entries {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(b"pwned").unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_install_rejects_entries_outside_the_skill() {
+        let temp = TempDir::new().unwrap();
+        let install_dir = temp.path().join("a/b/skills");
+
+        for entries in [
+            &["evil/SKILL.md", "../../.bashrc"][..],
+            &["evil/SKILL.md", "evil/../../outside.md"],
+            &["evil/SKILL.md", "other/SKILL.md"],
+            &["/etc/evil"],
+        ] {
+            let err = install_from_bytes(&zip_with(entries), &install_dir, &test_output());
+            assert!(err.is_err(), "{:?} was installed", entries);
+        }
+        assert!(!temp.path().join("a/.bashrc").exists());
+        assert!(!temp.path().join("a/b/outside.md").exists());
+        assert!(!install_dir.join("other").exists());
+    }
+
     #[test]
     fn test_default_constants() {
         assert_eq!(DEFAULT_REPO, "antstanley/skill-builder");
@@ -1,13 +1,18 @@
-//! Multi-source install resolution: local repo → remote repo → GitHub.
+//! Multi-source install resolution: local repo → remote repos → releases.
 
 use anyhow::{Context, Result};
 use std::path::Path;
 
-use crate::config::Config;
-use crate::install::{install_from_file, install_skill, InstallResult};
+use crate::cache::SkillCache;
+use crate::config::{Config, ReleaseHostKind, ReleasesConfig, RepositoryConfig};
+use crate::github::{GitHubClient, GitHubReleaseStorage};
+use crate::index::{compare_semver, is_not_found, NotFound, INDEX_KEY};
+use crate::install::{install_from_file, install_skill, InstallResult, ReleaseSource};
 use crate::local_storage::LocalStorageClient;
+use crate::offline::OfflineError;
 use crate::output::Output;
 use crate::repository::Repository;
+use crate::storage::StorageOperations;
 
 /// Options controlling install source resolution.
 pub struct InstallOptions<'a> {
@@ -18,14 +23,23 @@ pub struct InstallOptions<'a> {
     pub local_only: bool,
     pub remote_only: bool,
     pub github_only: bool,
+    /// Only use sources on this machine; see [`crate::offline`].
+    pub offline: bool,
+    /// Install the skill that replaces a deprecated one, when the repository
+    /// names it in `replaced_by`. Ignored when a version is pinned.
+    pub follow_replaced: bool,
 }
 
 /// Which source a skill was installed from.
 #[derive(Debug, PartialEq)]
 pub enum InstallSource {
     Local,
-    Remote,
-    GitHub,
+    /// The download cache of remote repositories, in offline mode.
+    Cache,
+    /// A remote repository, by display name.
+    Remote(String),
+    /// The release host: GitHub releases unless `releases` configures another.
+    Releases,
 }
 
 /// Result of a resolved install.
@@ -35,76 +49,124 @@ pub struct ResolvedInstall {
     pub result: InstallResult,
 }
 
-/// Resolve the install source and install the skill.
+/// Resolve and install a skill from the best available source.
 ///
 /// Resolution order (when no explicit source flag):
-/// 1. Local repository (if configured) → install if found
-/// 2. Remote S3 repository (if configured) → install if found
-/// 3. GitHub releases (fallback)
+/// 1. Local repository (if configured, or if the default one has an index) → install if found
+/// 2. Remote repositories in priority order → install from the first that has it
+/// 3. Releases on the configured release host, GitHub by default (fallback)
 ///
 /// Explicit flags (`--local`, `--remote`, `--github`) skip the cascade.
+///
+/// With `offline` set, remote repositories that need the network and the
+/// release host are skipped, the download cache is tried after the local repository, and
+/// the error when nothing is found lists the skipped sources.
 pub fn resolve_and_install(
     config: &Config,
     options: &InstallOptions,
     output: &Output,
 ) -> Result<ResolvedInstall> {
-    let repo_config = config.repository.as_ref();
+    let repos = config.all_repositories();
+    let local_path = config.local_repo_path();
+    let has_local = repos.iter().any(|r| r.has_local()) || local_path.join(INDEX_KEY).exists();
+    let mut remotes: Vec<&RepositoryConfig> =
+        repos.into_iter().filter(|r| r.has_remote()).collect();
+
+    // Network sources offline mode leaves out
+    let mut skipped = Vec::new();
+    if options.offline {
+        remotes.retain(|rc| {
+            if rc.uses_network() {
+                skipped.push(format!("repository '{}'", rc.display_name()));
+            }
+            !rc.uses_network()
+        });
+    }
+    let releases = config.releases.clone().unwrap_or_default();
+    let releases_label = format!("{} releases", releases.kind.name());
+    let offline_error = |github: bool| {
+        let mut skipped = skipped.clone();
+        if github {
+            skipped.push(releases_label.clone());
+        }
+        anyhow::Error::new(OfflineError { skipped })
+    };
 
     // Explicit source flags
     if options.local_only {
-        return install_from_local(repo_config, options, output);
+        return install_from_local(&local_path, options, output);
     }
     if options.remote_only {
-        return install_from_remote(config, options, output);
+        if remotes.is_empty() && !skipped.is_empty() {
+            return Err(offline_error(false));
+        }
+        return install_from_remotes(&remotes, options, output);
     }
     if options.github_only {
-        return install_from_github(options, output);
+        if options.offline {
+            return Err(offline_error(true));
+        }
+        return install_from_releases(&releases, options, output);
     }
 
-    // Cascade: local → remote → GitHub
-    if let Some(rc) = repo_config {
-        if rc.has_local() {
-            match install_from_local(repo_config, options, output) {
-                Ok(result) => return Ok(result),
-                Err(_) => {
-                    output.info(&format!(
-                        "Skill '{}' not found in local repository, trying next source...",
-                        options.skill_name
-                    ));
-                }
+    // Cascade: local → remotes → GitHub
+    if has_local {
+        match install_from_local(&local_path, options, output) {
+            Ok(result) => return Ok(result),
+            Err(e) if is_not_found(&e) => {
+                output.info(&format!(
+                    "Skill '{}' not found in local repository, trying next source...",
+                    options.skill_name
+                ));
             }
+            Err(e) => return Err(e),
         }
+    }
 
-        if rc.has_remote() {
-            match install_from_remote(config, options, output) {
-                Ok(result) => return Ok(result),
-                Err(_) => {
-                    output.info(&format!(
-                        "Skill '{}' not found in remote repository, trying GitHub...",
-                        options.skill_name
-                    ));
-                }
+    if options.offline {
+        match install_from_cache(&local_path, options, output) {
+            Ok(result) => return Ok(result),
+            Err(e) if is_not_found(&e) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    if !remotes.is_empty() {
+        match install_from_remotes(&remotes, options, output) {
+            Ok(result) => return Ok(result),
+            Err(e) if options.offline && is_not_found(&e) => {}
+            Err(e) if is_not_found(&e) => {
+                output.info(&format!(
+                    "Skill '{}' not found in remote repositories, trying {}...",
+                    options.skill_name, releases_label
+                ));
             }
+            Err(e) => return Err(e),
         }
     }
 
-    install_from_github(options, output)
+    if options.offline {
+        return Err(offline_error(true).context(format!(
+            "Skill '{}' not found in the local repository or cache",
+            options.skill_name
+        )));
+    }
+    install_from_releases(&releases, options, output)
 }
 
 fn install_from_local(
-    repo_config: Option<&crate::config::RepositoryConfig>,
+    local_path: &Path,
     options: &InstallOptions,
     output: &Output,
 ) -> Result<ResolvedInstall> {
-    let rc = repo_config.context("No repository configured for local install")?;
-    let local_path = rc.local_repo_path();
-    let client = LocalStorageClient::with_dir(&local_path);
+    let client = LocalStorageClient::with_dir(local_path);
 
     // Build a Repository backed by local storage
     let repo = Repository::new(client);
     output.info("Looking in local repository...");
+    let name = skill_to_install(&repo, options, output)?;
     let skill_path = repo
-        .download(options.skill_name, options.version, None, output)
+        .download(&name, options.version, None, output)
         .context("Skill not found in local repository")?;
 
     let result = install_from_file(&skill_path, options.install_dir, output)?;
@@ -114,52 +176,190 @@ fn install_from_local(
     })
 }
 
-fn install_from_remote(
-    config: &Config,
+/// Install from the download cache in the local repository, taking the
+/// highest cached version unless one is asked for.
+fn install_from_cache(
+    local_path: &Path,
     options: &InstallOptions,
     output: &Output,
 ) -> Result<ResolvedInstall> {
-    let rc = config
-        .repository
-        .as_ref()
-        .context("No repository configured for remote install")?;
+    let cache = SkillCache::from(LocalStorageClient::with_dir(local_path));
+    let not_cached = || NotFound {
+        message: format!(
+            "Skill '{}' not found in the download cache",
+            options.skill_name
+        ),
+    };
+    let version = match options.version {
+        Some(v) => v.to_string(),
+        None => cache
+            .index()?
+            .entries
+            .into_iter()
+            .filter(|e| e.name == options.skill_name)
+            .map(|e| e.version)
+            .max_by(|a, b| compare_semver(a, b))
+            .ok_or_else(not_cached)?,
+    };
+    let data = cache
+        .get(options.skill_name, &version)?
+        .ok_or_else(not_cached)?;
+    output.info(&format!(
+        "Using cached version: {} v{}",
+        options.skill_name, version
+    ));
+
+    let dir = std::env::temp_dir().join("skill-builder");
+    std::fs::create_dir_all(&dir)?;
+    let skill_path = dir.join(format!("{}.skill", options.skill_name));
+    std::fs::write(&skill_path, data)
+        .with_context(|| format!("Failed to write {}", skill_path.display()))?;
 
-    if !rc.has_remote() {
-        anyhow::bail!("No remote repository configured (missing bucket_name)");
+    let result = install_from_file(&skill_path, options.install_dir, output)?;
+    Ok(ResolvedInstall {
+        source: InstallSource::Cache,
+        result,
+    })
+}
+
+/// Try each remote repository in order, installing from the first that has the skill.
+///
+/// Only a repository that doesn't have the skill is passed over. Any other
+/// failure, e.g. a rejected token or a checksum mismatch, stops the search, so
+/// a later repository can't shadow the one that should have answered.
+fn install_from_remotes(
+    remotes: &[&RepositoryConfig],
+    options: &InstallOptions,
+    output: &Output,
+) -> Result<ResolvedInstall> {
+    let mut last_err = None;
+    for rc in remotes {
+        match install_from_remote(rc, options, output) {
+            Ok(result) => return Ok(result),
+            Err(e) if is_not_found(&e) => {
+                output.info(&format!(
+                    "Skill '{}' not found in repository '{}'",
+                    options.skill_name,
+                    rc.display_name()
+                ));
+                last_err = Some(e);
+            }
+            Err(e) => {
+                return Err(e.context(format!(
+                    "Failed to install '{}' from repository '{}'",
+                    options.skill_name,
+                    rc.display_name()
+                )))
+            }
+        }
     }
 
+    Err(last_err.unwrap_or_else(|| {
+        anyhow::anyhow!("No remote repository configured (missing bucket_name)")
+    }))
+}
+
+fn install_from_remote(
+    rc: &RepositoryConfig,
+    options: &InstallOptions,
+    output: &Output,
+) -> Result<ResolvedInstall> {
     let repo = Repository::from_config(rc)?;
 
-    output.info("Looking in remote repository...");
-    repo.install(
-        options.skill_name,
-        options.version,
-        options.install_dir,
-        output,
-    )?;
+    output.info(&format!("Looking in repository '{}'...", rc.display_name()));
+    let name = skill_to_install(&repo, options, output)?;
+    let result = repo.install(&name, options.version, options.install_dir, output)?;
 
     Ok(ResolvedInstall {
-        source: InstallSource::Remote,
-        result: InstallResult {
-            skill_name: options.skill_name.to_string(),
-            install_path: options.install_dir.join(options.skill_name),
-            files_extracted: 0, // repo.install handles extraction internally
-        },
+        source: InstallSource::Remote(rc.display_name().to_string()),
+        result,
     })
 }
 
-fn install_from_github(options: &InstallOptions, output: &Output) -> Result<ResolvedInstall> {
-    output.info("Installing from GitHub releases...");
+/// The skill to install from `repo`: the requested one, or with
+/// `follow_replaced` the last skill of its `replaced_by` chain that `repo`
+/// has. A missing index entry is left for the download to report.
+fn skill_to_install<S: StorageOperations>(
+    repo: &Repository<S>,
+    options: &InstallOptions,
+    output: &Output,
+) -> Result<String> {
+    let mut name = options.skill_name.to_string();
+    if !options.follow_replaced || options.version.is_some() {
+        return Ok(name);
+    }
+    let index = repo.list(None)?;
+    let mut seen = vec![name.clone()];
+    while let Some(replacement) = index
+        .find_skill(&name)
+        .and_then(|entry| entry.replaced_by.as_ref())
+    {
+        if seen.contains(replacement) {
+            output.warn(&format!(
+                "Not following replacements of {}: {} leads back to it",
+                options.skill_name, replacement
+            ));
+            return Ok(options.skill_name.to_string());
+        }
+        if index.find_skill(replacement).is_none() {
+            output.warn(&format!(
+                "{} is replaced by {}, which isn't in this repository",
+                name, replacement
+            ));
+            break;
+        }
+        output.info(&format!("{} is replaced by {}", name, replacement));
+        name = replacement.clone();
+        seen.push(name.clone());
+    }
+    Ok(name)
+}
+
+/// Install from the releases of `options.github_repo` (or the configured
+/// project) on the release host. On GitHub, the version is resolved from the
+/// releases that have the skill; if they can't be listed, e.g. because the API
+/// rate limit is used up, and on other hosts, the package is downloaded from
+/// the release download URL.
+fn install_from_releases(
+    releases: &ReleasesConfig,
+    options: &InstallOptions,
+    output: &Output,
+) -> Result<ResolvedInstall> {
+    let source = ReleaseSource::new(releases, options.github_repo)?;
+    output.info(&format!("Installing from {}...", source.describe()));
+    if source.kind == ReleaseHostKind::Github {
+        let repo = Repository::new(GitHubReleaseStorage::new(
+            GitHubClient::new(&source.api_url(), source.token())?,
+            &source.repo,
+        ));
+        match repo.list(None) {
+            Ok(_) => {
+                let name = skill_to_install(&repo, options, output)?;
+                let result = repo
+                    .install(&name, options.version, options.install_dir, output)
+                    .with_context(|| format!("Failed to install from {}", source.describe()))?;
+                return Ok(ResolvedInstall {
+                    source: InstallSource::Releases,
+                    result,
+                });
+            }
+            Err(e) => output.warn(&format!(
+                "Couldn't list GitHub releases ({:#}); trying the release download URL",
+                e
+            )),
+        }
+    }
+
     let result = install_skill(
         options.skill_name,
         options.version,
-        options.github_repo,
+        &source,
         Some(options.install_dir),
         output,
     )?;
 
     Ok(ResolvedInstall {
-        source: InstallSource::GitHub,
+        source: InstallSource::Releases,
         result,
     })
 }
@@ -167,7 +367,7 @@ fn install_from_github(options: &InstallOptions, output: &Output) -> Result<Reso
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{LocalRepositoryConfig, RepositoryConfig};
+    use crate::config::LocalRepositoryConfig;
     use crate::package::package_skill;
     use crate::repository::UploadParams;
     use tempfile::TempDir;
@@ -233,11 +433,11 @@ description: A test skill for resolver testing with enough characters to pass va
                 local: Some(LocalRepositoryConfig {
                     path: Some(local_path.to_string_lossy().to_string()),
                     cache: false,
+                    ..Default::default()
                 }),
-                bucket_name: None,
-                region: "us-east-1".to_string(),
-                endpoint: None,
+                ..Default::default()
             }),
+            ..Default::default()
         };
 
         let options = InstallOptions {
@@ -248,6 +448,8 @@ description: A test skill for resolver testing with enough characters to pass va
             local_only: true,
             remote_only: false,
             github_only: false,
+            offline: false,
+            follow_replaced: false,
         };
 
         let resolved = resolve_and_install(&config, &options, &out).unwrap();
@@ -255,6 +457,64 @@ description: A test skill for resolver testing with enough characters to pass va
         assert!(install_dir.join("resolver-test/SKILL.md").exists());
     }
 
+    #[test]
+    fn test_follow_replaced_by() {
+        let out = test_output();
+        let tmp = TempDir::new().unwrap();
+        let local_path = tmp.path().join("local");
+        create_test_skill_in_local_repo(&local_path);
+        let repo = Repository::new(LocalStorageClient::new(&local_path).unwrap());
+        let package = repo.download("resolver-test", None, None, &out).unwrap();
+        for name in ["legacy-a", "legacy-b"] {
+            repo.upload(
+                &UploadParams {
+                    name,
+                    version: "0.1.0",
+                    description: "Superseded",
+                    llms_txt_url: "",
+                    skill_file: &package,
+                    changelog: None,
+                    source_dir: None,
+                },
+                &out,
+            )
+            .unwrap();
+        }
+        repo.deprecate("legacy-a", None, Some("legacy-b")).unwrap();
+        repo.deprecate("legacy-b", Some("renamed"), Some("resolver-test"))
+            .unwrap();
+
+        let install_dir = tmp.path().join("installed");
+        let mut options = InstallOptions {
+            skill_name: "legacy-a",
+            version: None,
+            install_dir: &install_dir,
+            github_repo: None,
+            local_only: true,
+            remote_only: false,
+            github_only: false,
+            offline: false,
+            follow_replaced: true,
+        };
+        assert_eq!(
+            skill_to_install(&repo, &options, &out).unwrap(),
+            "resolver-test"
+        );
+
+        // A pinned version is for the requested skill
+        options.version = Some("0.1.0");
+        assert_eq!(skill_to_install(&repo, &options, &out).unwrap(), "legacy-a");
+        options.version = None;
+        options.follow_replaced = false;
+        assert_eq!(skill_to_install(&repo, &options, &out).unwrap(), "legacy-a");
+
+        // Cycles install the requested skill
+        repo.deprecate("resolver-test", None, Some("legacy-a"))
+            .unwrap();
+        options.follow_replaced = true;
+        assert_eq!(skill_to_install(&repo, &options, &out).unwrap(), "legacy-a");
+    }
+
     #[test]
     fn test_local_not_found_falls_through_to_github_error() {
         let out = test_output();
@@ -270,11 +530,11 @@ description: A test skill for resolver testing with enough characters to pass va
                 local: Some(LocalRepositoryConfig {
                     path: Some(local_path.to_string_lossy().to_string()),
                     cache: false,
+                    ..Default::default()
                 }),
-                bucket_name: None,
-                region: "us-east-1".to_string(),
-                endpoint: None,
+                ..Default::default()
             }),
+            ..Default::default()
         };
 
         let options = InstallOptions {
@@ -285,6 +545,8 @@ description: A test skill for resolver testing with enough characters to pass va
             local_only: false,
             remote_only: false,
             github_only: false,
+            offline: false,
+            follow_replaced: false,
         };
 
         // This will fail because GitHub won't have it either, but it should
@@ -308,11 +570,11 @@ description: A test skill for resolver testing with enough characters to pass va
                 local: Some(LocalRepositoryConfig {
                     path: Some(local_path.to_string_lossy().to_string()),
                     cache: false,
+                    ..Default::default()
                 }),
-                bucket_name: None,
-                region: "us-east-1".to_string(),
-                endpoint: None,
+                ..Default::default()
             }),
+            ..Default::default()
         };
 
         let options = InstallOptions {
@@ -323,6 +585,8 @@ description: A test skill for resolver testing with enough characters to pass va
             local_only: true,
             remote_only: false,
             github_only: false,
+            offline: false,
+            follow_replaced: false,
         };
 
         let result = resolve_and_install(&config, &options, &out);
@@ -345,10 +609,126 @@ description: A test skill for resolver testing with enough characters to pass va
             local_only: false,
             remote_only: false,
             github_only: false,
+            offline: false,
+            follow_replaced: false,
         };
 
         // Should fail at GitHub (no such release), but shouldn't panic
         let result = resolve_and_install(&config, &options, &out);
         assert!(result.is_err());
     }
+
+    /// A config with a local repository at `local_path` and an S3 remote.
+    fn offline_config(local_path: &Path) -> Config {
+        Config {
+            repository: Some(RepositoryConfig {
+                name: Some("company".to_string()),
+                bucket_name: Some("company-skills".to_string()),
+                local: Some(LocalRepositoryConfig {
+                    path: Some(local_path.to_string_lossy().to_string()),
+                    cache: true,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn offline_options<'a>(skill_name: &'a str, install_dir: &'a Path) -> InstallOptions<'a> {
+        InstallOptions {
+            skill_name,
+            version: None,
+            github_repo: None,
+            install_dir,
+            local_only: false,
+            remote_only: false,
+            github_only: false,
+            offline: true,
+            follow_replaced: false,
+        }
+    }
+
+    #[test]
+    fn test_offline_lists_skipped_sources() {
+        let tmp = TempDir::new().unwrap();
+        let local_path = tmp.path().join("local");
+        std::fs::create_dir_all(&local_path).unwrap();
+        let install_dir = tmp.path().join("installed");
+        let config = offline_config(&local_path);
+
+        let err = resolve_and_install(
+            &config,
+            &offline_options("missing", &install_dir),
+            &test_output(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "Skill 'missing' not found in the local repository or cache: offline mode is on \
+             (--offline or SB_OFFLINE=1); skipped network sources: repository 'company', \
+             GitHub releases"
+        );
+
+        let github_only = InstallOptions {
+            github_only: true,
+            ..offline_options("missing", &install_dir)
+        };
+        let err = resolve_and_install(&config, &github_only, &test_output()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<OfflineError>().unwrap().skipped,
+            vec!["repository 'company'", "GitHub releases"]
+        );
+    }
+
+    #[test]
+    fn test_offline_reports_broken_cache() {
+        let tmp = TempDir::new().unwrap();
+        let local_path = tmp.path().join("local");
+        std::fs::create_dir_all(local_path.join("cache")).unwrap();
+        std::fs::write(local_path.join(crate::cache::CACHE_INDEX_KEY), "not json").unwrap();
+        let config = offline_config(&local_path);
+
+        let err = resolve_and_install(
+            &config,
+            &offline_options("missing", &tmp.path().join("installed")),
+            &test_output(),
+        )
+        .unwrap_err();
+        assert!(err.downcast_ref::<OfflineError>().is_none());
+        assert!(format!("{:#}", err).contains("Failed to parse cache index"));
+    }
+
+    #[test]
+    fn test_offline_installs_from_local_repo_and_cache() {
+        let tmp = TempDir::new().unwrap();
+        let local_path = tmp.path().join("local");
+        create_test_skill_in_local_repo(&local_path);
+        let config = offline_config(&local_path);
+
+        let install_dir = tmp.path().join("installed");
+        let resolved = resolve_and_install(
+            &config,
+            &offline_options("resolver-test", &install_dir),
+            &test_output(),
+        )
+        .unwrap();
+        assert_eq!(resolved.source, InstallSource::Local);
+
+        // A download cached from the remote, but never published locally
+        let package =
+            std::fs::read(local_path.join("skills/resolver-test/1.0.0/resolver-test.skill"))
+                .unwrap();
+        let cache = SkillCache::from(LocalStorageClient::with_dir(&local_path));
+        cache.put("cached-skill", "2.0.0", &package).unwrap();
+
+        let resolved = resolve_and_install(
+            &config,
+            &offline_options("cached-skill", &install_dir),
+            &test_output(),
+        )
+        .unwrap();
+        assert_eq!(resolved.source, InstallSource::Cache);
+        assert!(install_dir.join("resolver-test/SKILL.md").exists());
+    }
 }
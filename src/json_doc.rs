@@ -0,0 +1,545 @@
+//! Span-preserving JSON documents for line-numbered diagnostics and in-place edits.
+//!
+//! `serde_json` discards positions and formatting, so config validation and
+//! editing parse files into a [`JsonNode`] tree that remembers the byte range of
+//! every value. Edits splice new text into those ranges and leave the rest of the
+//! file untouched.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::ops::Range;
+
+/// A parsed JSON value with its location in the source text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonNode {
+    pub kind: NodeKind,
+    /// Byte range of the value in the source.
+    pub span: Range<usize>,
+    /// 1-based line of the value's first character.
+    pub line: usize,
+}
+
+/// The kind of a JSON value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeKind {
+    Object(Vec<Member>),
+    Array(Vec<JsonNode>),
+    String(String),
+    Number(String),
+    Bool(bool),
+    Null,
+}
+
+/// An object member.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+    pub key: String,
+    /// Byte range of the quoted key.
+    pub key_span: Range<usize>,
+    /// 1-based line of the key.
+    pub key_line: usize,
+    pub value: JsonNode,
+}
+
+impl JsonNode {
+    /// Members of an object node.
+    pub fn members(&self) -> Option<&[Member]> {
+        match &self.kind {
+            NodeKind::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// Elements of an array node.
+    pub fn elements(&self) -> Option<&[JsonNode]> {
+        match &self.kind {
+            NodeKind::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// Look up an object member by key.
+    pub fn member(&self, key: &str) -> Option<&Member> {
+        self.members()?.iter().find(|m| m.key == key)
+    }
+
+    /// Value of a string node.
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.kind {
+            NodeKind::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// JSON type name used in diagnostics.
+    pub fn type_name(&self) -> &'static str {
+        match self.kind {
+            NodeKind::Object(_) => "object",
+            NodeKind::Array(_) => "array",
+            NodeKind::String(_) => "string",
+            NodeKind::Number(_) => "number",
+            NodeKind::Bool(_) => "boolean",
+            NodeKind::Null => "null",
+        }
+    }
+}
+
+/// Parse JSON text into a span-preserving tree.
+pub fn parse(text: &str) -> Result<JsonNode> {
+    let mut parser = Parser {
+        text,
+        bytes: text.as_bytes(),
+        pos: 0,
+    };
+    parser.skip_ws();
+    let node = parser.value()?;
+    parser.skip_ws();
+    if parser.pos != parser.bytes.len() {
+        return Err(parser.error("trailing characters"));
+    }
+    Ok(node)
+}
+
+/// 1-based line number of a byte offset.
+pub fn line_at(text: &str, offset: usize) -> usize {
+    text[..offset.min(text.len())].matches('\n').count() + 1
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, msg: &str) -> anyhow::Error {
+        anyhow::anyhow!(
+            "Invalid JSON at line {}: {}",
+            line_at(self.text, self.pos),
+            msg
+        )
+    }
+
+    fn skip_ws(&mut self) {
+        while self.pos < self.bytes.len() && self.bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<()> {
+        if self.bytes.get(self.pos) == Some(&byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", byte as char)))
+        }
+    }
+
+    fn value(&mut self) -> Result<JsonNode> {
+        let start = self.pos;
+        let line = line_at(self.text, start);
+        let kind = match self.bytes.get(self.pos) {
+            Some(b'{') => self.object()?,
+            Some(b'[') => self.array()?,
+            Some(b'"') => NodeKind::String(self.string()?),
+            Some(b't') => self.literal("true", NodeKind::Bool(true))?,
+            Some(b'f') => self.literal("false", NodeKind::Bool(false))?,
+            Some(b'n') => self.literal("null", NodeKind::Null)?,
+            Some(b'-' | b'0'..=b'9') => self.number()?,
+            Some(_) => return Err(self.error("unexpected character")),
+            None => return Err(self.error("unexpected end of input")),
+        };
+        Ok(JsonNode {
+            kind,
+            span: start..self.pos,
+            line,
+        })
+    }
+
+    fn object(&mut self) -> Result<NodeKind> {
+        self.expect(b'{')?;
+        let mut members = Vec::new();
+        self.skip_ws();
+        if self.bytes.get(self.pos) == Some(&b'}') {
+            self.pos += 1;
+            return Ok(NodeKind::Object(members));
+        }
+        loop {
+            self.skip_ws();
+            let key_start = self.pos;
+            let key_line = line_at(self.text, key_start);
+            if self.bytes.get(self.pos) != Some(&b'"') {
+                return Err(self.error("expected object key"));
+            }
+            let key = self.string()?;
+            let key_span = key_start..self.pos;
+            self.skip_ws();
+            self.expect(b':')?;
+            self.skip_ws();
+            let value = self.value()?;
+            members.push(Member {
+                key,
+                key_span,
+                key_line,
+                value,
+            });
+            self.skip_ws();
+            match self.bytes.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(NodeKind::Object(members));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<NodeKind> {
+        self.expect(b'[')?;
+        let mut elements = Vec::new();
+        self.skip_ws();
+        if self.bytes.get(self.pos) == Some(&b']') {
+            self.pos += 1;
+            return Ok(NodeKind::Array(elements));
+        }
+        loop {
+            self.skip_ws();
+            elements.push(self.value()?);
+            self.skip_ws();
+            match self.bytes.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(NodeKind::Array(elements));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let start = self.pos;
+        self.pos += 1;
+        while let Some(&b) = self.bytes.get(self.pos) {
+            match b {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    self.pos += 1;
+                    // Let serde_json handle escape sequences
+                    return serde_json::from_str(&self.text[start..self.pos])
+                        .map_err(|_| self.error("invalid string"));
+                }
+                _ => self.pos += 1,
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn number(&mut self) -> Result<NodeKind> {
+        let start = self.pos;
+        while let Some(&b) = self.bytes.get(self.pos) {
+            if b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.' | b'e' | b'E') {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        let raw = &self.text[start..self.pos];
+        serde_json::from_str::<serde_json::Number>(raw)
+            .map_err(|_| self.error("invalid number"))?;
+        Ok(NodeKind::Number(raw.to_string()))
+    }
+
+    fn literal(&mut self, word: &str, kind: NodeKind) -> Result<NodeKind> {
+        if self.text[self.pos..].starts_with(word) {
+            self.pos += word.len();
+            Ok(kind)
+        } else {
+            Err(self.error("unexpected literal"))
+        }
+    }
+}
+
+/// Leading whitespace of the line containing `offset`.
+fn indent_at(text: &str, offset: usize) -> &str {
+    let line_start = text[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line = &text[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Indentation unit used by the document (defaults to two spaces).
+fn indent_unit(text: &str) -> String {
+    text.lines()
+        .map(|l| &l[..l.len() - l.trim_start_matches([' ', '\t']).len()])
+        .filter(|i| !i.is_empty())
+        .min_by_key(|i| i.len())
+        .unwrap_or("  ")
+        .to_string()
+}
+
+/// Pretty-print a value, indenting continuation lines by `indent`.
+fn render<T: Serialize + ?Sized>(value: &T, indent: &str, unit: &str) -> Result<String> {
+    let pretty = serde_json::to_string_pretty(value).context("Failed to serialize value")?;
+    let lines: Vec<String> = pretty
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let depth = (line.len() - line.trim_start().len()) / 2;
+            let body = line.trim_start();
+            if i == 0 {
+                body.to_string()
+            } else {
+                format!("{}{}{}", indent, unit.repeat(depth), body)
+            }
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// Replace the value at `node` with `value`, keeping the surrounding text.
+pub fn replace_value<T: Serialize + ?Sized>(
+    text: &str,
+    node: &JsonNode,
+    value: &T,
+) -> Result<String> {
+    let indent = indent_at(text, node.span.start);
+    let rendered = render(value, indent, &indent_unit(text))?;
+    Ok(format!(
+        "{}{}{}",
+        &text[..node.span.start],
+        rendered,
+        &text[node.span.end..]
+    ))
+}
+
+/// Insert a new member at the end of an object node.
+pub fn insert_member<T: Serialize + ?Sized>(
+    text: &str,
+    object: &JsonNode,
+    key: &str,
+    value: &T,
+) -> Result<String> {
+    let members = object.members().context("Expected a JSON object")?;
+    let unit = indent_unit(text);
+    let key_json = serde_json::to_string(key)?;
+
+    match members.last() {
+        Some(last) if last.key_line == object.line => {
+            // Keep single-line objects on one line
+            let at = last.value.span.end;
+            let rendered = serde_json::to_string(value).context("Failed to serialize value")?;
+            Ok(format!(
+                "{}, {}: {}{}",
+                &text[..at],
+                key_json,
+                rendered,
+                &text[at..]
+            ))
+        }
+        Some(last) => {
+            let indent = indent_at(text, last.key_span.start).to_string();
+            let rendered = render(value, &indent, &unit)?;
+            let at = last.value.span.end;
+            Ok(format!(
+                "{},\n{}{}: {}{}",
+                &text[..at],
+                indent,
+                key_json,
+                rendered,
+                &text[at..]
+            ))
+        }
+        None => {
+            let outer = indent_at(text, object.span.start).to_string();
+            let indent = format!("{}{}", outer, unit);
+            let rendered = render(value, &indent, &unit)?;
+            Ok(format!(
+                "{}{{\n{}{}: {}\n{}}}{}",
+                &text[..object.span.start],
+                indent,
+                key_json,
+                rendered,
+                outer,
+                &text[object.span.end..]
+            ))
+        }
+    }
+}
+
+/// Append an element to the end of an array node.
+pub fn push_element<T: Serialize + ?Sized>(
+    text: &str,
+    array: &JsonNode,
+    value: &T,
+) -> Result<String> {
+    let elements = array.elements().context("Expected a JSON array")?;
+    let unit = indent_unit(text);
+
+    match elements.last() {
+        Some(last) if last.line == array.line => {
+            let at = last.span.end;
+            let rendered = serde_json::to_string(value).context("Failed to serialize value")?;
+            Ok(format!("{}, {}{}", &text[..at], rendered, &text[at..]))
+        }
+        Some(last) => {
+            let indent = indent_at(text, last.span.start).to_string();
+            let rendered = render(value, &indent, &unit)?;
+            let at = last.span.end;
+            Ok(format!(
+                "{},\n{}{}{}",
+                &text[..at],
+                indent,
+                rendered,
+                &text[at..]
+            ))
+        }
+        None => {
+            let outer = indent_at(text, array.span.start).to_string();
+            let indent = format!("{}{}", outer, unit);
+            let rendered = render(value, &indent, &unit)?;
+            Ok(format!(
+                "{}[\n{}{}\n{}]{}",
+                &text[..array.span.start],
+                indent,
+                rendered,
+                outer,
+                &text[array.span.end..]
+            ))
+        }
+    }
+}
+
+/// Remove the element at `index` from an array node, along with its separator.
+pub fn remove_element(text: &str, array: &JsonNode, index: usize) -> Result<String> {
+    let elements = array.elements().context("Expected a JSON array")?;
+    let element = elements.get(index).context("Array index out of range")?;
+
+    let range = if elements.len() == 1 {
+        // Leave an empty array behind
+        array.span.start + 1..array.span.end - 1
+    } else if let Some(next) = elements.get(index + 1) {
+        element.span.start..next.span.start
+    } else {
+        elements[index - 1].span.end..element.span.end
+    };
+
+    Ok(format!("{}{}", &text[..range.start], &text[range.end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    const DOC: &str = r#"{
+    "skills": [
+        {"name": "a", "llms_txt_url": "https://a.com/llms.txt"},
+        {"name": "b", "llms_txt_url": "https://b.com/llms.txt"}
+    ],
+    "repository": {
+        "bucket_name": "bucket"
+    }
+}
+"#;
+
+    #[test]
+    fn test_parse_tracks_lines() {
+        let root = parse(DOC).unwrap();
+        let skills = &root.member("skills").unwrap().value;
+        assert_eq!(skills.line, 2);
+        assert_eq!(skills.elements().unwrap()[1].line, 4);
+
+        let bucket = root
+            .member("repository")
+            .unwrap()
+            .value
+            .member("bucket_name")
+            .unwrap();
+        assert_eq!(bucket.key_line, 7);
+        assert_eq!(bucket.value.as_str(), Some("bucket"));
+    }
+
+    #[test]
+    fn test_parse_matches_serde() {
+        let root = parse(DOC).unwrap();
+        assert_eq!(root.members().unwrap().len(), 2);
+        assert!(parse(r#"{"a": "esc\"aped", "n": -1.5e3, "t": true, "z": null}"#).is_ok());
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let err = parse("{\n  \"a\": 1,\n  \"b\" 2\n}").unwrap_err();
+        assert!(err.to_string().contains("line 3"));
+        assert!(parse("{} extra").is_err());
+        assert!(parse("{\"a\": \"unterminated}").is_err());
+    }
+
+    #[test]
+    fn test_replace_value_preserves_rest() {
+        let root = parse(DOC).unwrap();
+        let node = &root
+            .member("repository")
+            .unwrap()
+            .value
+            .member("bucket_name")
+            .unwrap()
+            .value;
+        let edited = replace_value(DOC, node, &json!("other")).unwrap();
+        assert_eq!(edited, DOC.replace("\"bucket\"", "\"other\""));
+    }
+
+    #[test]
+    fn test_insert_member_uses_sibling_indent() {
+        let root = parse(DOC).unwrap();
+        let repo = &root.member("repository").unwrap().value;
+        let edited = insert_member(DOC, repo, "region", &json!("eu-west-1")).unwrap();
+        assert!(edited.contains("\"bucket\",\n        \"region\": \"eu-west-1\"\n    }"));
+        assert!(parse(&edited).is_ok());
+    }
+
+    #[test]
+    fn test_insert_member_into_empty_object() {
+        let text = "{\n  \"repository\": {}\n}\n";
+        let root = parse(text).unwrap();
+        let repo = &root.member("repository").unwrap().value;
+        let edited = insert_member(text, repo, "bucket_name", &json!("b")).unwrap();
+        assert_eq!(
+            edited,
+            "{\n  \"repository\": {\n    \"bucket_name\": \"b\"\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_push_and_remove_elements() {
+        let root = parse(DOC).unwrap();
+        let skills = &root.member("skills").unwrap().value;
+        let edited = push_element(DOC, skills, &json!({"name": "c"})).unwrap();
+        let reparsed: Value = serde_json::from_str(&edited).unwrap();
+        assert_eq!(reparsed["skills"][2]["name"], "c");
+        assert!(edited.contains("\n        {\n            \"name\": \"c\"\n        }\n    ]"));
+
+        let root = parse(DOC).unwrap();
+        let skills = &root.member("skills").unwrap().value;
+        let removed_first = remove_element(DOC, skills, 0).unwrap();
+        let reparsed: Value = serde_json::from_str(&removed_first).unwrap();
+        assert_eq!(reparsed["skills"].as_array().unwrap().len(), 1);
+        assert_eq!(reparsed["skills"][0]["name"], "b");
+
+        let removed_last = remove_element(DOC, skills, 1).unwrap();
+        let reparsed: Value = serde_json::from_str(&removed_last).unwrap();
+        assert_eq!(reparsed["skills"][0]["name"], "a");
+        assert!(removed_last.contains("\"https://a.com/llms.txt\"}\n    ],"));
+    }
+
+    #[test]
+    fn test_remove_only_element_leaves_empty_array() {
+        let text = r#"{"skills": [{"name": "a"}]}"#;
+        let root = parse(text).unwrap();
+        let skills = &root.member("skills").unwrap().value;
+        assert_eq!(
+            remove_element(text, skills, 0).unwrap(),
+            r#"{"skills": []}"#
+        );
+    }
+}
@@ -1,16 +1,34 @@
 //! skill-builder: A CLI tool that builds Claude Code skills from any llms.txt URL.
 
+pub mod adapter;
 pub mod agent;
+pub mod cache;
+pub mod changelog;
 pub mod config;
+pub mod config_file;
+pub mod direct_install;
 pub mod download;
+pub mod fsck;
+pub mod git_storage;
+pub mod github;
+pub mod http_storage;
 pub mod index;
 pub mod init;
 pub mod install;
 pub mod install_resolver;
+pub mod json_doc;
 pub mod local_storage;
+pub mod lockfile;
+pub mod mirror;
+pub mod oci;
+pub mod offline;
 pub mod output;
 pub mod package;
+pub mod release;
 pub mod repository;
 pub mod s3;
+pub mod search;
 pub mod storage;
+pub mod suggest;
 pub mod validate;
+pub mod version_range;
@@ -1,10 +1,15 @@
 //! Local filesystem implementation of StorageOperations.
 
 use anyhow::{Context, Result};
-use std::fs;
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 
-use crate::storage::StorageOperations;
+use crate::storage::{
+    content_type_for_key, ObjectMetadata, PreconditionFailed, PutCondition, StorageOperations,
+};
 
 /// Filesystem-backed storage client implementing StorageOperations.
 ///
@@ -44,17 +49,33 @@ impl LocalStorageClient {
     fn key_to_path(&self, key: &str) -> PathBuf {
         self.root.join(key)
     }
+
+    /// Write `key` from a reader via a temporary file renamed into place, so
+    /// readers never see a partly written object.
+    fn write_atomic(&self, key: &str, reader: &mut dyn Read) -> Result<()> {
+        let path = self.key_to_path(key);
+        let parent = path.parent().unwrap_or(&self.root);
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let tmp = parent.join(format!(".{}.tmp-{}", file_name, std::process::id()));
+        let result = fs::File::create(&tmp)
+            .and_then(|mut file| io::copy(reader, &mut file).and_then(|_| file.sync_all()))
+            .and_then(|_| fs::rename(&tmp, &path));
+        if result.is_err() {
+            fs::remove_file(&tmp).ok();
+        }
+        result.with_context(|| format!("Failed to write: {}", path.display()))
+    }
 }
 
 impl StorageOperations for LocalStorageClient {
     fn put_object(&self, key: &str, data: &[u8]) -> Result<()> {
-        let path = self.key_to_path(key);
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
-        }
-        fs::write(&path, data).with_context(|| format!("Failed to write: {}", path.display()))?;
-        Ok(())
+        self.write_atomic(key, &mut &data[..])
     }
 
     fn get_object(&self, key: &str) -> Result<Vec<u8>> {
@@ -103,7 +124,7 @@ impl StorageOperations for LocalStorageClient {
                         for entry in fs::read_dir(parent)? {
                             let entry = entry?;
                             let name = entry.file_name().to_string_lossy().to_string();
-                            if name.starts_with(&prefix_name) {
+                            if name.starts_with(&prefix_name) && !is_internal_file(&name) {
                                 let rel = entry
                                     .path()
                                     .strip_prefix(&self.root)
@@ -138,6 +159,133 @@ impl StorageOperations for LocalStorageClient {
     fn object_exists(&self, key: &str) -> Result<bool> {
         Ok(self.key_to_path(key).is_file())
     }
+
+    fn head_object(&self, key: &str) -> Result<Option<ObjectMetadata>> {
+        let path = self.key_to_path(key);
+        let file = match fs::File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to open: {}", path.display())),
+        };
+        if !file.metadata()?.is_file() {
+            return Ok(None);
+        }
+        file_metadata(key, &path, file).map(Some)
+    }
+
+    fn get_object_with_metadata(&self, key: &str) -> Result<(Vec<u8>, ObjectMetadata)> {
+        let path = self.key_to_path(key);
+        let data = fs::read(&path).with_context(|| format!("Object not found: {}", key))?;
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+        let metadata = ObjectMetadata {
+            last_modified: modified.map(Into::into),
+            ..ObjectMetadata::for_data(key, &data)
+        };
+        Ok((data, metadata))
+    }
+
+    fn get_object_reader(&self, key: &str) -> Result<Box<dyn Read + '_>> {
+        let path = self.key_to_path(key);
+        let file = fs::File::open(&path).with_context(|| format!("Object not found: {}", key))?;
+        Ok(Box::new(file))
+    }
+
+    fn put_object_from_reader(&self, key: &str, reader: &mut dyn Read) -> Result<()> {
+        self.write_atomic(key, reader)
+    }
+
+    fn put_object_if(
+        &self,
+        key: &str,
+        data: &[u8],
+        condition: &PutCondition,
+    ) -> Result<ObjectMetadata> {
+        // Other processes may be writing the same key; hold its lock from check to rename
+        let _lock = KeyLock::acquire(&self.key_to_path(key))?;
+        if !condition.is_met(self.head_object(key)?.as_ref()) {
+            return Err(PreconditionFailed {
+                key: key.to_string(),
+            }
+            .into());
+        }
+        self.write_atomic(key, &mut &data[..])?;
+        Ok(self
+            .head_object(key)?
+            .unwrap_or_else(|| ObjectMetadata::for_data(key, data)))
+    }
+}
+
+/// How long to wait for another writer's lock.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Age after which a lock is assumed to be left over from a crashed process.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(30);
+
+/// Exclusive lock on a key, held as a hidden `.<file>.lock` next to it.
+struct KeyLock {
+    path: PathBuf,
+}
+
+impl KeyLock {
+    fn acquire(object_path: &Path) -> Result<Self> {
+        let parent = object_path.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        let file_name = object_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let path = parent.join(format!(".{}.lock", file_name));
+
+        let deadline = Instant::now() + LOCK_TIMEOUT;
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(Self { path }),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    let age = fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|t| SystemTime::now().duration_since(t).ok());
+                    if age.is_some_and(|age| age > STALE_LOCK_AGE) {
+                        fs::remove_file(&path).ok();
+                        continue;
+                    }
+                    if Instant::now() > deadline {
+                        anyhow::bail!("Timed out waiting for lock: {}", path.display());
+                    }
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+                Err(e) => {
+                    return Err(e)
+                        .with_context(|| format!("Failed to create lock: {}", path.display()))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for KeyLock {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path).ok();
+    }
+}
+
+/// Whether a file name is one of this client's lock or temporary files.
+fn is_internal_file(name: &str) -> bool {
+    name.starts_with('.') && (name.ends_with(".lock") || name.contains(".tmp-"))
+}
+
+/// Metadata of a stored file; the ETag is a hash of its content.
+fn file_metadata(key: &str, path: &Path, mut file: fs::File) -> Result<ObjectMetadata> {
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("Failed to read: {}", path.display()))?;
+    Ok(ObjectMetadata {
+        size,
+        etag: hex::encode(hasher.finalize()),
+        last_modified: file.metadata()?.modified().ok().map(Into::into),
+        content_type: Some(content_type_for_key(key).to_string()),
+    })
 }
 
 fn collect_files_recursive(dir: &Path, root: &Path, keys: &mut Vec<String>) -> Result<()> {
@@ -149,7 +297,7 @@ fn collect_files_recursive(dir: &Path, root: &Path, keys: &mut Vec<String>) -> R
         let path = entry.path();
         if path.is_dir() {
             collect_files_recursive(&path, root, keys)?;
-        } else {
+        } else if !is_internal_file(&entry.file_name().to_string_lossy()) {
             let rel = path
                 .strip_prefix(root)
                 .map(|p| p.to_string_lossy().to_string())
@@ -234,6 +382,86 @@ mod tests {
         assert!(keys.is_empty());
     }
 
+    #[test]
+    fn test_metadata_and_streaming() {
+        let tmp = TempDir::new().unwrap();
+        let client = LocalStorageClient::new(tmp.path().join("store").as_path()).unwrap();
+
+        assert!(client.head_object("skills_index.json").unwrap().is_none());
+        client
+            .put_object_from_reader("skills_index.json", &mut &b"{}"[..])
+            .unwrap();
+
+        let meta = client.head_object("skills_index.json").unwrap().unwrap();
+        assert_eq!(meta.size, 2);
+        assert_eq!(meta.content_type.as_deref(), Some("application/json"));
+        assert!(meta.last_modified.is_some());
+        let (_, read_meta) = client
+            .get_object_with_metadata("skills_index.json")
+            .unwrap();
+        assert_eq!(read_meta.etag, meta.etag);
+
+        let mut data = String::new();
+        client
+            .get_object_reader("skills_index.json")
+            .unwrap()
+            .read_to_string(&mut data)
+            .unwrap();
+        assert_eq!(data, "{}");
+    }
+
+    #[test]
+    fn test_conditional_put() {
+        let tmp = TempDir::new().unwrap();
+        let client = LocalStorageClient::new(tmp.path().join("store").as_path()).unwrap();
+
+        let first = client
+            .put_object_if("key", b"one", &PutCondition::IfNoneMatch)
+            .unwrap();
+        let err = client
+            .put_object_if("key", b"two", &PutCondition::IfNoneMatch)
+            .unwrap_err();
+        assert!(crate::storage::is_precondition_failed(&err));
+
+        client
+            .put_object_if("key", b"two", &PutCondition::IfMatch(first.etag.clone()))
+            .unwrap();
+        let err = client
+            .put_object_if("key", b"three", &PutCondition::IfMatch(first.etag))
+            .unwrap_err();
+        assert!(crate::storage::is_precondition_failed(&err));
+        assert_eq!(client.get_object("key").unwrap(), b"two");
+    }
+
+    #[test]
+    fn test_conditional_put_waits_for_lock() {
+        let tmp = TempDir::new().unwrap();
+        let client = LocalStorageClient::new(tmp.path().join("store").as_path()).unwrap();
+        let path = client.key_to_path("key");
+
+        let lock = KeyLock::acquire(&path).unwrap();
+        assert!(tmp.path().join("store/.key.lock").exists());
+        assert!(client.list_objects("").unwrap().is_empty());
+
+        let writer = std::thread::spawn({
+            let root = client.root().to_path_buf();
+            move || {
+                LocalStorageClient::with_dir(&root).put_object_if(
+                    "key",
+                    b"data",
+                    &PutCondition::IfNoneMatch,
+                )
+            }
+        });
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!client.object_exists("key").unwrap());
+
+        drop(lock);
+        writer.join().unwrap().unwrap();
+        assert_eq!(client.get_object("key").unwrap(), b"data");
+        assert!(!tmp.path().join("store/.key.lock").exists());
+    }
+
     #[test]
     fn test_delete_nonexistent_is_ok() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,98 @@
+//! The install lockfile, `skills.lock`, recording where skills installed
+//! straight from a URL or git ref came from.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// File name of the lockfile, kept in the project root.
+pub const LOCKFILE: &str = "skills.lock";
+
+/// Contents of `skills.lock`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Lockfile {
+    /// Locked skills by name.
+    #[serde(default)]
+    pub skills: BTreeMap<String, LockedSkill>,
+}
+
+/// Where an installed skill came from.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LockedSkill {
+    /// Version from the package's SKILL.md, if it records one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Source as given to `sb install`: a package URL or
+    /// `git+<url>#<ref>:<path>`.
+    pub source: String,
+
+    /// Commit a git source's ref resolved to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+
+    /// SHA-256 of the installed package.
+    pub sha256: String,
+}
+
+impl Lockfile {
+    /// Load a lockfile. A missing file is an empty lockfile.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    /// Write the lockfile as pretty-printed JSON.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut content = serde_json::to_string_pretty(self)?;
+        content.push('\n');
+        fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Record `skill` in the lockfile at `path`, replacing any earlier entry.
+    pub fn record(path: &Path, name: &str, skill: LockedSkill) -> Result<()> {
+        let mut lockfile = Self::load(path)?;
+        lockfile.skills.insert(name.to_string(), skill);
+        lockfile.save(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn locked(source: &str) -> LockedSkill {
+        LockedSkill {
+            version: Some("1.0.0".to_string()),
+            source: source.to_string(),
+            commit: None,
+            sha256: "abc".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_record_adds_and_replaces_entries() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(LOCKFILE);
+        assert_eq!(Lockfile::load(&path).unwrap(), Lockfile::default());
+
+        Lockfile::record(&path, "a", locked("https://example.com/a.skill")).unwrap();
+        Lockfile::record(&path, "b", locked("https://example.com/b.skill")).unwrap();
+        Lockfile::record(&path, "a", locked("git+https://example.com/a.git#v2:a")).unwrap();
+
+        let lockfile = Lockfile::load(&path).unwrap();
+        assert_eq!(lockfile.skills.len(), 2);
+        assert_eq!(
+            lockfile.skills["a"].source,
+            "git+https://example.com/a.git#v2:a"
+        );
+        assert!(!fs::read_to_string(&path).unwrap().contains("commit"));
+    }
+}
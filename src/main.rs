@@ -5,15 +5,37 @@ use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 use std::process;
 
-use skill_builder::config::Config;
+use skill_builder::adapter::{adapt_installed_skill, AdapterContext};
+use skill_builder::agent::InstallTarget;
+use skill_builder::cache::{CachePolicy, SkillCache};
+use skill_builder::changelog::{
+    find_section, initial_section, parse_sections, prepend_section, versions_between, SkillDiff,
+};
+use skill_builder::config::{Config, LocalRepositoryConfig, RepositoryConfig, SkillConfig};
+use skill_builder::config_file::{self, validate_config_text};
+use skill_builder::direct_install::{self, DirectSource};
 use skill_builder::download::{download_from_url, download_skill_docs};
-use skill_builder::index::load_index;
-use skill_builder::install::install_from_file;
+use skill_builder::github::{
+    find_signature, list_release_skills, publish_release, GitHubClient, GitHubReleaseStorage,
+    PublishParams,
+};
+use skill_builder::index::{compare_semver, load_index, IndexEntry, SkillsIndex, INDEX_KEY};
+use skill_builder::install::{
+    install_from_bytes, install_from_file, installed_skills, DEFAULT_REPO,
+};
 use skill_builder::local_storage::LocalStorageClient;
+use skill_builder::lockfile::{Lockfile, LOCKFILE};
+use skill_builder::mirror::MirrorOptions;
+use skill_builder::oci::{OciReference, OciStorageClient};
+use skill_builder::offline;
 use skill_builder::output::Output;
+use skill_builder::package::{list_skill_contents, read_package_files, read_skill_files};
+use skill_builder::release::{next_version, write_skill_version, Bump};
 use skill_builder::repository::{Repository, UploadParams};
+use skill_builder::search::{rank, search_index};
 use skill_builder::storage::StorageOperations;
-use skill_builder::validate::{print_validation_result, validate_skill};
+use skill_builder::validate::{parse_frontmatter, print_validation_result, validate_skill};
+use skill_builder::version_range::VersionRange;
 
 /// Build Claude Code skills from llms.txt URLs.
 #[derive(Parser)]
@@ -35,6 +57,16 @@ struct Cli {
     #[arg(long, global = true)]
     agent_output: bool,
 
+    /// Never touch the network: only use the local repository, cache and files
+    /// [env: SB_OFFLINE=1]
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Project root for agent detection and project-level installs
+    /// [default: nearest ancestor with agent markers, skills.json, skills.lock or .git]
+    #[arg(long, global = true)]
+    project_root: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -101,18 +133,18 @@ enum Commands {
 
     /// Install a skill from local repo, remote repo, or GitHub releases
     #[command(
-        long_about = "Install a skill from the local repository, remote S3 repository, or GitHub releases.\n\nBy default, searches local repo → remote repo → GitHub releases in order.\nUse --local, --remote, or --github to restrict to a single source.\nAlternatively, use --file to install from a local .skill file directly.\n\nSkills are installed to all detected agent directories by default.\nUse --agent to target a specific agent, or --install-dir to override.",
-        after_help = "Examples:\n  sb install my-skill\n  sb install my-skill --version 1.0.0\n  sb install my-skill --local\n  sb install my-skill --remote\n  sb install my-skill --github --repo user/repo\n  sb install my-skill --file ./dist/my-skill.skill\n  sb install my-skill --install-dir ~/.claude/skills\n  sb install my-skill --agent codex\n  sb install my-skill --agent all\n  sb install my-skill --global"
+        long_about = "Install a skill from the local repository, remote S3 repository, or GitHub releases.\n\nBy default, searches local repo → remote repo → GitHub releases in order.\nThe 'releases' config section swaps GitHub for GitHub Enterprise, GitLab or Gitea.\nUse --local, --remote, or --github to restrict to a single source.\nAlternatively, use --file to install from a local .skill file directly, or pass\na package URL or git+<url>#<ref>:<path> instead of a name to install from\nthere; such installs are recorded in skills.lock.\n\nSkills are installed to all detected agent directories by default.\nUse --agent to target a specific agent, or --install-dir to override.",
+        after_help = "Examples:\n  sb install my-skill\n  sb install my-skill --version 1.0.0\n  sb install my-skill --local\n  sb install my-skill --remote\n  sb install my-skill --github --repo user/repo\n  sb install my-skill --file ./dist/my-skill.skill\n  sb install https://example.com/my-skill.skill\n  sb install git+https://github.com/user/repo#v1.0.0:skills/my-skill\n  sb install my-skill --install-dir ~/.claude/skills\n  sb install my-skill --agent codex\n  sb install my-skill --agent all\n  sb install my-skill --global"
     )]
     Install {
-        /// Name of the skill to install
+        /// Name of the skill to install, a .skill URL, or git+<url>#<ref>:<path>
         skill: String,
 
         /// Specific version to install (default: latest)
         #[arg(short, long)]
         version: Option<String>,
 
-        /// GitHub repository (owner/repo)
+        /// Release project: GitHub owner/repo, or a GitLab or Gitea project path
         #[arg(long)]
         repo: Option<String>,
 
@@ -128,10 +160,14 @@ enum Commands {
         #[arg(long, conflicts_with_all = ["local", "github", "file"])]
         remote: bool,
 
-        /// Install from GitHub releases only
+        /// Install from releases only (GitHub, or the configured release host)
         #[arg(long, conflicts_with_all = ["local", "remote", "file"])]
         github: bool,
 
+        /// Install the replacement of a deprecated skill instead
+        #[arg(long, conflicts_with_all = ["version", "file"])]
+        follow_replaced: bool,
+
         /// Installation directory (overrides agent detection)
         #[arg(long)]
         install_dir: Option<PathBuf>,
@@ -151,16 +187,176 @@ enum Commands {
     )]
     List,
 
+    /// Show a skill's versions, changelog and packaged files
+    #[command(
+        long_about = "Show everything a repository knows about a skill.\n\nPrints the index metadata, every version with its publish date and size, the\nchangelog section for the requested version (default: latest) and the files\nin its package. With --since, prints the changelog of every version after the\ngiven one instead, e.g. to see what changed since the installed version.\n\nLooks in the local repository, then each configured repository in priority\norder, unless --repo names one.",
+        after_help = "Examples:\n  sb info my-skill\n  sb info my-skill --version 1.0.0\n  sb info my-skill --since 1.0.0\n  sb info my-skill --repo community"
+    )]
+    Info {
+        /// Name of the skill
+        skill: String,
+
+        /// Version to describe (default: latest)
+        #[arg(short, long)]
+        version: Option<String>,
+
+        /// Show the changelog of every version after this one, e.g. the installed version
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Repository to look in, by name (`local` for the local repository)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Look in the GitHub releases of a repository (default: the official skills)
+        #[arg(
+            long,
+            value_name = "OWNER/REPO",
+            num_args = 0..=1,
+            default_missing_value = DEFAULT_REPO,
+            conflicts_with = "repo"
+        )]
+        github: Option<String>,
+    },
+
+    /// Compare installed skills with the latest published versions
+    #[command(
+        long_about = "Compare installed skills with the latest published versions.\n\nReads the version of each skill in the install directories from its SKILL.md\nand looks the skill up in the local repository, then each configured\nrepository in priority order, unless --repo names one. Deprecated skills are\nreported with their deprecation message and the skill that replaces them.",
+        after_help = "Examples:\n  sb outdated\n  sb outdated --global\n  sb outdated --agent codex --repo community"
+    )]
+    Outdated {
+        /// Repository to look in, by name (`local` for the local repository)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Installation directory to check (overrides agent detection)
+        #[arg(long)]
+        install_dir: Option<PathBuf>,
+
+        /// Target agent framework: claude, opencode, codex, kiro, or all
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Check global agent directories instead of project-level
+        #[arg(long)]
+        global: bool,
+    },
+
+    /// Generate a changelog section from changes since a published version
+    #[command(
+        long_about = "Generate a Keep a Changelog section for a skill.\n\nCompares the skill directory with a previously published package (default: the\nlatest) and lists added, removed and modified reference docs and SKILL.md\nchanges. The section is printed to stdout, so it can be pasted into or\nredirected to a CHANGELOG.md. `sb repo upload --changelog auto` and\n`sb local publish --changelog auto` generate the same text at upload time.",
+        after_help = "Examples:\n  sb changelog my-skill\n  sb changelog my-skill --since 1.0.2 --version 1.0.3\n  sb changelog ./path/to/skill --repo community"
+    )]
+    Changelog {
+        /// Name of the skill, or path to the skill directory
+        skill: String,
+
+        /// Published version to compare against (default: latest)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Version to head the section with (default: Unreleased)
+        #[arg(short, long)]
+        version: Option<String>,
+
+        /// Directory containing skills
+        #[arg(long, default_value = "skills")]
+        skills_dir: PathBuf,
+
+        /// Repository to compare against, by name (`local` for the local repository)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// Bump a skill's version, then package and upload it
+    #[command(
+        long_about = "Release the next version of a skill.\n\nReads the latest published version from the repository's index (or the\nversion in SKILL.md for a first release), bumps it, writes the new version\ninto the SKILL.md frontmatter, then validates, packages and uploads the skill.\nUse --version to pick the version yourself. A version that is already\npublished is only replaced with --force.\n\nUploads go to the first writable repository, or the one named by --repo\n(`local` for the local repository).",
+        after_help = "Examples:\n  sb release my-skill --bump patch\n  sb release my-skill --bump minor --changelog auto\n  sb release my-skill --version 2.0.0 --repo local\n  sb release my-skill --version 1.0.3 --force"
+    )]
+    Release {
+        /// Name of the skill to release, or path to skill directory
+        skill: String,
+
+        /// Version part to increment: major, minor or patch
+        #[arg(long, required_unless_present = "version", conflicts_with = "version")]
+        bump: Option<Bump>,
+
+        /// Release this exact version instead of bumping
+        #[arg(short, long)]
+        version: Option<String>,
+
+        /// Overwrite the version if it is already published
+        #[arg(long)]
+        force: bool,
+
+        /// Path to a CHANGELOG.md file to include, or `auto` to generate one
+        #[arg(long)]
+        changelog: Option<PathBuf>,
+
+        /// Path to source directory to archive and upload
+        #[arg(long)]
+        source_dir: Option<PathBuf>,
+
+        /// Directory containing skills
+        #[arg(long, default_value = "skills")]
+        skills_dir: PathBuf,
+
+        /// Output directory for the .skill file
+        #[arg(short, long, default_value = "dist")]
+        output: PathBuf,
+
+        /// Repository to release to, by name (`local` for the local repository)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// Search skills by keyword across repositories and GitHub releases
+    #[command(
+        long_about = "Search skills by keyword across every source sb installs from.\n\nSearches the local repository, each configured repository and the GitHub\nreleases of --repo. Results are ranked by how well the query matches the\nskill's name, tags, description and llms.txt URL; every word of the query has\nto match. Sources that can't be reached are skipped with a warning.",
+        after_help = "Examples:\n  sb search svelte\n  sb search \"ui components\"\n  sb search svelte --no-github\n  sb search svelte --repo user/repo --limit 5"
+    )]
+    Search {
+        /// Words to look for
+        #[arg(required = true, num_args = 1..)]
+        query: Vec<String>,
+
+        /// GitHub repository (owner/repo) whose releases are searched
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Don't search GitHub releases
+        #[arg(long, conflicts_with = "repo")]
+        no_github: bool,
+
+        /// Show at most this many results
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+
     /// Manage the S3-compatible skill repository
     #[command(
-        long_about = "Manage skills in an S3-compatible hosted repository.\n\nRequires a 'repository' section in skills.json with bucket_name and optional\nregion/endpoint. Authentication uses the standard AWS credential chain\n(environment variables, ~/.aws/credentials, IAM roles).",
-        after_help = "Examples:\n  sb repo upload my-skill 1.0.0\n  sb repo download my-skill --version 1.0.0\n  sb repo install my-skill\n  sb repo delete my-skill --yes\n  sb repo list"
+        long_about = "Manage skills in an S3-compatible hosted repository.\n\nRequires a 'repository' section or 'repositories' list in skills.json with\nbucket_name and optional region/endpoint. Authentication uses the standard AWS\ncredential chain (environment variables, ~/.aws/credentials, IAM roles), or\nthe repository's credentials profile.\n\nWith several repositories, --repo selects one by name. Otherwise uploads and\ndeletes go to the first writable repository, and downloads, installs and\nlistings search all repositories in priority order.",
+        after_help = "Examples:\n  sb repo upload my-skill 1.0.0\n  sb repo upload my-skill 1.0.0 --repo community\n  sb repo download my-skill --version 1.0.0\n  sb repo install my-skill\n  sb repo yank my-skill 1.0.0 --reason \"broken links\"\n  sb repo delete my-skill --purge --yes\n  sb repo list"
     )]
     Repo {
+        /// Repository to use, by name (default: all, in priority order)
+        #[arg(long, global = true)]
+        repo: Option<String>,
+
         #[command(subcommand)]
         action: RepoAction,
     },
 
+    /// Publish skills to GitHub Releases
+    #[command(
+        long_about = "Publish skills to GitHub Releases through the GitHub REST API.\n\nAuthenticates with a token from GITHUB_TOKEN or GH_TOKEN, which needs\npermission to write the repository's contents. Set SB_GITHUB_API_URL to use\nanother API endpoint.",
+        after_help = "Examples:\n  sb github publish my-skill 1.0.0 --repo user/repo"
+    )]
+    Github {
+        #[command(subcommand)]
+        action: GithubAction,
+    },
+
     /// Manage the local skill repository
     #[command(
         long_about = "Manage the local skill repository.\n\nSkills can be stored locally for offline access or as a cache for the remote\nrepository. Local repository is stored at $HOME/.skill-builder/local/ by default.",
@@ -171,6 +367,16 @@ enum Commands {
         action: LocalAction,
     },
 
+    /// Inspect the effective configuration
+    #[command(
+        long_about = "Inspect the effective configuration.\n\nConfiguration is layered: built-in defaults, then the global config at\n$HOME/.skill-builder/skills.config.json, then the project skills.json, then\nthe --config file, then SB_* environment variables. Later layers override\nearlier ones field by field.\n\nThe validate, add-skill, remove-skill and set subcommands check and edit a\nsingle config file in place, keeping its formatting and key order.",
+        after_help = "Examples:\n  sb config show --origin\n  sb config validate\n  sb config add-skill svelte --url https://svelte.dev/llms.txt\n  sb config set repository.bucket_name my-skills"
+    )]
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
     /// Initialize global configuration
     #[command(
         long_about = "Initialize the global skill-builder configuration.\n\nCreates a configuration file at $HOME/.skill-builder/skills.config.json with\noptions for setting up a local skill repository. Run this once to get started.",
@@ -183,7 +389,7 @@ enum Commands {
 enum RepoAction {
     /// Upload a skill to the repository
     #[command(
-        long_about = "Upload a .skill file to the S3 repository.\n\nIf --file is not specified, defaults to dist/<skill>.skill. Skill metadata\n(description, llms_txt_url) is read from skills.json if available.\nOptionally include a CHANGELOG.md and/or archive the source directory.",
+        long_about = "Upload a .skill file to the S3 repository.\n\nIf --file is not specified, defaults to dist/<skill>.skill. Skill metadata\n(description, llms_txt_url) is read from skills.json if available.\nOptionally include a CHANGELOG.md and/or archive the source directory.\nA version that is already published is only replaced with --force.",
         after_help = "Examples:\n  sb repo upload my-skill 1.0.0\n  sb repo upload my-skill 1.0.0 --file ./my-skill.skill\n  sb repo upload my-skill 1.0.0 --changelog CHANGELOG.md --source-dir ./source"
     )]
     Upload {
@@ -197,10 +403,14 @@ enum RepoAction {
         #[arg(long)]
         file: Option<PathBuf>,
 
-        /// Path to a CHANGELOG.md file to include
+        /// Path to a CHANGELOG.md file to include, or `auto` to generate one
         #[arg(long)]
         changelog: Option<PathBuf>,
 
+        /// Overwrite the version if it is already published
+        #[arg(long)]
+        force: bool,
+
         /// Path to source directory to archive and upload
         #[arg(long)]
         source_dir: Option<PathBuf>,
@@ -252,8 +462,8 @@ enum RepoAction {
 
     /// Delete a skill from the repository
     #[command(
-        long_about = "Delete a skill (or a specific version) from the S3 repository.\n\nRemoves the .skill file, changelog, and source archive from S3 and updates\nthe skills index. Also clears matching entries from the local cache.\nRequires --yes to confirm.",
-        after_help = "Examples:\n  sb repo delete my-skill --yes\n  sb repo delete my-skill --version 1.0.0 --yes"
+        long_about = "Delete a skill (or a specific version) from the S3 repository.\n\nRemoves the .skill file, changelog, and source archive from S3 and updates\nthe skills index. Also clears matching entries from the local cache.\nPublished versions are immutable: prefer `sb repo yank`, which keeps the\nversion installable by exact pin. Deleting requires --purge, and --yes to\nconfirm.",
+        after_help = "Examples:\n  sb repo delete my-skill --purge --yes\n  sb repo delete my-skill --version 1.0.0 --purge --yes"
     )]
     Delete {
         /// Skill name
@@ -263,11 +473,67 @@ enum RepoAction {
         #[arg(long)]
         version: Option<String>,
 
+        /// Really delete the files instead of yanking
+        #[arg(long)]
+        purge: bool,
+
         /// Skip confirmation prompt
         #[arg(long)]
         yes: bool,
     },
 
+    /// Yank a version so it is only installed when pinned
+    #[command(
+        long_about = "Yank a published version of a skill.\n\nA yanked version stays in the repository but is skipped when resolving the\nlatest version or a version range. It can still be installed by its exact\nversion, with a warning that shows the reason.",
+        after_help = "Examples:\n  sb repo yank my-skill 1.0.0\n  sb repo yank my-skill 1.0.0 --reason \"broken reference links\""
+    )]
+    Yank {
+        /// Skill name
+        skill: String,
+
+        /// Version to yank
+        version: String,
+
+        /// Why the version was yanked, shown to anyone who installs it
+        #[arg(long)]
+        reason: Option<String>,
+    },
+
+    /// Mark a skill as deprecated, optionally naming its replacement
+    #[command(
+        long_about = "Mark a skill as deprecated in the repository index.\n\nInstalling a deprecated skill prints a warning with the message, and `sb\noutdated` reports it. With --replaced-by, the warning names the skill that\nsupersedes it, and `sb install --follow-replaced` installs that skill instead.\nUse --undo to clear the deprecation.",
+        after_help = "Examples:\n  sb repo deprecate old-skill --replaced-by new-skill\n  sb repo deprecate old-skill --message \"no longer maintained\"\n  sb repo deprecate old-skill --undo"
+    )]
+    Deprecate {
+        /// Skill name
+        skill: String,
+
+        /// Why the skill is deprecated, shown to anyone who installs it
+        #[arg(long)]
+        message: Option<String>,
+
+        /// Skill that supersedes this one
+        #[arg(long)]
+        replaced_by: Option<String>,
+
+        /// Clear the deprecation
+        #[arg(long, conflicts_with_all = ["message", "replaced_by"])]
+        undo: bool,
+    },
+
+    /// Undo a yank
+    #[command(
+        long_about = "Make a yanked version of a skill available to version resolution again.",
+        after_help = "Examples:\n  sb repo unyank my-skill 1.0.0"
+    )]
+    Unyank {
+        /// Skill name
+        skill: String,
+
+        /// Version to restore
+        version: String,
+    },
+
     /// List skills in the repository
     #[command(
         long_about = "List all skills in the S3 repository.\n\nDisplays each skill's name, description, source URL, and available versions.\nOptionally filter to a single skill.",
@@ -278,110 +544,387 @@ enum RepoAction {
         #[arg(long)]
         skill: Option<String>,
     },
-}
 
-#[derive(Subcommand)]
-enum LocalAction {
-    /// List all skills in the local repository
+    /// Check the repository index against the stored objects
     #[command(
-        long_about = "List all skills and versions stored in the local repository.\n\nShows each skill name and version, plus the repository directory path."
+        long_about = "Check that the skills index and the stored objects agree.\n\nReports orphan objects, index entries pointing at missing keys, checksum\nmismatches and unreadable packages. With --fix, rebuilds the index from the\npackages' SKILL.md manifests. Without --repo, checks every repository.",
+        after_help = "Examples:\n  sb repo fsck\n  sb repo fsck --fix --repo company"
     )]
-    List,
+    Fsck {
+        /// Rebuild the index to fix what can be fixed
+        #[arg(long)]
+        fix: bool,
+    },
 
-    /// Clear skills from the local repository
+    /// Rebuild the repository index from the stored packages
     #[command(
-        long_about = "Clear skills from the local repository.\n\nRemoves all locally stored skills, or only a specific skill if --skill is provided.",
-        after_help = "Examples:\n  sb local clear\n  sb local clear --skill my-skill"
+        long_about = "Rebuild skills_index.json from the packages in the repository.\n\nNames and descriptions are read from each package's SKILL.md; source URLs are\nkept from the old index where it has them.",
+        after_help = "Examples:\n  sb repo reindex\n  sb repo reindex --repo company"
     )]
-    Clear {
-        /// Only clear a specific skill (default: clear all)
+    Reindex,
+
+    /// Copy skills from one repository to another
+    #[command(
+        long_about = "Copy skills from one repository to another.\n\nCopies packages, changelogs, source archives and index entries between any\ntwo configured repositories, whatever their backends. Use `local` for the\nlocal repository. Objects the destination already has with the same SHA-256\nare skipped, so an interrupted mirror can be re-run.\n\nVersion ranges are comma-separated comparators such as `>=1.0.0, <2.0.0`,\n`^1.2`, `~1.2.3` or `1.x`.",
+        after_help = "Examples:\n  sb repo mirror --from public --to local\n  sb repo mirror --from staging --to production --skill my-skill --versions 1.2.0\n  sb repo mirror --from public --to local --versions '>=2.0.0' --dry-run"
+    )]
+    Mirror {
+        /// Repository to copy from (`local` for the local repository)
+        #[arg(long)]
+        from: String,
+
+        /// Repository to copy to (`local` for the local repository)
+        #[arg(long)]
+        to: String,
+
+        /// Only mirror this skill
         #[arg(long)]
         skill: Option<String>,
-    },
-}
 
-fn main() {
-    if let Err(e) = run() {
-        eprintln!("Error: {:#}", e);
-        process::exit(1);
-    }
-}
+        /// Only mirror versions in this range (e.g. ">=1.0.0, <2.0.0")
+        #[arg(long)]
+        versions: Option<VersionRange>,
 
-fn run() -> Result<()> {
-    let cli = Cli::parse();
-    let output = Output::new(cli.agent_output);
+        /// Show what would be copied without copying it
+        #[arg(long)]
+        dry_run: bool,
+    },
 
-    match cli.command {
-        Commands::Download {
-            skill_name,
-            all,
-            url,
-            name,
-            source_dir,
-        } => {
-            // Handle --url override (no config needed)
-            if let Some(url) = url {
-                let name = name.context("--name is required when using --url")?;
-                output.info(&format!("Downloading from URL: {}", url));
-                output.step(&format!("Skill name: {}", name));
-                output.newline();
+    /// Push a skill to an OCI registry
+    #[command(
+        long_about = "Push a .skill file to an OCI registry as an artifact.\n\nThe reference names the registry, namespace, skill and version tag. The\nSKILL.md frontmatter is recorded as manifest annotations, and the skills index\nin the namespace is updated. Credentials are read from SB_OCI_USERNAME and\nSB_OCI_PASSWORD.",
+        after_help = "Examples:\n  sb repo push oci://ghcr.io/acme/skills/my-skill:1.2.0\n  sb repo push oci://localhost:5000/my-skill:1.0.0 --file ./my-skill.skill"
+    )]
+    Push {
+        /// Artifact reference, e.g. oci://ghcr.io/acme/skills/my-skill:1.2.0
+        reference: String,
 
-                let results = download_from_url(&url, &name, &source_dir, &output)?;
-                let failures: Vec<_> = results.iter().filter(|r| !r.success).collect();
+        /// Path to the .skill file [default: dist/<skill>.skill]
+        #[arg(long)]
+        file: Option<PathBuf>,
 
-                if !failures.is_empty() {
-                    anyhow::bail!("{} files failed to download", failures.len());
-                }
+        /// Path to a CHANGELOG.md file to include, or `auto` to generate one
+        #[arg(long)]
+        changelog: Option<PathBuf>,
 
-                return Ok(());
-            }
+        /// Overwrite the version if it is already published
+        #[arg(long)]
+        force: bool,
+    },
 
-            // Load config
-            let config = Config::load_with_fallback(cli.config.as_deref())?;
+    /// Pull a skill from an OCI registry
+    #[command(
+        long_about = "Pull a .skill file from an OCI registry.\n\nWithout a tag, pulls the highest version tag. Writes <skill>.skill to the\noutput directory.",
+        after_help = "Examples:\n  sb repo pull oci://ghcr.io/acme/skills/my-skill:1.2.0\n  sb repo pull oci://ghcr.io/acme/skills/my-skill --output ./downloads"
+    )]
+    Pull {
+        /// Artifact reference, e.g. oci://ghcr.io/acme/skills/my-skill:1.2.0
+        reference: String,
 
-            if all {
-                // Download all skills
-                output.header(&format!(
-                    "Downloading all {} skills...",
-                    config.skills.len()
-                ));
-                output.newline();
+        /// Output directory [default: current directory]
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
 
-                for skill in &config.skills {
-                    output.header(&format!("=== {} ===", skill.name));
-                    if let Err(e) = download_skill_docs(skill, &source_dir, &output) {
-                        output.error(&format!("Failed to download {}: {}", skill.name, e));
-                    }
-                    output.newline();
-                }
-            } else if let Some(name) = skill_name {
-                // Download specific skill
-                let skill = config
-                    .find_skill(&name)
-                    .with_context(|| format!("Skill '{}' not found in config", name))?;
+#[derive(Subcommand)]
+enum GithubAction {
+    /// Upload a skill to the v<version> release of a GitHub repository
+    #[command(
+        long_about = "Upload a .skill file to the v<version> release of a GitHub repository.\n\nCreates the release (and its tag, from --target or the default branch) if it\ndoesn't exist. Uploads the package, a <skill>.skill.sha256 checksum file and,\nif there is one, a detached signature: --signature, or a .sig, .asc or\n.minisig file next to the package. Other assets on the release are left alone,\nso several skills can share a release. If the release already has the skill,\nits assets are only replaced with --force.\n\nIf --file is not specified, defaults to dist/<skill>.skill.",
+        after_help = "Examples:\n  sb github publish my-skill 1.0.0 --repo user/repo\n  sb github publish my-skill 1.0.0 --repo user/repo --notes NOTES.md\n  sb github publish my-skill 1.0.0 --repo user/repo --signature my-skill.skill.asc --force"
+    )]
+    Publish {
+        /// Skill name
+        skill: String,
 
-                let results = download_skill_docs(skill, &source_dir, &output)?;
-                let failures: Vec<_> = results.iter().filter(|r| !r.success).collect();
+        /// Version, released under the tag v<version>
+        version: String,
 
-                if !failures.is_empty() {
-                    anyhow::bail!("{} files failed to download", failures.len());
-                }
-            } else {
-                anyhow::bail!("Please specify a skill name, --all, or --url with --name");
-            }
-        }
+        /// GitHub repository (owner/repo)
+        #[arg(long)]
+        repo: String,
 
-        Commands::Validate { skill, skills_dir } => {
-            // Determine skill path
-            let skill_path = if PathBuf::from(&skill).exists() {
-                PathBuf::from(&skill)
-            } else {
-                skills_dir.join(&skill)
-            };
+        /// Path to .skill file (default: dist/<skill>.skill)
+        #[arg(long)]
+        file: Option<PathBuf>,
 
-            if !skill_path.exists() {
-                anyhow::bail!("Skill directory not found: {}", skill_path.display());
-            }
+        /// Detached signature of the .skill file
+        #[arg(long)]
+        signature: Option<PathBuf>,
+
+        /// File with the release notes
+        #[arg(long)]
+        notes: Option<PathBuf>,
+
+        /// Commit or branch to create the tag from (default: the default branch)
+        #[arg(long)]
+        target: Option<String>,
+
+        /// Replace the skill's assets if the release already has them
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Show the effective configuration
+    #[command(
+        long_about = "Show the effective configuration after merging all layers.\n\nPrints the merged config as JSON. With --origin, prints each value with the\nlayer (default, global, project, --config, env) and file or variable it came from.",
+        after_help = "Examples:\n  sb config show\n  sb config show --origin\n  SB_BUCKET_NAME=other sb config show --origin"
+    )]
+    Show {
+        /// Show which layer each value came from
+        #[arg(long)]
+        origin: bool,
+    },
+
+    /// Check config files for mistakes
+    #[command(
+        long_about = "Check config files against the skills.json schema.\n\nReports unknown keys (with suggestions), values of the wrong type, invalid URLs\nand duplicate skill names, with line numbers. Without a file, checks every\nconfig layer that exists. Exits with an error if any problem is found.",
+        after_help = "Examples:\n  sb config validate\n  sb config validate path/to/skills.json"
+    )]
+    Validate {
+        /// Config file to check (default: all existing config layers)
+        file: Option<PathBuf>,
+    },
+
+    /// Add a skill to a config file
+    #[command(
+        long_about = "Add a skill entry to a config file.\n\nEdits the --config file if given, the global config with --global, or the\nproject skills.json otherwise (created if missing).",
+        after_help = "Examples:\n  sb config add-skill svelte --url https://svelte.dev/llms.txt\n  sb config add-skill svelte --url https://svelte.dev/llms.txt --description \"Svelte docs\""
+    )]
+    AddSkill {
+        /// Unique name for the skill
+        name: String,
+
+        /// URL to the llms.txt file
+        #[arg(long)]
+        url: String,
+
+        /// Description of what the skill provides
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Base URL for resolving relative paths
+        #[arg(long)]
+        base_url: Option<String>,
+
+        /// Path prefix to strip from URLs
+        #[arg(long)]
+        path_prefix: Option<String>,
+
+        /// Edit the global config instead of the project skills.json
+        #[arg(long)]
+        global: bool,
+    },
+
+    /// Remove a skill from a config file
+    #[command(after_help = "Examples:\n  sb config remove-skill svelte")]
+    RemoveSkill {
+        /// Name of the skill to remove
+        name: String,
+
+        /// Edit the global config instead of the project skills.json
+        #[arg(long)]
+        global: bool,
+    },
+
+    /// Set a value in a config file
+    #[command(
+        long_about = "Set a value in a config file.\n\nKEY uses the same paths as `sb config show --origin`, e.g. repository.region or\nskills[svelte].description. VALUE is parsed as JSON when possible (true, 42,\nnull) and used as a string otherwise. Missing objects are created.",
+        after_help = "Examples:\n  sb config set repository.bucket_name my-skills\n  sb config set repository.local.cache true\n  sb config set skills[svelte].description \"Svelte docs\""
+    )]
+    Set {
+        /// Config path to set
+        key: String,
+
+        /// New value
+        value: String,
+
+        /// Edit the global config instead of the project skills.json
+        #[arg(long)]
+        global: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum LocalAction {
+    /// List all skills in the local repository
+    #[command(
+        long_about = "List all skills and versions stored in the local repository.\n\nShows each skill name and version, plus the repository directory path."
+    )]
+    List,
+
+    /// Clear skills from the local repository
+    #[command(
+        long_about = "Clear skills from the local repository.\n\nRemoves all locally stored skills, or only a specific skill if --skill is provided.",
+        after_help = "Examples:\n  sb local clear\n  sb local clear --skill my-skill"
+    )]
+    Clear {
+        /// Only clear a specific skill (default: clear all)
+        #[arg(long)]
+        skill: Option<String>,
+    },
+
+    /// Publish a skill to the local repository
+    #[command(
+        long_about = "Publish a .skill file to the local repository.\n\nWrites the package, and optionally a CHANGELOG.md and source archive, and\nupdates the local skills_index.json, so `sb install --local` can install it\nwithout network access. If --file is not specified, defaults to\ndist/<skill>.skill. Skill metadata (description, llms_txt_url) is read from\nskills.json if available.",
+        after_help = "Examples:\n  sb local publish my-skill 1.0.0\n  sb local publish my-skill 1.0.0 --file ./my-skill.skill\n  sb local publish my-skill 1.0.0 --changelog CHANGELOG.md --source-dir ./source"
+    )]
+    Publish {
+        /// Skill name
+        skill: String,
+
+        /// Version to publish (e.g. "1.0.0")
+        version: String,
+
+        /// Path to the .skill file [default: dist/<skill>.skill]
+        #[arg(long)]
+        file: Option<PathBuf>,
+
+        /// Path to a CHANGELOG.md file to include, or `auto` to generate one
+        #[arg(long)]
+        changelog: Option<PathBuf>,
+
+        /// Overwrite the version if it is already published
+        #[arg(long)]
+        force: bool,
+
+        /// Path to source directory to archive and publish
+        #[arg(long)]
+        source_dir: Option<PathBuf>,
+    },
+
+    /// Evict cached downloads beyond the configured limits
+    #[command(
+        long_about = "Evict cached downloads beyond the cache limits.\n\nLimits come from the repository's `local` config section (max_size_mb,\nmax_age_days, keep_versions) and can be overridden with flags. Least recently\nused entries go first. Skills published with `sb local publish` are never\npruned.",
+        after_help = "Examples:\n  sb local prune\n  sb local prune --max-size-mb 200 --dry-run\n  sb local prune --keep-versions 1"
+    )]
+    Prune {
+        /// Maximum total cache size in MiB
+        #[arg(long)]
+        max_size_mb: Option<u64>,
+
+        /// Evict entries not used for this many days
+        #[arg(long)]
+        max_age_days: Option<u64>,
+
+        /// Most recently used versions to keep per skill
+        #[arg(long)]
+        keep_versions: Option<usize>,
+
+        /// Show what would be evicted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Check the local repository index against the stored files
+    #[command(
+        long_about = "Check that the local skills index and the stored files agree.\n\nReports orphan files, index entries pointing at missing files, checksum\nmismatches and unreadable packages. With --fix, rebuilds the index.",
+        after_help = "Examples:\n  sb local fsck\n  sb local fsck --fix"
+    )]
+    Fsck {
+        /// Rebuild the index to fix what can be fixed
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Rebuild the local repository index from the stored packages
+    #[command(
+        long_about = "Rebuild the local skills_index.json from the packages on disk.\n\nNames and descriptions are read from each package's SKILL.md."
+    )]
+    Reindex,
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("Error: {:#}", e);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let cli = Cli::parse();
+    offline::init(cli.offline);
+    let output = Output::new(cli.agent_output);
+    let project_root = resolve_project_root(cli.project_root.as_deref())?;
+    let load_config = || -> Result<Config> {
+        Ok(Config::load_layered(cli.config.as_deref(), &project_root)?.config)
+    };
+
+    match cli.command {
+        Commands::Download {
+            skill_name,
+            all,
+            url,
+            name,
+            source_dir,
+        } => {
+            // Handle --url override (no config needed)
+            if let Some(url) = url {
+                let name = name.context("--name is required when using --url")?;
+                output.info(&format!("Downloading from URL: {}", url));
+                output.step(&format!("Skill name: {}", name));
+                output.newline();
+
+                let results = download_from_url(&url, &name, &source_dir, &output)?;
+                let failures: Vec<_> = results.iter().filter(|r| !r.success).collect();
+
+                if !failures.is_empty() {
+                    anyhow::bail!("{} files failed to download", failures.len());
+                }
+
+                return Ok(());
+            }
+
+            // Load config
+            let config = load_config()?;
+
+            if all {
+                // Download all skills
+                output.header(&format!(
+                    "Downloading all {} skills...",
+                    config.skills.len()
+                ));
+                output.newline();
+
+                for skill in &config.skills {
+                    output.header(&format!("=== {} ===", skill.name));
+                    if let Err(e) = download_skill_docs(skill, &source_dir, &output) {
+                        output.error(&format!("Failed to download {}: {}", skill.name, e));
+                    }
+                    output.newline();
+                }
+            } else if let Some(name) = skill_name {
+                // Download specific skill
+                let skill = config
+                    .find_skill(&name)
+                    .with_context(|| format!("Skill '{}' not found in config", name))?;
+
+                let results = download_skill_docs(skill, &source_dir, &output)?;
+                let failures: Vec<_> = results.iter().filter(|r| !r.success).collect();
+
+                if !failures.is_empty() {
+                    anyhow::bail!("{} files failed to download", failures.len());
+                }
+            } else {
+                anyhow::bail!("Please specify a skill name, --all, or --url with --name");
+            }
+        }
+
+        Commands::Validate { skill, skills_dir } => {
+            // Determine skill path
+            let skill_path = if PathBuf::from(&skill).exists() {
+                PathBuf::from(&skill)
+            } else {
+                skills_dir.join(&skill)
+            };
+
+            if !skill_path.exists() {
+                anyhow::bail!("Skill directory not found: {}", skill_path.display());
+            }
 
             output.info(&format!("Validating: {}", skill_path.display()));
             output.newline();
@@ -421,103 +964,948 @@ fn run() -> Result<()> {
             local,
             remote,
             github,
+            follow_replaced,
             install_dir,
             agent,
             global,
         } => {
             // Resolve target directories
             let agent_target = skill_builder::agent::parse_agent_flag(agent.as_deref())?;
-            let install_dirs = skill_builder::agent::resolve_install_dirs(
+            let targets = skill_builder::agent::resolve_install_targets(
                 &agent_target,
                 install_dir.as_deref(),
                 global,
-                std::path::Path::new("."),
+                &project_root,
             );
 
             if let Some(file_path) = file {
                 // Install from local file to each target directory
-                for dir in &install_dirs {
-                    output.info(&format!("Installing to {}", dir.display()));
-                    install_from_file(&file_path, dir, &output)?;
+                for target in &targets {
+                    output.info(&format!("Installing to {}", target.dir.display()));
+                    let result = install_from_file(&file_path, &target.dir, &output)?;
+                    adapt_for_target(target, &result.install_path, &project_root, global, &output);
+                }
+            } else if let Some(source) = DirectSource::parse(&skill)? {
+                if version.is_some()
+                    || repo.is_some()
+                    || local
+                    || remote
+                    || github
+                    || follow_replaced
+                {
+                    anyhow::bail!(
+                        "--version, --repo, --local, --remote, --github and --follow-replaced don't apply to a URL or git source; put a git ref in the source instead"
+                    );
+                }
+                output.header(&format!("Installing from {}", source));
+                let fetched = direct_install::fetch(&source, &output)?;
+                let mut name = None;
+                for target in &targets {
+                    output.info(&format!("Installing to {}", target.dir.display()));
+                    let result = install_from_bytes(&fetched.data, &target.dir, &output)?;
+                    adapt_for_target(target, &result.install_path, &project_root, global, &output);
+                    name = Some(result.skill_name);
+                }
+                if let Some(name) = name {
+                    let lock_dir = if global {
+                        skill_builder::config::global_config_dir()
+                    } else {
+                        project_root.clone()
+                    };
+                    let lock_path = lock_dir.join(LOCKFILE);
+                    Lockfile::record(&lock_path, &name, fetched.locked(&source))?;
+                    output.step(&format!("Recorded {} in {}", name, lock_path.display()));
                 }
             } else {
                 // Use the install resolver for source cascade
-                let config = Config::load_with_fallback(cli.config.as_deref())?;
-                for dir in &install_dirs {
-                    output.info(&format!("Installing to {}", dir.display()));
+                let config = load_config()?;
+                for target in &targets {
+                    output.info(&format!("Installing to {}", target.dir.display()));
                     let options = skill_builder::install_resolver::InstallOptions {
                         skill_name: &skill,
                         version: version.as_deref(),
                         github_repo: repo.as_deref(),
-                        install_dir: dir,
+                        install_dir: &target.dir,
                         local_only: local,
                         remote_only: remote,
                         github_only: github,
+                        offline: offline::is_offline(),
+                        follow_replaced,
                     };
-                    skill_builder::install_resolver::resolve_and_install(
+                    let resolved = skill_builder::install_resolver::resolve_and_install(
                         &config, &options, &output,
                     )?;
+                    adapt_for_target(
+                        target,
+                        &resolved.result.install_path,
+                        &project_root,
+                        global,
+                        &output,
+                    );
+                }
+            }
+        }
+
+        Commands::List => {
+            let config = load_config()?;
+
+            if config.skills.is_empty() {
+                output.info("No skills configured.");
+            } else {
+                output.header("Configured skills:");
+                output.newline();
+                let mut rows = Vec::new();
+                for skill in &config.skills {
+                    let desc = if skill.description.chars().count() > 60 {
+                        let truncated: String = skill.description.chars().take(60).collect();
+                        format!("{}...", truncated)
+                    } else {
+                        skill.description.clone()
+                    };
+                    rows.push(vec![skill.name.clone(), skill.llms_txt_url.clone(), desc]);
+                }
+                output.table(&rows);
+            }
+        }
+
+        Commands::Outdated {
+            repo,
+            install_dir,
+            agent,
+            global,
+        } => {
+            let agent_target = skill_builder::agent::parse_agent_flag(agent.as_deref())?;
+            let targets = skill_builder::agent::resolve_install_targets(
+                &agent_target,
+                install_dir.as_deref(),
+                global,
+                &project_root,
+            );
+            handle_outdated(&load_config()?, &targets, repo.as_deref(), &output)?;
+        }
+
+        Commands::Info {
+            skill,
+            version,
+            since,
+            repo,
+            github,
+        } => {
+            let found = match github {
+                Some(github_repo) => github_skill_entry(&github_repo, &skill)?,
+                None => find_skill_entry(&load_config()?, &skill, repo.as_deref(), &output)?,
+            };
+            handle_info(found, version.as_deref(), since.as_deref(), &output)?;
+        }
+
+        Commands::Changelog {
+            skill,
+            since,
+            version,
+            skills_dir,
+            repo,
+        } => {
+            let skill_path = if PathBuf::from(&skill).exists() {
+                PathBuf::from(&skill)
+            } else {
+                skills_dir.join(&skill)
+            };
+            if !skill_path.exists() {
+                anyhow::bail!("Skill directory not found: {}", skill_path.display());
+            }
+            let name = skill_path
+                .canonicalize()?
+                .file_name()
+                .context("Invalid skill path")?
+                .to_string_lossy()
+                .to_string();
+
+            let config = load_config()?;
+            let (repo_display, repo, entry) =
+                find_skill_entry(&config, &name, repo.as_deref(), &output)?;
+            let since = match since {
+                Some(v) => v,
+                None => entry
+                    .latest_version()
+                    .map(str::to_string)
+                    .with_context(|| format!("Skill '{}' has no versions", name))?,
+            };
+            output.info(&format!(
+                "Comparing {} with v{} from {}",
+                skill_path.display(),
+                since,
+                repo_display
+            ));
+            let diff = SkillDiff::between(
+                &repo.package_files(&name, &since)?,
+                &read_skill_files(&skill_path)?,
+            );
+            if diff.is_empty() {
+                output.info(&format!("No changes since v{}.", since));
+            } else {
+                let heading = version.as_deref().unwrap_or("Unreleased");
+                print!("{}", diff.to_section(heading, &today()));
+            }
+        }
+
+        Commands::Release {
+            skill,
+            bump,
+            version,
+            force,
+            changelog,
+            source_dir,
+            skills_dir,
+            output: output_dir,
+            repo,
+        } => {
+            let skill_path = if PathBuf::from(&skill).exists() {
+                PathBuf::from(&skill)
+            } else {
+                skills_dir.join(&skill)
+            };
+            if !skill_path.exists() {
+                anyhow::bail!("Skill directory not found: {}", skill_path.display());
+            }
+            let name = skill_path
+                .canonicalize()?
+                .file_name()
+                .context("Invalid skill path")?
+                .to_string_lossy()
+                .to_string();
+
+            let config = load_config()?;
+            let (repo_display, repository) = match repo.as_deref() {
+                Some(repo) => open_repository(&config, repo, true)?,
+                None => {
+                    let rc = config.write_repository(None)?;
+                    (rc.display_name().to_string(), Repository::from_config(rc)?)
+                }
+            };
+            let version = match (version, bump) {
+                (Some(version), _) => version,
+                (None, Some(bump)) => {
+                    // Yanked versions count too, so a version is never reused
+                    let published = repository
+                        .list(Some(&name))?
+                        .find_skill(&name)
+                        .and_then(|entry| entry.versions.keys().max_by(|a, b| compare_semver(a, b)))
+                        .cloned();
+                    let current = match published {
+                        Some(v) => Some(v),
+                        None => {
+                            let content = std::fs::read_to_string(skill_path.join("SKILL.md"))
+                                .with_context(|| {
+                                    format!("Failed to read {}/SKILL.md", skill_path.display())
+                                })?;
+                            parse_frontmatter(&content).ok().and_then(|f| f.version)
+                        }
+                    };
+                    let next = next_version(current.as_deref(), bump)?;
+                    output.info(&format!(
+                        "Bumping {} {} -> {} ({})",
+                        name,
+                        current.as_deref().unwrap_or("(unreleased)"),
+                        next,
+                        bump
+                    ));
+                    next
+                }
+                (None, None) => unreachable!("clap requires --bump or --version"),
+            };
+            if !force && repository.has_version(&name, &version)? {
+                anyhow::bail!(
+                    "{} v{} is already published to {}; use --force to overwrite it",
+                    name,
+                    version,
+                    repo_display
+                );
+            }
+
+            // Validate before touching SKILL.md; packaging validates again
+            let validation = validate_skill(&skill_path);
+            if !validation.valid {
+                print_validation_result(&validation, &output);
+                anyhow::bail!("Skill validation failed");
+            }
+            // Put SKILL.md back if the release doesn't go through
+            let skill_md = skill_path.join("SKILL.md");
+            let original = std::fs::read_to_string(&skill_md)
+                .with_context(|| format!("Failed to read {}", skill_md.display()))?;
+            write_skill_version(&skill_path, &version)?;
+            output.step(&format!("Set version {} in SKILL.md", version));
+            let released = (|| -> Result<()> {
+                output.newline();
+                let package = skill_builder::package::package_skill_with_output(
+                    &skill_path,
+                    &output_dir,
+                    &output,
+                )?;
+
+                let skill_config = config.find_skill(&name);
+                output.newline();
+                output.header(&format!(
+                    "Uploading {} v{} to {}...",
+                    name, version, repo_display
+                ));
+                upload_skill(
+                    &repository,
+                    UploadParams {
+                        name: &name,
+                        version: &version,
+                        description: skill_config.map(|s| s.description.as_str()).unwrap_or(""),
+                        llms_txt_url: skill_config.map(|s| s.llms_txt_url.as_str()).unwrap_or(""),
+                        skill_file: &package.output_path,
+                        changelog: None,
+                        source_dir: source_dir.as_deref(),
+                    },
+                    changelog,
+                    force,
+                    &output,
+                )?;
+                Ok(())
+            })();
+            if let Err(e) = released {
+                std::fs::write(&skill_md, original)
+                    .with_context(|| format!("Failed to restore {}", skill_md.display()))?;
+                return Err(e);
+            }
+            output.status("Released", &format!("{} v{}", name, version));
+        }
+
+        Commands::Search {
+            query,
+            repo,
+            no_github,
+            limit,
+        } => {
+            let github = (!no_github).then_some(repo.as_deref());
+            handle_search(&load_config()?, &query.join(" "), github, limit, &output)?;
+        }
+
+        Commands::Repo { repo, action } => {
+            handle_repo_command(
+                load_config()?,
+                repo.as_deref(),
+                &project_root,
+                action,
+                &output,
+            )?;
+        }
+
+        Commands::Github { action } => {
+            handle_github_command(action, &output)?;
+        }
+
+        Commands::Local { action } => {
+            handle_local_command(load_config()?, action, &output)?;
+        }
+
+        Commands::Config { action } => {
+            handle_config_command(cli.config.as_deref(), &project_root, action, &output)?;
+        }
+
+        Commands::Init => {
+            skill_builder::init::run_init(&output)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Use the `--project-root` override, or discover the project root from the cwd.
+fn resolve_project_root(explicit: Option<&std::path::Path>) -> Result<PathBuf> {
+    if let Some(root) = explicit {
+        if !root.is_dir() {
+            anyhow::bail!("Project root not found: {}", root.display());
+        }
+        return Ok(root.to_path_buf());
+    }
+    let cwd = std::env::current_dir().context("Failed to determine current directory")?;
+    Ok(skill_builder::agent::find_project_root(&cwd))
+}
+
+/// Run the agent-specific adapter for a freshly installed skill.
+///
+/// Adapter failures are reported as warnings: the skill itself is already installed.
+fn adapt_for_target(
+    target: &InstallTarget,
+    skill_path: &std::path::Path,
+    project_root: &std::path::Path,
+    global: bool,
+    output: &Output,
+) {
+    let Some(agent) = target.agent else {
+        return;
+    };
+    let ctx = AdapterContext {
+        project_root,
+        global,
+    };
+    match adapt_installed_skill(agent, skill_path, &ctx) {
+        Ok(paths) => {
+            for path in paths {
+                output.step(&format!("Updated {}: {}", agent.name(), path.display()));
+            }
+        }
+        Err(e) => output.warn(&format!(
+            "Failed to adapt skill for {}: {:#}",
+            agent.name(),
+            e
+        )),
+    }
+}
+
+/// Repositories to read from: the one named by `--repo`, or every remote
+/// repository in priority order.
+fn read_repositories<'a>(
+    config: &'a Config,
+    repo_name: Option<&str>,
+) -> Result<Vec<&'a RepositoryConfig>> {
+    if let Some(name) = repo_name {
+        return Ok(vec![config.find_repository(name)?]);
+    }
+    let repos: Vec<&RepositoryConfig> = config
+        .all_repositories()
+        .into_iter()
+        .filter(|r| r.has_remote())
+        .collect();
+    if repos.is_empty() {
+        anyhow::bail!("No 'repository' section found in config. Add one to use repo commands.");
+    }
+    Ok(repos)
+}
+
+/// `--changelog` value that generates the changelog from the package.
+const AUTO_CHANGELOG: &str = "auto";
+
+/// Today's date for changelog headings.
+fn today() -> String {
+    chrono::Utc::now().format("%Y-%m-%d").to_string()
+}
+
+/// Upload a skill with the changelog at `changelog`, or for `auto` one
+/// generated from the changes since the newest earlier version in `repo`.
+///
+/// A generated section is added to that version's changelog, so the uploaded
+/// file keeps the whole history. Refuses to replace a published version
+/// unless `force` is set.
+fn upload_skill<S: StorageOperations>(
+    repo: &Repository<S>,
+    params: UploadParams,
+    changelog: Option<PathBuf>,
+    force: bool,
+    output: &Output,
+) -> Result<()> {
+    if repo.has_version(params.name, params.version)? {
+        if !force {
+            anyhow::bail!(
+                "{} v{} is already published; use --force to overwrite it",
+                params.name,
+                params.version
+            );
+        }
+        output.warn(&format!(
+            "Overwriting published {} v{}",
+            params.name, params.version
+        ));
+    }
+    if changelog.as_deref() != Some(std::path::Path::new(AUTO_CHANGELOG)) {
+        let params = UploadParams {
+            changelog: changelog.as_deref(),
+            ..params
+        };
+        return repo.upload_version(&params, force, output);
+    }
+    let (skill, version, skill_file) = (params.name, params.version, params.skill_file);
+    let file = std::fs::File::open(skill_file)
+        .with_context(|| format!("Failed to read skill file: {}", skill_file.display()))?;
+    let files = read_package_files(file)?;
+    let previous = repo.list(Some(skill))?.skills.pop().and_then(|entry| {
+        entry
+            .versions
+            .into_keys()
+            .filter(|v| compare_semver(v, version).is_lt())
+            .max_by(|a, b| compare_semver(a, b))
+    });
+
+    let text = match &previous {
+        Some(previous) => {
+            output.step(&format!(
+                "Generated changelog from changes since v{}",
+                previous
+            ));
+            let diff = SkillDiff::between(&repo.package_files(skill, previous)?, &files);
+            let history = repo.changelog(skill, previous)?.unwrap_or_default();
+            prepend_section(&history, &diff.to_section(version, &today()))
+        }
+        None => {
+            output.step("Generated changelog for the first release");
+            initial_section(version, &today(), &files)
+        }
+    };
+    let mut file = tempfile::Builder::new()
+        .prefix(&format!("sb-{}-{}-", skill, version))
+        .suffix("-CHANGELOG.md")
+        .tempfile()
+        .context("Failed to create a temporary changelog")?;
+    std::io::Write::write_all(&mut file, text.as_bytes())
+        .with_context(|| format!("Failed to write {}", file.path().display()))?;
+    repo.upload_version(
+        &UploadParams {
+            changelog: Some(file.path()),
+            ..params
+        },
+        force,
+        output,
+    )
+}
+
+/// A repository opened from config, whatever its backend.
+type DynRepository = Repository<Box<dyn StorageOperations>>;
+
+/// The first repository with the skill: `repo_name` if given, else the local
+/// repository and then the configured ones in priority order.
+fn find_skill_entry(
+    config: &Config,
+    skill: &str,
+    repo_name: Option<&str>,
+    output: &Output,
+) -> Result<(String, DynRepository, IndexEntry)> {
+    let candidates = skill_repositories(config, repo_name);
+    let several = candidates.len() > 1;
+    for candidate in candidates {
+        let found = candidate.and_then(|(display, repo)| {
+            let entry = repo.list(Some(skill))?.skills.pop();
+            Ok(entry.map(|entry| (display, repo, entry)))
+        });
+        match found {
+            Ok(Some(found)) => return Ok(found),
+            Ok(None) => {}
+            Err(e) if several => output.warn(&format!("Skipping: {:#}", e)),
+            Err(e) => return Err(e),
+        }
+    }
+    anyhow::bail!("Skill '{}' not found in any repository", skill)
+}
+
+/// A skill on the GitHub releases of `github_repo`, read like a repository.
+fn github_skill_entry(
+    github_repo: &str,
+    skill: &str,
+) -> Result<(String, DynRepository, IndexEntry)> {
+    let client: Box<dyn StorageOperations> = Box::new(GitHubReleaseStorage::new(
+        GitHubClient::from_env()?,
+        github_repo,
+    ));
+    let repo = Repository::new(client);
+    let mut index = repo.list(None)?;
+    let Some(at) = index.skills.iter().position(|e| e.name == skill) else {
+        let names = index.skills.iter().map(|e| e.name.as_str());
+        let hint = match skill_builder::suggest::did_you_mean(skill, names) {
+            Some(suggestion) => format!(" (did you mean '{}'?)", suggestion),
+            None => String::new(),
+        };
+        anyhow::bail!(
+            "Skill '{}' not found in GitHub releases of {}{}",
+            skill,
+            github_repo,
+            hint
+        );
+    };
+    let entry = index.skills.remove(at);
+    Ok((format!("GitHub releases of {}", github_repo), repo, entry))
+}
+
+/// Repositories to look up skills in, in priority order: `repo_name` if
+/// given, else the local repository (if it has an index) and every remote.
+fn skill_repositories(
+    config: &Config,
+    repo_name: Option<&str>,
+) -> Vec<Result<(String, DynRepository)>> {
+    let mut candidates = Vec::new();
+    match repo_name {
+        Some(name) => candidates.push(open_repository(config, name, false)),
+        None => {
+            let local_path = config.local_repo_path();
+            if local_path.join(INDEX_KEY).exists() {
+                candidates.push(open_repository(config, "local", false));
+            }
+            for rc in config.all_repositories() {
+                if rc.has_remote() {
+                    candidates.push(
+                        Repository::from_config(rc)
+                            .map(|repo| (rc.display_name().to_string(), repo))
+                            .with_context(|| {
+                                format!("Failed to open repository '{}'", rc.display_name())
+                            }),
+                    );
+                }
+            }
+        }
+    }
+    candidates
+}
+
+/// Print how each installed skill compares with its latest published version.
+fn handle_outdated(
+    config: &Config,
+    targets: &[InstallTarget],
+    repo_name: Option<&str>,
+    output: &Output,
+) -> Result<()> {
+    let candidates = skill_repositories(config, repo_name);
+    let several = candidates.len() > 1;
+    let mut indexes = Vec::new();
+    for candidate in candidates {
+        match candidate.and_then(|(display, repo)| Ok((display, repo.list(None)?))) {
+            Ok(found) => indexes.push(found),
+            Err(e) if several => output.warn(&format!("Skipping: {:#}", e)),
+            Err(e) => return Err(e),
+        }
+    }
+    let find = |name: &str| {
+        indexes
+            .iter()
+            .find_map(|(_, index)| index.find_skill(name).map(|entry| (index, entry)))
+    };
+
+    let mut checked = 0;
+    for target in targets {
+        let installed = installed_skills(&target.dir)?;
+        if installed.is_empty() {
+            continue;
+        }
+        checked += installed.len();
+        output.header(&format!("{}:", target.dir.display()));
+        let mut rows = Vec::new();
+        let mut notices = Vec::new();
+        for skill in &installed {
+            let version = skill
+                .version
+                .as_deref()
+                .map_or_else(|| "unknown".to_string(), |v| format!("v{}", v));
+            let Some((index, entry)) = find(&skill.name) else {
+                rows.push(vec![
+                    skill.name.clone(),
+                    version,
+                    "not in any repository".to_string(),
+                ]);
+                continue;
+            };
+            let status = match (skill.version.as_deref(), entry.latest_version()) {
+                (_, None) => "no published versions".to_string(),
+                (None, Some(latest)) => format!("latest is v{}", latest),
+                (Some(installed), Some(latest)) if compare_semver(installed, latest).is_lt() => {
+                    format!("v{} available", latest)
                 }
+                (Some(_), Some(_)) => "up to date".to_string(),
+            };
+            rows.push(vec![skill.name.clone(), version, status]);
+
+            if let Some(notice) = entry.deprecation_notice() {
+                let replacement = entry
+                    .replaced_by
+                    .as_deref()
+                    .and_then(|name| index.latest_version(name).map(|v| (name, v)));
+                notices.push(match replacement {
+                    Some((name, v)) => format!(
+                        "{}; install {} v{} with `sb install {} --follow-replaced`",
+                        notice, name, v, skill.name
+                    ),
+                    None => notice,
+                });
+            }
+        }
+        output.table(&rows);
+        for notice in &notices {
+            output.warn(notice);
+        }
+        output.newline();
+    }
+
+    if checked == 0 {
+        output.info("No installed skills found.");
+    }
+    Ok(())
+}
+
+/// Print a skill's metadata, versions, changelog and packaged files.
+fn handle_info(
+    (repo_display, repo, entry): (String, DynRepository, IndexEntry),
+    version: Option<&str>,
+    since: Option<&str>,
+    output: &Output,
+) -> Result<()> {
+    let skill = entry.name.as_str();
+    let details = repo.version_details(&entry)?;
+    let target = match version {
+        Some(v) => entry.resolve_version(Some(v))?.to_string(),
+        // The newest version, if every version has been yanked
+        None => entry
+            .latest_version()
+            .map(str::to_string)
+            .or_else(|| details.first().map(|d| d.version.clone()))
+            .with_context(|| format!("Skill '{}' has no versions", skill))?,
+    };
+
+    output.header(&entry.name);
+    if !entry.description.is_empty() {
+        output.info(&entry.description);
+    }
+    output.step(&format!("Repository: {}", repo_display));
+    if !entry.llms_txt_url.is_empty() {
+        output.step(&format!("Source: {}", entry.llms_txt_url));
+    }
+    if !entry.tags.is_empty() {
+        output.step(&format!("Tags: {}", entry.tags.join(", ")));
+    }
+    if let Some(author) = &entry.author {
+        output.step(&format!("Author: {}", author));
+    }
+    if let Some(homepage) = &entry.homepage {
+        output.step(&format!("Homepage: {}", homepage));
+    }
+    if let Some(notice) = entry.deprecation_notice() {
+        output.warn(&notice);
+    }
+
+    output.newline();
+    output.header("Versions:");
+    let rows: Vec<Vec<String>> = details
+        .iter()
+        .map(|d| {
+            let mut extras = Vec::new();
+            if d.has_changelog {
+                extras.push("changelog".to_string());
+            }
+            if let Some(size) = d.source_size {
+                extras.push(format!("source {}", format_size(size)));
+            }
+            match entry.yanked.get(&d.version) {
+                Some(reason) if reason.is_empty() => extras.push("yanked".to_string()),
+                Some(reason) => extras.push(format!("yanked: {}", reason)),
+                None => {}
+            }
+            vec![
+                d.version.clone(),
+                d.published
+                    .map(|p| p.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                d.size
+                    .map(format_size)
+                    .unwrap_or_else(|| "missing".to_string()),
+                extras.join(", "),
+            ]
+        })
+        .collect();
+    output.table(&rows);
+
+    // Each version's own CHANGELOG.md, falling back to the target's, which
+    // usually holds the whole history
+    output.newline();
+    let selected = match since {
+        Some(since) => versions_between(
+            entry.versions.keys().map(String::as_str),
+            Some(since),
+            &target,
+        ),
+        None => vec![target.as_str()],
+    };
+    match since {
+        Some(since) => output.header(&format!("Changes since v{}:", since)),
+        None => output.header(&format!("Changelog for v{}:", target)),
+    }
+    let target_changelog = repo.changelog(skill, &target)?;
+    let mut printed = 0;
+    for v in &selected {
+        let own = repo.changelog(skill, v)?;
+        let notes = [own.as_deref(), target_changelog.as_deref()]
+            .into_iter()
+            .flatten()
+            .find_map(|text| find_section(text, v))
+            .map(|section| section.text)
+            .or_else(|| own.clone().filter(|text| parse_sections(text).is_empty()));
+        if let Some(notes) = notes {
+            for line in notes.trim().lines() {
+                output.info(line);
             }
+            output.newline();
+            printed += 1;
         }
+    }
+    if selected.is_empty() {
+        output.info(&format!("v{} is the latest version.", target));
+    } else if printed == 0 {
+        output.info("No changelog was uploaded.");
+    }
 
-        Commands::List => {
-            let config = Config::load_with_fallback(cli.config.as_deref())?;
+    let package = repo.download(skill, Some(&target), None, output)?;
+    let files = list_skill_contents(&package)?;
+    output.newline();
+    output.header(&format!("Files in v{}:", target));
+    for file in files.iter().filter(|f| !f.ends_with('/')) {
+        output.step(file);
+    }
+    Ok(())
+}
 
-            if config.skills.is_empty() {
-                output.info("No skills configured.");
-            } else {
-                output.header("Configured skills:");
-                output.newline();
-                let mut rows = Vec::new();
-                for skill in &config.skills {
-                    let desc = if skill.description.chars().count() > 60 {
-                        let truncated: String = skill.description.chars().take(60).collect();
-                        format!("{}...", truncated)
-                    } else {
-                        skill.description.clone()
-                    };
-                    rows.push(vec![skill.name.clone(), skill.llms_txt_url.clone(), desc]);
-                }
-                output.table(&rows);
-            }
+/// Search the local repository, the configured repositories and, unless
+/// `github` is `None`, the GitHub releases of the given (or default) repo.
+fn handle_search(
+    config: &Config,
+    query: &str,
+    github: Option<Option<&str>>,
+    limit: usize,
+    output: &Output,
+) -> Result<()> {
+    let mut hits = Vec::new();
+    let mut searched = 0;
+    let mut search = |source: String, index: Result<SkillsIndex>| match index {
+        Ok(index) => {
+            searched += 1;
+            hits.extend(search_index(&index, &source, query));
         }
-
-        Commands::Repo { action } => {
-            handle_repo_command(cli.config.as_deref(), action, &output)?;
+        Err(e) => output.warn(&format!("Skipping {}: {:#}", source, e)),
+    };
+
+    let local_path = config.local_repo_path();
+    if local_path.join(INDEX_KEY).exists() {
+        search(
+            "local repository".to_string(),
+            LocalStorageClient::new(&local_path).and_then(|c| Repository::new(c).list(None)),
+        );
+    }
+    for rc in config.all_repositories() {
+        if rc.has_remote() {
+            search(
+                format!("repository '{}'", rc.display_name()),
+                Repository::from_config(rc).and_then(|r| r.list(None)),
+            );
         }
+    }
+    if let Some(repo) = github {
+        search(
+            format!("GitHub releases of {}", repo.unwrap_or(DEFAULT_REPO)),
+            list_release_skills(repo),
+        );
+    }
+    if searched == 0 {
+        anyhow::bail!("No skill sources could be searched");
+    }
 
-        Commands::Local { action } => {
-            handle_local_command(cli.config.as_deref(), action, &output)?;
+    rank(&mut hits);
+    if hits.is_empty() {
+        output.info(&format!("No skills match '{}'.", query));
+        return Ok(());
+    }
+    output.header(&format!("Skills matching '{}':", query));
+    output.newline();
+    for hit in hits.iter().take(limit) {
+        let entry = &hit.entry;
+        let name = match entry.latest_version() {
+            Some(version) => format!("{} v{}", entry.name, version),
+            None => entry.name.clone(),
+        };
+        if entry.description.is_empty() {
+            output.info(&format!("  {}", name));
+        } else {
+            output.info(&format!("  {} - {}", name, entry.description));
+        }
+        output.step(&format!("Source: {}", hit.source));
+        if !entry.tags.is_empty() {
+            output.step(&format!("Tags: {}", entry.tags.join(", ")));
         }
+        if let Some(author) = &entry.author {
+            output.step(&format!("Author: {}", author));
+        }
+        if let Some(homepage) = &entry.homepage {
+            output.step(&format!("Homepage: {}", homepage));
+        }
+    }
+    if hits.len() > limit {
+        output.info(&format!(
+            "{} more results; use --limit to see them",
+            hits.len() - limit
+        ));
+    }
+    Ok(())
+}
 
-        Commands::Init => {
-            skill_builder::init::run_init(&output)?;
+/// Open a repository by name for `sb repo mirror`. `local` names the local
+/// repository unless a configured repository has that name.
+fn open_repository(config: &Config, name: &str, write: bool) -> Result<(String, DynRepository)> {
+    let is_configured = config
+        .all_repositories()
+        .iter()
+        .any(|r| r.display_name() == name);
+    if name == "local" && !is_configured {
+        let path = config.local_repo_path();
+        let client: Box<dyn StorageOperations> = Box::new(LocalStorageClient::new(&path)?);
+        return Ok((
+            format!("local ({})", path.display()),
+            Repository::new(client),
+        ));
+    }
+    let repo_config = if write {
+        config.write_repository(Some(name))?
+    } else {
+        config.find_repository(name)?
+    };
+    Ok((
+        repo_config.display_name().to_string(),
+        Repository::from_config(repo_config)?,
+    ))
+}
+
+/// The first repository whose index has the skill (and version, if given).
+fn find_skill_repository<'a>(
+    repos: &[&'a RepositoryConfig],
+    skill: &str,
+    version: Option<&str>,
+    output: &Output,
+) -> Result<&'a RepositoryConfig> {
+    for rc in repos {
+        let index = match Repository::from_config(rc).and_then(|r| r.list(Some(skill))) {
+            Ok(index) => index,
+            Err(e) if repos.len() > 1 => {
+                output.warn(&format!(
+                    "Skipping repository '{}': {}",
+                    rc.display_name(),
+                    e
+                ));
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let found = index
+            .find_skill(skill)
+            .is_some_and(|entry| version.is_none() || entry.resolve_version(version).is_ok());
+        if found {
+            return Ok(rc);
         }
     }
 
-    Ok(())
+    match version {
+        Some(v) => anyhow::bail!("Skill '{}' v{} not found in any repository", skill, v),
+        None => anyhow::bail!("Skill '{}' not found in any repository", skill),
+    }
 }
 
 fn handle_repo_command(
-    config_path: Option<&std::path::Path>,
+    config: Config,
+    repo_name: Option<&str>,
+    project_root: &std::path::Path,
     action: RepoAction,
     output: &Output,
 ) -> Result<()> {
-    let config = Config::load_with_fallback(config_path)?;
-    let repo_config = config
-        .repository
-        .as_ref()
-        .context("No 'repository' section found in config. Add one to use repo commands.")?;
-
-    let repo = Repository::from_config(repo_config)?;
-
     match action {
         RepoAction::Upload {
             skill,
             version,
             file,
             changelog,
+            force,
             source_dir,
         } => {
             let skill_file = if let Some(f) = file {
@@ -536,17 +1924,27 @@ fn handle_repo_command(
             let description = skill_config.map(|s| s.description.as_str()).unwrap_or("");
             let llms_txt_url = skill_config.map(|s| s.llms_txt_url.as_str()).unwrap_or("");
 
-            output.header(&format!("Uploading {} v{}...", skill, version));
-            repo.upload(
-                &UploadParams {
+            let repo_config = config.write_repository(repo_name)?;
+            let repo = Repository::from_config(repo_config)?;
+            output.header(&format!(
+                "Uploading {} v{} to {}...",
+                skill,
+                version,
+                repo_config.display_name()
+            ));
+            upload_skill(
+                &repo,
+                UploadParams {
                     name: &skill,
                     version: &version,
                     description,
                     llms_txt_url,
                     skill_file: &skill_file,
-                    changelog: changelog.as_deref(),
+                    changelog: None,
                     source_dir: source_dir.as_deref(),
                 },
+                changelog,
+                force,
                 output,
             )?;
             output.status("Done", &format!("Uploaded {} v{}", skill, version));
@@ -557,6 +1955,9 @@ fn handle_repo_command(
             version,
             output: output_dir,
         } => {
+            let repos = read_repositories(&config, repo_name)?;
+            let repo_config = find_skill_repository(&repos, &skill, version.as_deref(), output)?;
+            let repo = Repository::from_config(repo_config)?;
             let path = repo.download(&skill, version.as_deref(), output_dir.as_deref(), output)?;
             output.status("Downloaded", &format!("{}", path.display()));
         }
@@ -569,23 +1970,40 @@ fn handle_repo_command(
             global,
         } => {
             let agent_target = skill_builder::agent::parse_agent_flag(agent.as_deref())?;
-            let install_dirs = skill_builder::agent::resolve_install_dirs(
+            let targets = skill_builder::agent::resolve_install_targets(
                 &agent_target,
                 install_dir.as_deref(),
                 global,
-                std::path::Path::new("."),
+                project_root,
             );
 
-            for dir in &install_dirs {
-                repo.install(&skill, version.as_deref(), dir, output)?;
+            let repos = read_repositories(&config, repo_name)?;
+            let repo_config = find_skill_repository(&repos, &skill, version.as_deref(), output)?;
+            let repo = Repository::from_config(repo_config)?;
+            output.info(&format!("Installing from {}", repo_config.display_name()));
+
+            for target in &targets {
+                let result = repo.install(&skill, version.as_deref(), &target.dir, output)?;
+                adapt_for_target(target, &result.install_path, project_root, global, output);
             }
         }
 
         RepoAction::Delete {
             skill,
             version,
+            purge,
             yes,
         } => {
+            if !purge {
+                let yank = match &version {
+                    Some(v) => format!("sb repo yank {} {}", skill, v),
+                    None => format!("sb repo yank {} <version>", skill),
+                };
+                anyhow::bail!(
+                    "Published versions are immutable; use `{}` to withdraw a version, or --purge to delete its files",
+                    yank
+                );
+            }
             if !yes {
                 let target = if let Some(ref v) = version {
                     format!("{} v{}", skill, v)
@@ -599,51 +2017,476 @@ fn handle_repo_command(
                 process::exit(1);
             }
 
-            output.header(&format!("Deleting {}...", skill));
+            let repo_config = config.write_repository(repo_name)?;
+            let repo = Repository::from_config(repo_config)?;
+            output.header(&format!(
+                "Deleting {} from {}...",
+                skill,
+                repo_config.display_name()
+            ));
             repo.delete(&skill, version.as_deref(), output)?;
             output.status("Done", &format!("Deleted {}", skill));
         }
 
+        RepoAction::Yank {
+            skill,
+            version,
+            reason,
+        } => {
+            let repo_config = config.write_repository(repo_name)?;
+            let repo = Repository::from_config(repo_config)?;
+            repo.yank(&skill, &version, reason.as_deref())?;
+            output.status(
+                "Yanked",
+                &format!("{} v{} in {}", skill, version, repo_config.display_name()),
+            );
+        }
+
+        RepoAction::Deprecate {
+            skill,
+            message,
+            replaced_by,
+            undo,
+        } => {
+            let repo_config = config.write_repository(repo_name)?;
+            let repo = Repository::from_config(repo_config)?;
+            if undo {
+                if repo.undeprecate(&skill)? {
+                    output.status(
+                        "Undeprecated",
+                        &format!("{} in {}", skill, repo_config.display_name()),
+                    );
+                } else {
+                    output.info(&format!("{} is not deprecated", skill));
+                }
+                return Ok(());
+            }
+            if let Some(replacement) = &replaced_by {
+                if repo.list(Some(replacement))?.skills.is_empty() {
+                    output.warn(&format!(
+                        "{} is not in {}; installs can only follow it from repositories that have it",
+                        replacement,
+                        repo_config.display_name()
+                    ));
+                }
+            }
+            repo.deprecate(&skill, message.as_deref(), replaced_by.as_deref())?;
+            output.status(
+                "Deprecated",
+                &format!("{} in {}", skill, repo_config.display_name()),
+            );
+        }
+
+        RepoAction::Unyank { skill, version } => {
+            let repo_config = config.write_repository(repo_name)?;
+            let repo = Repository::from_config(repo_config)?;
+            if repo.unyank(&skill, &version)? {
+                output.status(
+                    "Unyanked",
+                    &format!("{} v{} in {}", skill, version, repo_config.display_name()),
+                );
+            } else {
+                output.info(&format!("{} v{} is not yanked", skill, version));
+            }
+        }
+
         RepoAction::List { skill } => {
-            let index = repo.list(skill.as_deref())?;
+            let repos = read_repositories(&config, repo_name)?;
+            let mut listed = Vec::new();
+            for rc in &repos {
+                match Repository::from_config(rc).and_then(|r| r.list(skill.as_deref())) {
+                    Ok(index) => listed.push((rc.display_name(), index)),
+                    Err(e) if repos.len() > 1 => {
+                        output.warn(&format!(
+                            "Skipping repository '{}': {}",
+                            rc.display_name(),
+                            e
+                        ));
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
 
-            if index.skills.is_empty() {
+            if listed.iter().all(|(_, index)| index.skills.is_empty()) {
                 output.info("No skills found in repository.");
             } else {
                 output.header("Repository skills:");
                 output.newline();
-                for entry in &index.skills {
-                    output.info(&format!("  {} - {}", entry.name, entry.description));
-                    if !entry.llms_txt_url.is_empty() {
-                        output.step(&format!("Source: {}", entry.llms_txt_url));
+                for (repo_display, index) in &listed {
+                    for entry in &index.skills {
+                        output.info(&format!("  {} - {}", entry.name, entry.description));
+                        output.step(&format!("Repository: {}", repo_display));
+                        if !entry.llms_txt_url.is_empty() {
+                            output.step(&format!("Source: {}", entry.llms_txt_url));
+                        }
+                        if !entry.tags.is_empty() {
+                            output.step(&format!("Tags: {}", entry.tags.join(", ")));
+                        }
+                        if let Some(notice) = entry.deprecation_notice() {
+                            output.step(&notice);
+                        }
+                        let mut versions: Vec<&str> =
+                            entry.versions.keys().map(|s| s.as_str()).collect();
+                        versions.sort();
+                        versions.reverse();
+                        let versions: Vec<String> = versions
+                            .iter()
+                            .map(|v| {
+                                if entry.is_yanked(v) {
+                                    format!("{} (yanked)", v)
+                                } else {
+                                    v.to_string()
+                                }
+                            })
+                            .collect();
+                        output.step(&format!("Versions: {}", versions.join(", ")));
                     }
-                    let mut versions: Vec<&str> =
-                        entry.versions.keys().map(|s| s.as_str()).collect();
-                    versions.sort();
-                    versions.reverse();
-                    output.step(&format!("Versions: {}", versions.join(", ")));
                 }
             }
         }
+
+        RepoAction::Fsck { fix } => {
+            let repos = if fix {
+                vec![config.write_repository(repo_name)?]
+            } else {
+                read_repositories(&config, repo_name)?
+            };
+            let mut problems = 0;
+            for rc in &repos {
+                output.header(&format!("Checking {}...", rc.display_name()));
+                let repo = Repository::from_config(rc)?;
+                problems += run_fsck(&repo, fix, output)?;
+            }
+            if problems > 0 {
+                anyhow::bail!("{}", fsck_failure(problems, fix));
+            }
+        }
+
+        RepoAction::Reindex => {
+            let repo_config = config.write_repository(repo_name)?;
+            output.header(&format!("Reindexing {}...", repo_config.display_name()));
+            run_reindex(&Repository::from_config(repo_config)?, output)?;
+        }
+
+        RepoAction::Mirror {
+            from,
+            to,
+            skill,
+            versions,
+            dry_run,
+        } => {
+            let (from_name, source) = open_repository(&config, &from, false)?;
+            let (to_name, dest) = open_repository(&config, &to, true)?;
+            output.header(&format!(
+                "{} {} to {}...",
+                if dry_run {
+                    "Checking mirror from"
+                } else {
+                    "Mirroring"
+                },
+                from_name,
+                to_name
+            ));
+            let report = source.mirror_to(
+                &dest,
+                &MirrorOptions {
+                    skill: skill.as_deref(),
+                    versions: versions.as_ref(),
+                    dry_run,
+                },
+                output,
+            )?;
+            let summary = format!(
+                "{} versions, {} objects {}, {} already up to date",
+                report.versions.len(),
+                report.copied.len(),
+                if dry_run { "to copy" } else { "copied" },
+                report.unchanged.len()
+            );
+            output.status(if dry_run { "Dry run" } else { "Done" }, &summary);
+        }
+
+        RepoAction::Push {
+            reference,
+            file,
+            changelog,
+            force,
+        } => {
+            let reference = OciReference::parse(&reference)?;
+            let skill = reference.skill_name();
+            let version = reference.tag.as_deref().with_context(|| {
+                format!(
+                    "Push needs a version tag, e.g. oci://{}/{}:1.0.0",
+                    reference.registry, reference.repository
+                )
+            })?;
+            let skill_file = file.unwrap_or_else(|| PathBuf::from(format!("dist/{}.skill", skill)));
+            if !skill_file.exists() {
+                anyhow::bail!("Skill file not found: {}", skill_file.display());
+            }
+
+            let skill_config = config.find_skill(skill);
+            let description = skill_config.map(|s| s.description.as_str()).unwrap_or("");
+            let llms_txt_url = skill_config.map(|s| s.llms_txt_url.as_str()).unwrap_or("");
+
+            let repo = Repository::new(OciStorageClient::for_reference(&reference)?);
+            output.header(&format!(
+                "Pushing {} v{} to {}...",
+                skill, version, reference.registry
+            ));
+            upload_skill(
+                &repo,
+                UploadParams {
+                    name: skill,
+                    version,
+                    description,
+                    llms_txt_url,
+                    skill_file: &skill_file,
+                    changelog: None,
+                    source_dir: None,
+                },
+                changelog,
+                force,
+                output,
+            )?;
+            output.status("Done", &format!("Pushed {} v{}", skill, version));
+        }
+
+        RepoAction::Pull {
+            reference,
+            output: output_dir,
+        } => {
+            let reference = OciReference::parse(&reference)?;
+            let skill = reference.skill_name();
+            let client = OciStorageClient::for_reference(&reference)?;
+            let (version, data) = client.pull(skill, reference.tag.as_deref())?;
+
+            let dir = output_dir.unwrap_or_else(|| PathBuf::from("."));
+            std::fs::create_dir_all(&dir)
+                .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+            let path = dir.join(format!("{}.skill", skill));
+            std::fs::write(&path, data)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            output.status(
+                "Pulled",
+                &format!("{} v{} to {}", skill, version, path.display()),
+            );
+        }
     }
 
     Ok(())
 }
 
-fn handle_local_command(
+fn handle_config_command(
     config_path: Option<&std::path::Path>,
-    action: LocalAction,
+    project_root: &std::path::Path,
+    action: ConfigAction,
     output: &Output,
 ) -> Result<()> {
-    let config = Config::load_with_fallback(config_path)?;
+    match action {
+        ConfigAction::Show { origin } => {
+            let layered = Config::load_layered(config_path, project_root)?;
+            if origin {
+                output.header("Effective configuration:");
+                output.newline();
+                let rows: Vec<Vec<String>> = layered
+                    .entries()
+                    .into_iter()
+                    .map(|(path, value, origin)| {
+                        vec![
+                            path,
+                            value.to_string(),
+                            format!("{} ({})", origin.layer.name(), origin.source),
+                        ]
+                    })
+                    .collect();
+                output.table(&rows);
+            } else {
+                let json = serde_json::to_string_pretty(&layered.config)
+                    .context("Failed to serialize config")?;
+                println!("{}", json);
+            }
+        }
+
+        ConfigAction::Validate { file } => {
+            let files = match file {
+                Some(file) => vec![file],
+                None => {
+                    let mut files = vec![
+                        skill_builder::config::global_config_path(),
+                        project_root.join(skill_builder::config::PROJECT_CONFIG_FILE),
+                    ];
+                    files.extend(config_path.map(|p| p.to_path_buf()));
+                    files.retain(|f| f.exists());
+                    files
+                }
+            };
+            if files.is_empty() {
+                output.info("No config files found.");
+                return Ok(());
+            }
+
+            let mut problems = 0;
+            for file in &files {
+                let text = std::fs::read_to_string(file)
+                    .with_context(|| format!("Failed to read {}", file.display()))?;
+                match validate_config_text(&text) {
+                    Ok(issues) if issues.is_empty() => {
+                        output.status("Valid", &file.display().to_string());
+                    }
+                    Ok(issues) => {
+                        for issue in &issues {
+                            output.error(&format!("{}:{}", file.display(), issue));
+                        }
+                        problems += issues.len();
+                    }
+                    Err(e) => {
+                        output.error(&format!("{}: {}", file.display(), e));
+                        problems += 1;
+                    }
+                }
+            }
+
+            if problems > 0 {
+                anyhow::bail!("Found {} problem(s) in config", problems);
+            }
+        }
+
+        ConfigAction::AddSkill {
+            name,
+            url,
+            description,
+            base_url,
+            path_prefix,
+            global,
+        } => {
+            let skill = SkillConfig {
+                name,
+                description: description.unwrap_or_default(),
+                llms_txt_url: url,
+                base_url,
+                path_prefix,
+            };
+            let path = edit_config_file(config_path, project_root, global, |text| {
+                config_file::add_skill(text, &skill)
+            })?;
+            output.status("Added", &format!("{} to {}", skill.name, path.display()));
+        }
+
+        ConfigAction::RemoveSkill { name, global } => {
+            let path = edit_config_file(config_path, project_root, global, |text| {
+                config_file::remove_skill(text, &name)
+            })?;
+            output.status("Removed", &format!("{} from {}", name, path.display()));
+        }
+
+        ConfigAction::Set { key, value, global } => {
+            let path = edit_config_file(config_path, project_root, global, |text| {
+                config_file::set_value(text, &key, config_file::parse_set_value(&value))
+            })?;
+            output.status("Set", &format!("{} in {}", key, path.display()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply an edit to the config file chosen by `--config`, `--global` or the
+/// project root, creating it if missing. Returns the edited file's path.
+fn edit_config_file<F>(
+    config_path: Option<&std::path::Path>,
+    project_root: &std::path::Path,
+    global: bool,
+    edit: F,
+) -> Result<PathBuf>
+where
+    F: FnOnce(&str) -> Result<String>,
+{
+    let path = match config_path {
+        Some(path) => path.to_path_buf(),
+        None if global => skill_builder::config::global_config_path(),
+        None => project_root.join(skill_builder::config::PROJECT_CONFIG_FILE),
+    };
+
+    let text = if path.exists() {
+        std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+    } else {
+        "{}\n".to_string()
+    };
+
+    let edited = edit(&text)?;
+    config_file::check_edited(&edited)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, edited).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+fn handle_github_command(action: GithubAction, output: &Output) -> Result<()> {
+    match action {
+        GithubAction::Publish {
+            skill,
+            version,
+            repo,
+            file,
+            signature,
+            notes,
+            target,
+            force,
+        } => {
+            let skill_file = file.unwrap_or_else(|| PathBuf::from(format!("dist/{}.skill", skill)));
+            if !skill_file.exists() {
+                anyhow::bail!("Skill file not found: {}", skill_file.display());
+            }
+            let signature = signature.or_else(|| find_signature(&skill_file));
+            let notes = notes
+                .map(|path| {
+                    std::fs::read_to_string(&path)
+                        .with_context(|| format!("Failed to read {}", path.display()))
+                })
+                .transpose()?;
+
+            output.header(&format!(
+                "Publishing {} v{} to GitHub releases of {}...",
+                skill, version, repo
+            ));
+            let release = publish_release(
+                &GitHubClient::from_env()?,
+                &PublishParams {
+                    repo: &repo,
+                    skill: &skill,
+                    version: &version,
+                    skill_file: &skill_file,
+                    signature: signature.as_deref(),
+                    notes: notes.as_deref(),
+                    target: target.as_deref(),
+                    force,
+                },
+                output,
+            )?;
+            let location = if release.html_url.is_empty() {
+                format!("{} v{}", skill, version)
+            } else {
+                format!("{} v{} at {}", skill, version, release.html_url)
+            };
+            output.status("Published", &location);
+        }
+    }
+    Ok(())
+}
 
-    let local_path = config
-        .repository
-        .as_ref()
-        .map(|r| r.local_repo_path())
-        .unwrap_or_else(skill_builder::config::default_local_repo_path);
+fn handle_local_command(config: Config, action: LocalAction, output: &Output) -> Result<()> {
+    let local_path = config.local_repo_path();
+    let local_config = config.local_repository().cloned().unwrap_or_default();
 
     let client = LocalStorageClient::with_dir(&local_path);
+    let cache = SkillCache::new(
+        LocalStorageClient::with_dir(&local_path),
+        CachePolicy::from_config(&local_config),
+    );
 
     match action {
         LocalAction::List => {
@@ -659,11 +2502,16 @@ fn handle_local_command(
                         versions.sort();
                         versions.reverse();
                         for ver in &versions {
-                            rows.push(vec![entry.name.clone(), format!("v{}", ver)]);
+                            let mut version = format!("v{}", ver);
+                            if entry.is_yanked(ver) {
+                                version.push_str(" (yanked)");
+                            }
+                            rows.push(vec![entry.name.clone(), version]);
                         }
                     }
                     output.table(&rows);
                     output.newline();
+                    print_cache_summary(&cache, output);
                     output.info(&format!("Local repository: {}", local_path.display()));
                 }
                 _ => {
@@ -679,7 +2527,9 @@ fn handle_local_command(
                                 output.step(key);
                             }
                         }
+                        output.info("No index found; run `sb local reindex` to rebuild it.");
                     }
+                    print_cache_summary(&cache, output);
                     output.info(&format!("Local repository: {}", local_path.display()));
                 }
             }
@@ -694,6 +2544,9 @@ fn handle_local_command(
                         output.warn(&format!("Failed to delete {}: {}", key, e));
                     }
                 }
+                if let Err(e) = cache.remove(&name, None) {
+                    output.warn(&format!("Failed to clear cached {}: {:#}", name, e));
+                }
                 output.status("Cleared", &format!("local repository for {}", name));
             } else {
                 let keys = client.list_objects("skills/").unwrap_or_default();
@@ -702,10 +2555,201 @@ fn handle_local_command(
                         output.warn(&format!("Failed to delete {}: {}", key, e));
                     }
                 }
+                if let Err(e) = cache.clear() {
+                    output.warn(&format!("Failed to clear the download cache: {:#}", e));
+                }
                 output.status("Cleared", "all skills from local repository");
             }
         }
+
+        LocalAction::Publish {
+            skill,
+            version,
+            file,
+            changelog,
+            force,
+            source_dir,
+        } => {
+            let skill_file = file.unwrap_or_else(|| PathBuf::from(format!("dist/{}.skill", skill)));
+            if !skill_file.exists() {
+                anyhow::bail!("Skill file not found: {}", skill_file.display());
+            }
+
+            let skill_config = config.find_skill(&skill);
+            let description = skill_config.map(|s| s.description.as_str()).unwrap_or("");
+            let llms_txt_url = skill_config.map(|s| s.llms_txt_url.as_str()).unwrap_or("");
+
+            output.header(&format!(
+                "Publishing {} v{} to {}...",
+                skill,
+                version,
+                local_path.display()
+            ));
+            let repo = Repository::new(LocalStorageClient::new(&local_path)?);
+            upload_skill(
+                &repo,
+                UploadParams {
+                    name: &skill,
+                    version: &version,
+                    description,
+                    llms_txt_url,
+                    skill_file: &skill_file,
+                    changelog: None,
+                    source_dir: source_dir.as_deref(),
+                },
+                changelog,
+                force,
+                output,
+            )?;
+            output.status("Done", &format!("Published {} v{}", skill, version));
+        }
+
+        LocalAction::Prune {
+            max_size_mb,
+            max_age_days,
+            keep_versions,
+            dry_run,
+        } => {
+            let policy = CachePolicy::from_config(&LocalRepositoryConfig {
+                max_size_mb: max_size_mb.or(local_config.max_size_mb),
+                max_age_days: max_age_days.or(local_config.max_age_days),
+                keep_versions: keep_versions.or(local_config.keep_versions),
+                ..local_config.clone()
+            });
+            if !policy.is_limited() {
+                output.info(
+                    "No cache limits configured; set local.max_size_mb, local.max_age_days or local.keep_versions, or pass them as flags.",
+                );
+            }
+            let cache = SkillCache::new(LocalStorageClient::with_dir(&local_path), policy);
+            let report = cache.prune(dry_run)?;
+            for entry in &report.evicted {
+                output.step(&format!(
+                    "{} {} v{} ({}, last used {})",
+                    if dry_run { "Would evict" } else { "Evicted" },
+                    entry.name,
+                    entry.version,
+                    format_size(entry.size),
+                    entry.last_accessed.format("%Y-%m-%d")
+                ));
+            }
+            output.status(
+                if dry_run { "Dry run" } else { "Pruned" },
+                &format!(
+                    "{} entries ({}) {}, {} entries ({}) kept",
+                    report.evicted.len(),
+                    format_size(report.freed()),
+                    if dry_run { "to evict" } else { "evicted" },
+                    report.kept,
+                    format_size(report.kept_size)
+                ),
+            );
+        }
+
+        LocalAction::Fsck { fix } => {
+            output.header(&format!("Checking {}...", local_path.display()));
+            let problems = run_fsck(&Repository::new(client), fix, output)?;
+            if problems > 0 {
+                anyhow::bail!("{}", fsck_failure(problems, fix));
+            }
+        }
+
+        LocalAction::Reindex => {
+            output.header(&format!("Reindexing {}...", local_path.display()));
+            run_reindex(&Repository::new(client), output)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// One line on what the download cache holds, if anything.
+fn print_cache_summary(cache: &SkillCache, output: &Output) {
+    if let Ok(index) = cache.index() {
+        if !index.entries.is_empty() {
+            output.info(&format!(
+                "Download cache: {} versions, {}",
+                index.entries.len(),
+                format_size(index.total_size())
+            ));
+        }
+    }
+}
+
+/// A byte count in human units.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Check a repository, rebuilding its index first if `fix` is set and there
+/// are problems. Returns the number of problems left.
+fn run_fsck<S: StorageOperations>(
+    repo: &Repository<S>,
+    fix: bool,
+    output: &Output,
+) -> Result<usize> {
+    let mut report = repo.fsck()?;
+    if !report.is_clean() && fix {
+        for issue in &report.issues {
+            output.step(&format!("Found: {}", issue));
+        }
+        run_reindex(repo, output)?;
+        report = repo.fsck()?;
     }
 
+    for issue in &report.issues {
+        output.warn(&issue.to_string());
+    }
+    if report.is_clean() {
+        output.status(
+            "Clean",
+            &format!(
+                "{} packages checked, no problems found",
+                report.packages_checked
+            ),
+        );
+    }
+    Ok(report.issues.len())
+}
+
+fn fsck_failure(problems: usize, fix: bool) -> String {
+    if fix {
+        format!(
+            "{} problem(s) remain that rebuilding the index can't fix",
+            problems
+        )
+    } else {
+        format!(
+            "{} problem(s) found. Run with --fix to rebuild the index.",
+            problems
+        )
+    }
+}
+
+fn run_reindex<S: StorageOperations>(repo: &Repository<S>, output: &Output) -> Result<()> {
+    let rebuild = repo.reindex()?;
+    for (key, reason) in &rebuild.skipped {
+        output.warn(&format!("Skipped {}: {}", key, reason));
+    }
+    let versions: usize = rebuild.index.skills.iter().map(|e| e.versions.len()).sum();
+    output.status(
+        "Reindexed",
+        &format!(
+            "{} skills, {} versions",
+            rebuild.index.skills.len(),
+            versions
+        ),
+    );
     Ok(())
 }
@@ -0,0 +1,187 @@
+//! Copying skills between repositories.
+
+use anyhow::{Context, Result};
+
+use crate::index::{compare_semver, load_index, update_index, IndexEntry};
+use crate::output::Output;
+use crate::storage::{reader_sha256, StorageOperations};
+use crate::version_range::VersionRange;
+
+/// What to mirror.
+#[derive(Debug, Default)]
+pub struct MirrorOptions<'a> {
+    /// Only this skill (default: every skill).
+    pub skill: Option<&'a str>,
+    /// Only versions in this range (default: every version).
+    pub versions: Option<&'a VersionRange>,
+    /// Report what would be copied without writing anything.
+    pub dry_run: bool,
+}
+
+/// Result of a mirror.
+#[derive(Debug, Default, PartialEq)]
+pub struct MirrorReport {
+    /// Keys copied (or that would be, on a dry run).
+    pub copied: Vec<String>,
+    /// Keys already present in the destination with the same content.
+    pub unchanged: Vec<String>,
+    /// Skill versions mirrored, as (name, version).
+    pub versions: Vec<(String, String)>,
+}
+
+/// SHA-256 of an object, or `None` if it doesn't exist.
+fn object_sha256<S: StorageOperations>(client: &S, key: &str) -> Result<Option<String>> {
+    if client.head_object(key)?.is_none() {
+        return Ok(None);
+    }
+    let mut reader = client.get_object_reader(key)?;
+    let sha = reader_sha256(&mut reader).with_context(|| format!("Failed to read {}", key))?;
+    Ok(Some(sha))
+}
+
+/// Keys stored for a skill version: the package, then changelog and source archive.
+fn version_keys(entry: &IndexEntry, version: &str, package_key: &str) -> [String; 3] {
+    [
+        package_key.to_string(),
+        format!("skills/{}/{}/CHANGELOG.md", entry.name, version),
+        format!(
+            "source/{}/{}/{}-source.zip",
+            entry.name, version, entry.name
+        ),
+    ]
+}
+
+/// Copy skills, with their changelogs, source archives and index entries,
+/// from one repository to another.
+///
+/// Objects the destination already holds with the same SHA-256 are left
+/// alone, so an interrupted mirror can simply be run again. Index entries are
+/// merged into the destination index: mirrored versions are added, the
+/// entry's other fields are taken from the source, and versions only the
+/// destination has are kept.
+pub fn mirror<F, T>(
+    from: &F,
+    to: &T,
+    options: &MirrorOptions,
+    output: &Output,
+) -> Result<MirrorReport>
+where
+    F: StorageOperations,
+    T: StorageOperations,
+{
+    let source = load_index(from).context("Failed to read the source index")?;
+    if let Some(name) = options.skill {
+        if source.find_skill(name).is_none() {
+            anyhow::bail!("Skill '{}' not found in the source repository", name);
+        }
+    }
+
+    let mut report = MirrorReport::default();
+    let mut entries = Vec::new();
+    for entry in &source.skills {
+        if options.skill.is_some_and(|name| name != entry.name) {
+            continue;
+        }
+        let mut versions: Vec<(&String, &String)> = entry
+            .versions
+            .iter()
+            .filter(|(v, _)| options.versions.is_none_or(|range| range.matches(v)))
+            .collect();
+        if versions.is_empty() {
+            continue;
+        }
+        versions.sort_by(|a, b| compare_semver(a.0, b.0));
+
+        let mut mirrored = IndexEntry {
+            versions: Default::default(),
+            checksums: Default::default(),
+            published: Default::default(),
+            yanked: Default::default(),
+            ..entry.clone()
+        };
+        'versions: for (version, package_key) in versions {
+            let mut checksum = entry.checksums.get(version).cloned();
+            for key in version_keys(entry, version, package_key) {
+                let Some(sha) = object_sha256(from, &key)? else {
+                    if key == *package_key {
+                        output.warn(&format!(
+                            "Skipping {} v{}: {} is missing from the source",
+                            entry.name, version, key
+                        ));
+                        continue 'versions;
+                    }
+                    continue;
+                };
+                if key == *package_key {
+                    checksum.get_or_insert_with(|| sha.clone());
+                }
+                if object_sha256(to, &key)?.as_deref() == Some(sha.as_str()) {
+                    report.unchanged.push(key);
+                    continue;
+                }
+                if options.dry_run {
+                    output.step(&format!("Would copy: {}", key));
+                } else {
+                    let mut reader = from.get_object_reader(&key)?;
+                    to.put_object_from_reader(&key, &mut reader)
+                        .with_context(|| format!("Failed to copy {}", key))?;
+                    output.step(&format!("Copied: {}", key));
+                }
+                report.copied.push(key);
+            }
+
+            mirrored
+                .versions
+                .insert(version.clone(), package_key.clone());
+            if let Some(checksum) = checksum {
+                mirrored.checksums.insert(version.clone(), checksum);
+            }
+            if let Some(published) = entry.published.get(version) {
+                mirrored
+                    .published
+                    .insert(version.clone(), published.clone());
+            }
+            if let Some(reason) = entry.yanked.get(version) {
+                mirrored.yanked.insert(version.clone(), reason.clone());
+            }
+            report.versions.push((entry.name.clone(), version.clone()));
+        }
+        if !mirrored.versions.is_empty() {
+            entries.push(mirrored);
+        }
+    }
+
+    if options.dry_run || entries.is_empty() {
+        return Ok(report);
+    }
+    update_index(to, |index| {
+        for mirrored in &entries {
+            match index.find_skill_mut(&mirrored.name) {
+                Some(existing) => {
+                    let mut versions = std::mem::take(&mut existing.versions);
+                    let mut checksums = std::mem::take(&mut existing.checksums);
+                    let mut published = std::mem::take(&mut existing.published);
+                    versions.extend(mirrored.versions.clone());
+                    checksums.extend(mirrored.checksums.clone());
+                    published.extend(mirrored.published.clone());
+                    // The source decides whether a mirrored version is yanked
+                    let mut yanked = std::mem::take(&mut existing.yanked);
+                    yanked.retain(|v, _| !mirrored.versions.contains_key(v));
+                    yanked.extend(mirrored.yanked.clone());
+                    *existing = IndexEntry {
+                        versions,
+                        checksums,
+                        published,
+                        yanked,
+                        ..mirrored.clone()
+                    };
+                }
+                None => index.skills.push(mirrored.clone()),
+            }
+        }
+        Ok(())
+    })
+    .context("Failed to update the destination index")?;
+    output.step("Updated index");
+    Ok(report)
+}
@@ -0,0 +1,952 @@
+//! OCI registry backend: skills published as OCI artifacts.
+//!
+//! Each skill version is one artifact, `<namespace>/<skill>:<version>`, whose
+//! layers are the version's files (the .skill package, CHANGELOG.md, source
+//! archive) named by their `org.opencontainers.image.title` annotation. The
+//! skills index is stored as `<namespace>/skill-builder-index:latest`, and its
+//! version lists are rebuilt from registry tags whenever it is read.
+
+use anyhow::{Context, Result};
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{
+    ACCEPT, CONTENT_TYPE, IF_MATCH, IF_NONE_MATCH, LINK, LOCATION, WWW_AUTHENTICATE,
+};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use crate::index::{compare_semver, SkillsIndex, INDEX_KEY};
+use crate::package::read_skill_frontmatter;
+use crate::storage::{ObjectMetadata, PreconditionFailed, PutCondition, StorageOperations};
+
+/// Artifact type of a published skill version.
+pub const SKILL_ARTIFACT_TYPE: &str = "application/vnd.skill-builder.skill.v1";
+
+/// Media type of the .skill package layer.
+pub const SKILL_LAYER_MEDIA_TYPE: &str = "application/vnd.skill-builder.skill.v1+zip";
+
+/// Artifact type of the stored skills index.
+pub const INDEX_ARTIFACT_TYPE: &str = "application/vnd.skill-builder.index.v1";
+
+const MANIFEST_MEDIA_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+const EMPTY_CONFIG_MEDIA_TYPE: &str = "application/vnd.oci.empty.v1+json";
+const EMPTY_CONFIG: &[u8] = b"{}";
+const TITLE_ANNOTATION: &str = "org.opencontainers.image.title";
+const INDEX_REPOSITORY: &str = "skill-builder-index";
+const INDEX_TAG: &str = "latest";
+
+/// Environment variables holding registry credentials.
+pub const USERNAME_ENV: &str = "SB_OCI_USERNAME";
+pub const PASSWORD_ENV: &str = "SB_OCI_PASSWORD";
+
+/// Set to `1` to talk plain HTTP to registries other than localhost.
+pub const INSECURE_ENV: &str = "SB_OCI_INSECURE";
+
+/// A parsed `oci://registry/namespace/skill:tag` reference.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OciReference {
+    /// Registry host, with optional port.
+    pub registry: String,
+    /// Repository path within the registry, e.g. `team/my-skill`.
+    pub repository: String,
+    pub tag: Option<String>,
+}
+
+impl OciReference {
+    /// Parse an `oci://` reference.
+    pub fn parse(reference: &str) -> Result<Self> {
+        let rest = reference
+            .strip_prefix("oci://")
+            .with_context(|| format!("OCI references must start with oci://: {}", reference))?;
+        let (registry, path) = rest
+            .split_once('/')
+            .with_context(|| format!("OCI reference is missing a repository: {}", reference))?;
+
+        let (repository, tag) = match path.rsplit_once(':') {
+            Some((repo, tag)) if !tag.contains('/') => (repo, Some(tag.to_string())),
+            _ => (path, None),
+        };
+        let repository = repository.trim_matches('/');
+        if registry.is_empty() || repository.is_empty() {
+            anyhow::bail!("Invalid OCI reference: {}", reference);
+        }
+        if repository.chars().any(|c| c.is_ascii_uppercase()) {
+            anyhow::bail!("OCI repository names must be lowercase: {}", repository);
+        }
+
+        Ok(Self {
+            registry: registry.to_string(),
+            repository: repository.to_string(),
+            tag,
+        })
+    }
+
+    /// Skill name: the last segment of the repository path.
+    pub fn skill_name(&self) -> &str {
+        self.repository
+            .rsplit_once('/')
+            .map(|(_, name)| name)
+            .unwrap_or(&self.repository)
+    }
+
+    /// Namespace holding the skill: the repository path without the skill name.
+    pub fn namespace(&self) -> &str {
+        self.repository
+            .rsplit_once('/')
+            .map(|(ns, _)| ns)
+            .unwrap_or("")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Descriptor {
+    media_type: String,
+    digest: String,
+    size: u64,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    annotations: BTreeMap<String, String>,
+}
+
+impl Descriptor {
+    fn for_data(media_type: &str, data: &[u8]) -> Self {
+        Self {
+            media_type: media_type.to_string(),
+            digest: digest(data),
+            size: data.len() as u64,
+            annotations: BTreeMap::new(),
+        }
+    }
+
+    fn metadata(&self) -> ObjectMetadata {
+        ObjectMetadata {
+            size: self.size,
+            etag: self.digest.clone(),
+            last_modified: None,
+            content_type: Some(self.media_type.clone()),
+        }
+    }
+
+    fn title(&self) -> Option<&str> {
+        self.annotations.get(TITLE_ANNOTATION).map(|s| s.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    schema_version: u32,
+    media_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    artifact_type: Option<String>,
+    config: Descriptor,
+    layers: Vec<Descriptor>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    annotations: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    fn new(artifact_type: &str) -> Self {
+        Self {
+            schema_version: 2,
+            media_type: MANIFEST_MEDIA_TYPE.to_string(),
+            artifact_type: Some(artifact_type.to_string()),
+            config: Descriptor::for_data(EMPTY_CONFIG_MEDIA_TYPE, EMPTY_CONFIG),
+            layers: Vec::new(),
+            annotations: BTreeMap::new(),
+        }
+    }
+
+    fn layer(&self, title: &str) -> Option<&Descriptor> {
+        self.layers.iter().find(|l| l.title() == Some(title))
+    }
+}
+
+/// `sha256:<hex>` content digest.
+fn digest(data: &[u8]) -> String {
+    format!("sha256:{}", hex::encode(Sha256::digest(data)))
+}
+
+/// Media type for a file stored as a layer.
+fn layer_media_type(title: &str) -> &'static str {
+    match title.rsplit_once('.').map(|(_, ext)| ext) {
+        Some("skill") => SKILL_LAYER_MEDIA_TYPE,
+        Some("md") => "text/markdown",
+        Some("zip") => "application/zip",
+        Some("json") => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Minimal client for the OCI distribution API.
+struct RegistryClient {
+    base_url: String,
+    client: Client,
+    /// Bearer tokens by the scope they were issued for.
+    tokens: RefCell<HashMap<String, String>>,
+    /// The scope whose token each repository was last accessed with.
+    scopes: RefCell<HashMap<String, String>>,
+}
+
+impl RegistryClient {
+    fn new(registry: &str) -> Result<Self> {
+        crate::offline::ensure_online(format!("OCI registry {}", registry))?;
+        let host = match registry.rsplit_once(':') {
+            Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+            _ => registry,
+        };
+        let insecure = matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+            || std::env::var(INSECURE_ENV).is_ok_and(|v| v == "1" || v == "true");
+        let scheme = if insecure { "http" } else { "https" };
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(60))
+            .user_agent("sb/1.0")
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            base_url: format!("{}://{}", scheme, registry),
+            client,
+            tokens: RefCell::new(HashMap::new()),
+            scopes: RefCell::new(HashMap::new()),
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/v2/{}", self.base_url, path)
+    }
+
+    fn credentials() -> Option<(String, String)> {
+        Some((
+            std::env::var(USERNAME_ENV).ok()?,
+            std::env::var(PASSWORD_ENV).ok()?,
+        ))
+    }
+
+    /// Attach the token last used for `repository`, or else basic credentials.
+    fn authorize(&self, repository: &str, request: RequestBuilder) -> RequestBuilder {
+        let token = self
+            .scopes
+            .borrow()
+            .get(repository)
+            .and_then(|scope| self.tokens.borrow().get(scope).cloned());
+        if let Some(token) = token {
+            return request.bearer_auth(token);
+        }
+        match Self::credentials() {
+            Some((user, password)) => request.basic_auth(user, Some(password)),
+            None => request,
+        }
+    }
+
+    /// Send a request about `repository`, answering a bearer-token challenge
+    /// once if the registry asks for a scope we hold no token for.
+    ///
+    /// Registries scope tokens to a repository and its actions, so tokens are
+    /// cached per scope: the index and each skill live in separate
+    /// repositories, and a push needs more than a pull.
+    fn send(&self, repository: &str, request: RequestBuilder) -> Result<Response> {
+        let retry = request.try_clone();
+        let response = self
+            .authorize(repository, request)
+            .send()
+            .with_context(|| format!("Failed to reach registry {}", self.base_url))?;
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        let challenge = response
+            .headers()
+            .get(WWW_AUTHENTICATE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let Some(params) = challenge.strip_prefix("Bearer ") else {
+            anyhow::bail!(
+                "Registry {} requires authentication. Set {} and {}.",
+                self.base_url,
+                USERNAME_ENV,
+                PASSWORD_ENV
+            );
+        };
+        let params = parse_challenge(params);
+        let scope = params.get("scope").copied().unwrap_or_default().to_string();
+
+        // The token for this very scope was refused; another won't help
+        let sent = self.scopes.borrow().get(repository) == Some(&scope);
+        if sent && self.tokens.borrow().contains_key(&scope) {
+            return Ok(response);
+        }
+        let Some(retry) = retry else {
+            return Ok(response);
+        };
+        if !self.tokens.borrow().contains_key(&scope) {
+            let token = self.fetch_token(&params)?;
+            self.tokens.borrow_mut().insert(scope.clone(), token);
+        }
+        self.scopes
+            .borrow_mut()
+            .insert(repository.to_string(), scope);
+        self.authorize(repository, retry)
+            .send()
+            .with_context(|| format!("Failed to reach registry {}", self.base_url))
+    }
+
+    /// Exchange credentials (or nothing, for anonymous pulls) for a bearer
+    /// token answering the challenge `params`.
+    fn fetch_token(&self, params: &BTreeMap<&str, &str>) -> Result<String> {
+        let realm = params
+            .get("realm")
+            .context("Registry auth challenge has no realm")?;
+
+        let query: Vec<(&str, &str)> = ["service", "scope"]
+            .iter()
+            .filter_map(|k| params.get(k).map(|v| (*k, *v)))
+            .collect();
+        let mut request = self.client.get(*realm).query(&query);
+        if let Some((user, password)) = Self::credentials() {
+            request = request.basic_auth(user, Some(password));
+        }
+
+        let response = request.send().context("Failed to fetch registry token")?;
+        if !response.status().is_success() {
+            anyhow::bail!("Registry token request failed: HTTP {}", response.status());
+        }
+        let body: serde_json::Value = serde_json::from_slice(&response.bytes()?)
+            .context("Invalid registry token response")?;
+        body.get("token")
+            .or_else(|| body.get("access_token"))
+            .and_then(|t| t.as_str())
+            .map(str::to_string)
+            .context("Registry token response has no token")
+    }
+
+    /// Fetch a manifest and its raw bytes, or `None` if the tag doesn't exist.
+    fn get_manifest(
+        &self,
+        repository: &str,
+        reference: &str,
+    ) -> Result<Option<(Manifest, Vec<u8>)>> {
+        let url = self.url(&format!("{}/manifests/{}", repository, reference));
+        let response = self.send(
+            repository,
+            self.client.get(&url).header(ACCEPT, MANIFEST_MEDIA_TYPE),
+        )?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => {
+                let raw = response.bytes()?.to_vec();
+                let manifest = serde_json::from_slice(&raw).with_context(|| {
+                    format!("Invalid manifest for {}:{}", repository, reference)
+                })?;
+                Ok(Some((manifest, raw)))
+            }
+            status => anyhow::bail!("HTTP {} fetching manifest {}", status, url),
+        }
+    }
+
+    /// Push a manifest. A `condition` on the manifest digest is sent as
+    /// `If-Match` / `If-None-Match`, which registries may ignore.
+    fn put_manifest(
+        &self,
+        repository: &str,
+        tag: &str,
+        manifest: &Manifest,
+        condition: Option<&PutCondition>,
+    ) -> Result<()> {
+        let url = self.url(&format!("{}/manifests/{}", repository, tag));
+        let body = serde_json::to_vec(manifest).context("Failed to serialize manifest")?;
+        let mut request = self
+            .client
+            .put(&url)
+            .header(CONTENT_TYPE, MANIFEST_MEDIA_TYPE)
+            .body(body);
+        match condition {
+            Some(PutCondition::IfMatch(digest)) => {
+                request = request.header(IF_MATCH, format!("\"{}\"", digest));
+            }
+            Some(PutCondition::IfNoneMatch) => request = request.header(IF_NONE_MATCH, "*"),
+            None => {}
+        }
+        let response = self.send(repository, request)?;
+        if response.status() == StatusCode::PRECONDITION_FAILED {
+            return Err(PreconditionFailed {
+                key: format!("{}:{}", repository, tag),
+            }
+            .into());
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("HTTP {} pushing manifest {}", response.status(), url);
+        }
+        Ok(())
+    }
+
+    fn delete_manifest(&self, repository: &str, digest: &str) -> Result<()> {
+        let url = self.url(&format!("{}/manifests/{}", repository, digest));
+        let response = self.send(repository, self.client.delete(&url))?;
+        if !response.status().is_success() && response.status() != StatusCode::NOT_FOUND {
+            anyhow::bail!("HTTP {} deleting manifest {}", response.status(), url);
+        }
+        Ok(())
+    }
+
+    fn get_blob(&self, repository: &str, blob_digest: &str) -> Result<Vec<u8>> {
+        let url = self.url(&format!("{}/blobs/{}", repository, blob_digest));
+        let response = self.send(repository, self.client.get(&url))?;
+        if !response.status().is_success() {
+            anyhow::bail!("HTTP {} fetching blob {}", response.status(), url);
+        }
+        let data = response.bytes()?.to_vec();
+        if digest(&data) != blob_digest {
+            anyhow::bail!("Digest mismatch for blob {}", blob_digest);
+        }
+        Ok(data)
+    }
+
+    /// Upload a blob unless the registry already has it.
+    fn push_blob(&self, repository: &str, data: &[u8]) -> Result<()> {
+        let blob_digest = digest(data);
+        let blob_url = self.url(&format!("{}/blobs/{}", repository, blob_digest));
+        if self
+            .send(repository, self.client.head(&blob_url))?
+            .status()
+            .is_success()
+        {
+            return Ok(());
+        }
+
+        let start_url = self.url(&format!("{}/blobs/uploads/", repository));
+        let response = self.send(repository, self.client.post(&start_url))?;
+        if response.status() != StatusCode::ACCEPTED {
+            anyhow::bail!(
+                "HTTP {} starting blob upload {}",
+                response.status(),
+                start_url
+            );
+        }
+        let location = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .context("Registry did not return an upload location")?;
+        let location = if location.starts_with("http") {
+            location.to_string()
+        } else {
+            format!("{}{}", self.base_url, location)
+        };
+        let separator = if location.contains('?') { '&' } else { '?' };
+        let upload_url = format!("{}{}digest={}", location, separator, blob_digest);
+
+        let response = self.send(
+            repository,
+            self.client
+                .put(&upload_url)
+                .header(CONTENT_TYPE, "application/octet-stream")
+                .body(data.to_vec()),
+        )?;
+        if !response.status().is_success() {
+            anyhow::bail!("HTTP {} uploading blob {}", response.status(), blob_digest);
+        }
+        Ok(())
+    }
+
+    /// Every tag of a repository, following `Link` headers across pages.
+    fn list_tags(&self, repository: &str) -> Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct TagList {
+            #[serde(default)]
+            tags: Option<Vec<String>>,
+        }
+
+        let mut tags = Vec::new();
+        let mut url = self.url(&format!("{}/tags/list", repository));
+        loop {
+            let response = self.send(repository, self.client.get(&url))?;
+            match response.status() {
+                StatusCode::NOT_FOUND => return Ok(tags),
+                status if status.is_success() => {}
+                status => anyhow::bail!("HTTP {} listing tags {}", status, url),
+            }
+            let next = response
+                .headers()
+                .get(LINK)
+                .and_then(|v| v.to_str().ok())
+                .and_then(next_link);
+            let list: TagList =
+                serde_json::from_slice(&response.bytes()?).context("Invalid tag list")?;
+            tags.extend(list.tags.unwrap_or_default());
+            match next {
+                Some(next) if next.starts_with("http") => url = next,
+                Some(next) => url = format!("{}{}", self.base_url, next),
+                None => return Ok(tags),
+            }
+        }
+    }
+}
+
+/// Parameters of a `WWW-Authenticate` challenge, e.g.
+/// `realm="https://auth.example/token",scope="repository:foo:pull,push"`.
+/// Quoted values may contain commas.
+fn parse_challenge(params: &str) -> BTreeMap<&str, &str> {
+    let mut parsed = BTreeMap::new();
+    let mut rest = params;
+    while let Some((key, after)) = rest.split_once('=') {
+        let key = key.trim().trim_start_matches(',').trim();
+        let (value, next) = match after.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some((value, next)) => (value, next),
+                None => (quoted, ""),
+            },
+            None => after.split_once(',').unwrap_or((after, "")),
+        };
+        parsed.insert(key, value.trim());
+        rest = next;
+    }
+    parsed
+}
+
+/// Whether a tag names a version: numbers separated by dots, optionally with
+/// a leading `v` and a pre-release or build suffix.
+fn is_version_tag(tag: &str) -> bool {
+    let core = tag.trim_start_matches('v');
+    let core = core.split(['-', '+']).next().unwrap_or_default();
+    core.split('.')
+        .all(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// The `rel="next"` target of a `Link` header, e.g.
+/// `</v2/skills/foo/tags/list?n=100&last=1.2.0>; rel="next"`.
+fn next_link(header: &str) -> Option<String> {
+    header.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        params
+            .split(';')
+            .any(|p| matches!(p.trim(), "rel=\"next\"" | "rel=next"))
+            .then(|| {
+                target
+                    .trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
+/// Where a storage key lives in the registry.
+struct Location {
+    repository: String,
+    tag: String,
+    title: String,
+    artifact_type: &'static str,
+}
+
+/// Storage client that keeps a skill repository in an OCI registry.
+pub struct OciStorageClient {
+    registry: RegistryClient,
+    namespace: String,
+}
+
+impl OciStorageClient {
+    /// Create a client for skills under `namespace` in `registry`.
+    pub fn new(registry: &str, namespace: &str) -> Result<Self> {
+        Ok(Self {
+            registry: RegistryClient::new(registry)?,
+            namespace: namespace.trim_matches('/').to_string(),
+        })
+    }
+
+    /// Create a client from a repository URL such as `oci://ghcr.io/acme/skills`.
+    pub fn from_url(url: &str) -> Result<Self> {
+        let rest = url
+            .strip_prefix("oci://")
+            .with_context(|| format!("OCI repository URL must start with oci://: {}", url))?;
+        let (registry, namespace) = rest.split_once('/').unwrap_or((rest, ""));
+        Self::new(registry, namespace)
+    }
+
+    /// Create a client for the namespace of a skill reference.
+    pub fn for_reference(reference: &OciReference) -> Result<Self> {
+        Self::new(&reference.registry, reference.namespace())
+    }
+
+    fn repository(&self, name: &str) -> String {
+        if self.namespace.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", self.namespace, name)
+        }
+    }
+
+    fn locate(&self, key: &str) -> Result<Location> {
+        if key == INDEX_KEY {
+            return Ok(Location {
+                repository: self.repository(INDEX_REPOSITORY),
+                tag: INDEX_TAG.to_string(),
+                title: INDEX_KEY.to_string(),
+                artifact_type: INDEX_ARTIFACT_TYPE,
+            });
+        }
+        match key.split('/').collect::<Vec<_>>().as_slice() {
+            ["skills" | "source", name, version, file] => Ok(Location {
+                repository: self.repository(name),
+                tag: version.to_string(),
+                title: file.to_string(),
+                artifact_type: SKILL_ARTIFACT_TYPE,
+            }),
+            _ => anyhow::bail!("Unsupported key for an OCI repository: {}", key),
+        }
+    }
+
+    /// Version tags of a skill, highest first. Tags that aren't versions,
+    /// such as `latest`, are left out.
+    pub fn list_versions(&self, skill: &str) -> Result<Vec<String>> {
+        let mut tags = self.registry.list_tags(&self.repository(skill))?;
+        tags.retain(|tag| is_version_tag(tag));
+        tags.sort_by(|a, b| compare_semver(b, a));
+        Ok(tags)
+    }
+
+    /// Fetch a skill package, resolving a missing version to the latest one
+    /// that isn't yanked in the index, or to the highest version tag if the
+    /// index doesn't list the skill.
+    ///
+    /// Returns the version pulled and the package bytes.
+    pub fn pull(&self, skill: &str, version: Option<&str>) -> Result<(String, Vec<u8>)> {
+        let version = match version {
+            Some(v) => v.to_string(),
+            None => {
+                let index: SkillsIndex = match self.head_object(INDEX_KEY)? {
+                    Some(_) => serde_json::from_slice(&self.read_index()?.0)?,
+                    None => SkillsIndex::new(),
+                };
+                match index.find_skill(skill) {
+                    Some(entry) => entry.resolve_version(None)?.to_string(),
+                    None => self
+                        .list_versions(skill)?
+                        .into_iter()
+                        .next()
+                        .with_context(|| format!("No versions of '{}' in the registry", skill))?,
+                }
+            }
+        };
+        let key = format!("skills/{}/{}/{}.skill", skill, version, skill);
+        let data = self
+            .get_object(&key)
+            .with_context(|| format!("Failed to pull {} {}", skill, version))?;
+        Ok((version, data))
+    }
+
+    /// Read the stored index with each skill's versions replaced by its tags.
+    fn read_index(&self) -> Result<(Vec<u8>, ObjectMetadata)> {
+        let location = self.locate(INDEX_KEY)?;
+        let (data, metadata) = self.read_layer(&location)?;
+        let mut index: SkillsIndex =
+            serde_json::from_slice(&data).context("Failed to parse skills index")?;
+
+        for entry in &mut index.skills {
+            entry.versions = self
+                .list_versions(&entry.name)?
+                .into_iter()
+                .map(|v| {
+                    let key = format!("skills/{}/{}/{}.skill", entry.name, v, entry.name);
+                    (v, key)
+                })
+                .collect();
+        }
+        index.skills.retain(|e| !e.versions.is_empty());
+        let data = serde_json::to_vec_pretty(&index).context("Failed to serialize skills index")?;
+        // The ETag stays that of the stored index, which is what a conditional write replaces
+        Ok((data, metadata))
+    }
+
+    /// The manifest layer holding a key, if the key exists.
+    fn find_layer(&self, location: &Location) -> Result<Option<Descriptor>> {
+        Ok(self
+            .registry
+            .get_manifest(&location.repository, &location.tag)?
+            .and_then(|(m, _)| m.layer(&location.title).cloned()))
+    }
+
+    fn read_layer(&self, location: &Location) -> Result<(Vec<u8>, ObjectMetadata)> {
+        let key = format!(
+            "{}:{}/{}",
+            location.repository, location.tag, location.title
+        );
+        let layer = self
+            .find_layer(location)?
+            .with_context(|| format!("Object not found: {}", key))?;
+        let data = self
+            .registry
+            .get_blob(&location.repository, &layer.digest)?;
+        Ok((data, layer.metadata()))
+    }
+}
+
+impl OciStorageClient {
+    /// Store `data` as a layer of its version's manifest. With a `condition`
+    /// on the layer, the write fails with [`PreconditionFailed`] if the layer
+    /// changed, as far as the registry lets us tell; see
+    /// [`StorageOperations::put_object_if`].
+    fn write_object(&self, key: &str, data: &[u8], condition: Option<&PutCondition>) -> Result<()> {
+        let location = self.locate(key)?;
+        let repository = &location.repository;
+        let precondition_failed = || PreconditionFailed {
+            key: key.to_string(),
+        };
+
+        let (mut manifest, manifest_condition) =
+            match self.registry.get_manifest(repository, &location.tag)? {
+                Some((manifest, raw)) => (manifest, PutCondition::IfMatch(digest(&raw))),
+                None => {
+                    self.registry.push_blob(repository, EMPTY_CONFIG)?;
+                    (
+                        Manifest::new(location.artifact_type),
+                        PutCondition::IfNoneMatch,
+                    )
+                }
+            };
+        if let Some(condition) = condition {
+            let current = manifest.layer(&location.title).map(|l| l.metadata());
+            if !condition.is_met(current.as_ref()) {
+                return Err(precondition_failed().into());
+            }
+        }
+
+        self.registry.push_blob(repository, data)?;
+        let mut layer = Descriptor::for_data(layer_media_type(&location.title), data);
+        layer
+            .annotations
+            .insert(TITLE_ANNOTATION.to_string(), location.title.clone());
+        manifest
+            .layers
+            .retain(|l| l.title() != Some(&location.title));
+        manifest.layers.push(layer);
+
+        // The package's SKILL.md frontmatter becomes the artifact's annotations
+        if location.title.ends_with(".skill") {
+            let annotations = &mut manifest.annotations;
+            if let Ok(frontmatter) = read_skill_frontmatter(data) {
+                if let Some(name) = frontmatter.name {
+                    annotations.insert(TITLE_ANNOTATION.to_string(), name);
+                }
+                if let Some(description) = frontmatter.description {
+                    annotations.insert(
+                        "org.opencontainers.image.description".to_string(),
+                        description,
+                    );
+                }
+                if let Some(author) = frontmatter.author {
+                    annotations.insert("org.opencontainers.image.authors".to_string(), author);
+                }
+                if let Some(homepage) = frontmatter.homepage {
+                    annotations.insert("org.opencontainers.image.url".to_string(), homepage);
+                }
+            }
+            annotations.insert(
+                "org.opencontainers.image.version".to_string(),
+                location.tag.clone(),
+            );
+            annotations.insert(
+                "org.opencontainers.image.created".to_string(),
+                chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            );
+        }
+
+        if condition.is_none() {
+            return self
+                .registry
+                .put_manifest(repository, &location.tag, &manifest, None);
+        }
+        self.registry.put_manifest(
+            repository,
+            &location.tag,
+            &manifest,
+            Some(&manifest_condition),
+        )?;
+        // Registries may ignore If-Match, so check that our layer is the one
+        // stored; a writer that pushes in between still goes unnoticed
+        let stored = self.find_layer(&location)?;
+        if stored.map(|l| l.digest) != Some(digest(data)) {
+            return Err(precondition_failed().into());
+        }
+        Ok(())
+    }
+}
+
+impl StorageOperations for OciStorageClient {
+    fn put_object(&self, key: &str, data: &[u8]) -> Result<()> {
+        self.write_object(key, data, None)
+    }
+
+    /// Best-effort: the manifest push carries `If-Match`, and the manifest is
+    /// read back to check the write stuck. Registries that ignore `If-Match`
+    /// leave a short window in which a concurrent index update can be lost.
+    fn put_object_if(
+        &self,
+        key: &str,
+        data: &[u8],
+        condition: &PutCondition,
+    ) -> Result<ObjectMetadata> {
+        self.write_object(key, data, Some(condition))?;
+        Ok(Descriptor::for_data(layer_media_type(&self.locate(key)?.title), data).metadata())
+    }
+
+    fn get_object(&self, key: &str) -> Result<Vec<u8>> {
+        self.get_object_with_metadata(key).map(|(data, _)| data)
+    }
+
+    fn delete_object(&self, key: &str) -> Result<()> {
+        let location = self.locate(key)?;
+        let Some((mut manifest, raw)) = self
+            .registry
+            .get_manifest(&location.repository, &location.tag)?
+        else {
+            return Ok(());
+        };
+
+        manifest
+            .layers
+            .retain(|l| l.title() != Some(&location.title));
+        // Removing the package removes the whole version
+        if location.title.ends_with(".skill") || manifest.layers.is_empty() {
+            self.registry
+                .delete_manifest(&location.repository, &digest(&raw))
+        } else {
+            self.registry
+                .put_manifest(&location.repository, &location.tag, &manifest, None)
+        }
+    }
+
+    fn list_objects(&self, prefix: &str) -> Result<Vec<String>> {
+        // No index is an empty repository; a registry error is still an error
+        if self.head_object(INDEX_KEY)?.is_none() {
+            return Ok(Vec::new());
+        }
+        let (data, _) = self.read_index()?;
+        let index: SkillsIndex = serde_json::from_slice(&data)?;
+        let mut keys: Vec<String> = std::iter::once(INDEX_KEY.to_string())
+            .chain(
+                index
+                    .skills
+                    .iter()
+                    .flat_map(|entry| entry.versions.values().cloned()),
+            )
+            .filter(|key| key.starts_with(prefix))
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn object_exists(&self, key: &str) -> Result<bool> {
+        Ok(self.find_layer(&self.locate(key)?)?.is_some())
+    }
+
+    /// Layer digests serve as ETags.
+    fn head_object(&self, key: &str) -> Result<Option<ObjectMetadata>> {
+        Ok(self
+            .find_layer(&self.locate(key)?)?
+            .map(|layer| layer.metadata()))
+    }
+
+    fn get_object_with_metadata(&self, key: &str) -> Result<(Vec<u8>, ObjectMetadata)> {
+        if key == INDEX_KEY {
+            return self.read_index();
+        }
+        self.read_layer(&self.locate(key)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reference() {
+        let r = OciReference::parse("oci://ghcr.io/acme/skills/my-skill:1.2.0").unwrap();
+        assert_eq!(r.registry, "ghcr.io");
+        assert_eq!(r.repository, "acme/skills/my-skill");
+        assert_eq!(r.tag.as_deref(), Some("1.2.0"));
+        assert_eq!(r.skill_name(), "my-skill");
+        assert_eq!(r.namespace(), "acme/skills");
+
+        let r = OciReference::parse("oci://localhost:5000/my-skill").unwrap();
+        assert_eq!(r.registry, "localhost:5000");
+        assert_eq!(r.tag, None);
+        assert_eq!(r.namespace(), "");
+    }
+
+    #[test]
+    fn test_parse_reference_errors() {
+        assert!(OciReference::parse("https://ghcr.io/acme/skill").is_err());
+        assert!(OciReference::parse("oci://ghcr.io").is_err());
+        assert!(OciReference::parse("oci://ghcr.io/Acme/Skill:1.0.0").is_err());
+    }
+
+    #[test]
+    fn test_digest_and_media_types() {
+        assert_eq!(
+            digest(EMPTY_CONFIG),
+            "sha256:44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+        assert_eq!(layer_media_type("foo.skill"), SKILL_LAYER_MEDIA_TYPE);
+        assert_eq!(layer_media_type("CHANGELOG.md"), "text/markdown");
+        assert_eq!(layer_media_type("blob"), "application/octet-stream");
+    }
+
+    #[test]
+    fn test_locate_keys() {
+        let client = OciStorageClient::from_url("oci://registry.example.com/team").unwrap();
+        let loc = client.locate("skills/foo/1.0.0/foo.skill").unwrap();
+        assert_eq!(loc.repository, "team/foo");
+        assert_eq!(loc.tag, "1.0.0");
+        assert_eq!(loc.title, "foo.skill");
+
+        let loc = client.locate("source/foo/1.0.0/foo-source.zip").unwrap();
+        assert_eq!(loc.repository, "team/foo");
+        assert_eq!(loc.title, "foo-source.zip");
+
+        let loc = client.locate(INDEX_KEY).unwrap();
+        assert_eq!(loc.repository, "team/skill-builder-index");
+        assert!(client.locate("other/key").is_err());
+    }
+
+    #[test]
+    fn test_parse_challenge() {
+        let params = parse_challenge(
+            r#"realm="https://auth.example/token",service="registry",scope="repository:a/b:pull,push""#,
+        );
+        assert_eq!(params["realm"], "https://auth.example/token");
+        assert_eq!(params["service"], "registry");
+        assert_eq!(params["scope"], "repository:a/b:pull,push");
+        assert_eq!(parse_challenge("realm=x, scope=y")["scope"], "y");
+    }
+
+    #[test]
+    fn test_is_version_tag() {
+        assert!(is_version_tag("1.2.0"));
+        assert!(is_version_tag("v2.0.0-beta.1"));
+        assert!(!is_version_tag("latest"));
+        assert!(!is_version_tag("1..0"));
+        assert!(!is_version_tag(""));
+    }
+
+    #[test]
+    fn test_next_link() {
+        assert_eq!(
+            next_link(r#"</v2/team/foo/tags/list?n=2&last=1.1.0>; rel="next""#).as_deref(),
+            Some("/v2/team/foo/tags/list?n=2&last=1.1.0")
+        );
+        assert_eq!(
+            next_link(
+                r#"<https://a.example/prev>; rel="prev", <https://a.example/next>; rel="next""#
+            )
+            .as_deref(),
+            Some("https://a.example/next")
+        );
+        assert_eq!(next_link(r#"</v2/x>; rel="prev""#), None);
+    }
+}
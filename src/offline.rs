@@ -0,0 +1,80 @@
+//! Offline mode, in which `sb` never touches the network.
+//!
+//! Turned on with `--offline` or `SB_OFFLINE=1`. Every client that would
+//! connect somewhere calls [`ensure_online`] first, so commands fail fast
+//! instead of waiting on timeouts or loading credentials.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Environment variable that turns offline mode on.
+pub const OFFLINE_ENV: &str = "SB_OFFLINE";
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Turn offline mode on for this process if `flag` is set or `SB_OFFLINE` is
+/// `1` or `true`.
+pub fn init(flag: bool) {
+    let env = std::env::var(OFFLINE_ENV).is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
+    set_offline(flag || env);
+}
+
+/// Turn offline mode on or off.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Whether offline mode is on.
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Error for network sources skipped in offline mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfflineError {
+    /// The sources that weren't contacted, e.g. "GitHub releases".
+    pub skipped: Vec<String>,
+}
+
+impl fmt::Display for OfflineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "offline mode is on (--offline or {}=1); skipped network sources: {}",
+            OFFLINE_ENV,
+            self.skipped.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for OfflineError {}
+
+/// Fail with an [`OfflineError`] naming `source` if offline mode is on.
+pub fn ensure_online(source: impl Into<String>) -> anyhow::Result<()> {
+    if is_offline() {
+        return Err(OfflineError {
+            skipped: vec![source.into()],
+        }
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offline_error_lists_skipped_sources() {
+        let err = OfflineError {
+            skipped: vec![
+                "remote repository 'company'".to_string(),
+                "GitHub releases".to_string(),
+            ],
+        };
+        assert_eq!(
+            err.to_string(),
+            "offline mode is on (--offline or SB_OFFLINE=1); skipped network sources: remote repository 'company', GitHub releases"
+        );
+    }
+}
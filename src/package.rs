@@ -1,14 +1,15 @@
 //! Package skills into distributable .skill files.
 
 use anyhow::{Context, Result};
+use std::collections::BTreeMap;
 use std::fs::{self, File};
-use std::io::{Read, Write};
+use std::io::{Cursor, Read, Seek, Write};
 use std::path::{Path, PathBuf};
 use zip::write::SimpleFileOptions;
 use zip::ZipWriter;
 
 use crate::output::Output;
-use crate::validate::{validate_skill, ValidationResult};
+use crate::validate::{parse_frontmatter, validate_skill, Frontmatter, ValidationResult};
 
 /// Files and directories to skip when packaging.
 const SKIP_EXTENSIONS: &[&str] = &["pyc", "pyo"];
@@ -129,7 +130,55 @@ pub fn package_skill_with_output<P: AsRef<Path>, Q: AsRef<Path>>(
     // Create output file
     let output_path = output_dir.join(format!("{}.skill", skill_name));
     let file = File::create(&output_path)?;
-    let mut zip = ZipWriter::new(file);
+    write_archive(file, skill_path, &skill_name, &files, output)?;
+
+    output.status("Packaged", &format!("{}", output_path.display()));
+
+    Ok(PackageResult {
+        output_path,
+        files_included: files.len(),
+        validation,
+    })
+}
+
+/// Package a skill directory into the bytes of a .skill file, with
+/// `skill_name` as its top-level directory.
+pub fn package_skill_to_memory<P: AsRef<Path>>(
+    skill_path: P,
+    skill_name: &str,
+    output: &Output,
+) -> Result<Vec<u8>> {
+    let skill_path = skill_path.as_ref();
+    let validation = validate_skill(skill_path);
+    if !validation.valid {
+        let errors: Vec<String> = validation.errors.iter().map(|e| e.to_string()).collect();
+        anyhow::bail!(
+            "Skill validation failed for {}: {}",
+            skill_path.display(),
+            errors.join("; ")
+        );
+    }
+
+    let files = collect_files(skill_path)?;
+    let archive = write_archive(
+        Cursor::new(Vec::new()),
+        skill_path,
+        skill_name,
+        &files,
+        output,
+    )?;
+    Ok(archive.into_inner())
+}
+
+/// Write `files` from `skill_path` as a zip archive under `skill_name/`.
+fn write_archive<W: Write + Seek>(
+    writer: W,
+    skill_path: &Path,
+    skill_name: &str,
+    files: &[PathBuf],
+    output: &Output,
+) -> Result<W> {
+    let mut zip = ZipWriter::new(writer);
 
     let zip_options = SimpleFileOptions::default()
         .compression_method(zip::CompressionMethod::Deflated)
@@ -138,9 +187,9 @@ pub fn package_skill_with_output<P: AsRef<Path>, Q: AsRef<Path>>(
     // Add files to archive
     let progress = output.progress_bar(files.len() as u64, "Adding files");
 
-    for file_path in &files {
+    for file_path in files {
         let relative_path = file_path.strip_prefix(skill_path)?;
-        let archive_path = PathBuf::from(skill_name.as_ref()).join(relative_path);
+        let archive_path = PathBuf::from(skill_name).join(relative_path);
 
         zip.start_file(archive_path.to_string_lossy(), zip_options)?;
 
@@ -153,15 +202,7 @@ pub fn package_skill_with_output<P: AsRef<Path>, Q: AsRef<Path>>(
     }
 
     progress.finish_and_clear();
-    zip.finish()?;
-
-    output.status("Packaged", &format!("{}", output_path.display()));
-
-    Ok(PackageResult {
-        output_path,
-        files_included: files.len(),
-        validation,
-    })
+    Ok(zip.finish()?)
 }
 
 /// List contents of a .skill file.
@@ -180,6 +221,69 @@ pub fn list_skill_contents<P: AsRef<Path>>(skill_file: P) -> Result<Vec<String>>
     Ok(contents)
 }
 
+/// Files a skill directory would be packaged with, keyed by their path
+/// relative to the skill directory.
+pub fn read_skill_files<P: AsRef<Path>>(skill_path: P) -> Result<BTreeMap<String, Vec<u8>>> {
+    let skill_path = skill_path.as_ref();
+    let mut files = BTreeMap::new();
+    for path in collect_files(skill_path)? {
+        let relative = path.strip_prefix(skill_path)?;
+        let key = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let data = fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        files.insert(key, data);
+    }
+    Ok(files)
+}
+
+/// Files in a .skill package, keyed by their path below the skill's
+/// top-level directory.
+pub fn read_package_files<R: Read + Seek>(reader: R) -> Result<BTreeMap<String, Vec<u8>>> {
+    let mut archive = zip::ZipArchive::new(reader).context("Invalid .skill archive")?;
+    let mut files = BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+        let Some((_, key)) = file.name().split_once('/') else {
+            continue;
+        };
+        let key = key.to_string();
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        files.insert(key, data);
+    }
+    Ok(files)
+}
+
+/// Read the SKILL.md frontmatter from the bytes of a .skill archive.
+pub fn read_skill_frontmatter(data: &[u8]) -> Result<Frontmatter> {
+    read_package_frontmatter(std::io::Cursor::new(data))
+}
+
+/// Read the SKILL.md frontmatter from a .skill package without loading it
+/// into memory.
+pub fn read_package_frontmatter<R: Read + Seek>(reader: R) -> Result<Frontmatter> {
+    let mut archive = zip::ZipArchive::new(reader).context("Invalid .skill archive")?;
+
+    let name = archive
+        .file_names()
+        .find(|n| n.split('/').count() == 2 && n.ends_with("/SKILL.md"))
+        .map(|n| n.to_string())
+        .context("No SKILL.md found in .skill archive")?;
+
+    let mut content = String::new();
+    archive
+        .by_name(&name)?
+        .read_to_string(&mut content)
+        .context("Failed to read SKILL.md")?;
+    parse_frontmatter(&content).map_err(|e| anyhow::anyhow!("Invalid SKILL.md: {}", e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -342,4 +446,58 @@ description: A test skill for testing archive structure with proper path prefixe
         assert!(!contents.is_empty());
         assert!(contents.iter().any(|c| c.contains("SKILL.md")));
     }
+
+    #[test]
+    fn test_read_skill_frontmatter() {
+        let temp = TempDir::new().unwrap();
+        let skill_dir = temp.path().join("test-skill");
+        create_test_skill(&skill_dir);
+
+        let result = package_skill(&skill_dir, temp.path().join("dist")).unwrap();
+        let data = fs::read(&result.output_path).unwrap();
+
+        let frontmatter = read_skill_frontmatter(&data).unwrap();
+        assert_eq!(frontmatter.name.as_deref(), Some("test-skill"));
+        assert!(frontmatter.description.is_some());
+        assert!(read_skill_frontmatter(b"not a zip").is_err());
+    }
+
+    #[test]
+    fn test_package_files_match_skill_files() {
+        let temp = TempDir::new().unwrap();
+        let skill_dir = temp.path().join("test-skill");
+        create_test_skill(&skill_dir);
+        fs::write(skill_dir.join(".hidden"), "skipped").unwrap();
+
+        let result = package_skill(&skill_dir, temp.path().join("dist")).unwrap();
+        let packaged = read_package_files(File::open(&result.output_path).unwrap()).unwrap();
+        let on_disk = read_skill_files(&skill_dir).unwrap();
+        assert_eq!(packaged, on_disk);
+        assert!(on_disk.contains_key("SKILL.md"));
+        assert!(!on_disk.contains_key(".hidden"));
+    }
+
+    #[test]
+    fn test_package_skill_to_memory() {
+        let temp = TempDir::new().unwrap();
+        let skill_dir = temp.path().join("checkout");
+        create_test_skill(&skill_dir);
+
+        let data = package_skill_to_memory(&skill_dir, "test-skill", &Output::new(true)).unwrap();
+        let contents: Vec<String> = zip::ZipArchive::new(Cursor::new(&data))
+            .unwrap()
+            .file_names()
+            .map(|n| n.to_string())
+            .collect();
+        assert!(contents.iter().all(|n| n.starts_with("test-skill/")));
+        assert_eq!(
+            read_package_files(Cursor::new(&data)).unwrap(),
+            read_skill_files(&skill_dir).unwrap()
+        );
+
+        fs::write(skill_dir.join("SKILL.md"), "# No frontmatter").unwrap();
+        let err =
+            package_skill_to_memory(&skill_dir, "test-skill", &Output::new(true)).unwrap_err();
+        assert!(err.to_string().contains("Skill validation failed"));
+    }
 }
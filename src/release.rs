@@ -0,0 +1,140 @@
+//! Version bumps for `sb release`.
+
+use anyhow::{Context, Result};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Which part of a version to increment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bump {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl FromStr for Bump {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "major" => Ok(Self::Major),
+            "minor" => Ok(Self::Minor),
+            "patch" => Ok(Self::Patch),
+            _ => anyhow::bail!("Unknown bump '{}'; use major, minor or patch", s),
+        }
+    }
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Major => "major",
+            Self::Minor => "minor",
+            Self::Patch => "patch",
+        })
+    }
+}
+
+/// The version after `current`, or after 0.0.0 for a first release.
+///
+/// A leading `v` and any pre-release or build suffix on `current` are
+/// dropped.
+pub fn next_version(current: Option<&str>, bump: Bump) -> Result<String> {
+    let current = current.unwrap_or("0.0.0");
+    let core = current.trim_start_matches('v');
+    let core = core.split(['-', '+']).next().unwrap_or_default();
+    let parts: Vec<u64> = core
+        .split('.')
+        .map(|p| p.parse())
+        .collect::<Result<_, _>>()
+        .ok()
+        .filter(|p: &Vec<u64>| p.len() == 3)
+        .with_context(|| format!("Can't bump '{}': not a major.minor.patch version", current))?;
+    let [major, minor, patch] = [parts[0], parts[1], parts[2]];
+    Ok(match bump {
+        Bump::Major => format!("{}.0.0", major + 1),
+        Bump::Minor => format!("{}.{}.0", major, minor + 1),
+        Bump::Patch => format!("{}.{}.{}", major, minor, patch + 1),
+    })
+}
+
+/// Set the `version` field in SKILL.md frontmatter, adding it after `name`
+/// (or at the end) if it isn't there.
+pub fn set_frontmatter_version(content: &str, version: &str) -> Result<String> {
+    let rest = content
+        .strip_prefix("---\n")
+        .context("SKILL.md missing YAML frontmatter (must start with ---)")?;
+    let end = rest
+        .find("\n---")
+        .context("SKILL.md frontmatter is not closed with ---")?;
+    let (yaml, body) = rest.split_at(end);
+
+    let line = format!("version: {}", version);
+    let mut lines: Vec<String> = yaml.lines().map(str::to_string).collect();
+    if let Some(existing) = lines.iter_mut().find(|l| l.starts_with("version:")) {
+        *existing = line;
+    } else {
+        let at = lines
+            .iter()
+            .position(|l| l.starts_with("name:"))
+            .map_or(lines.len(), |i| i + 1);
+        lines.insert(at, line);
+    }
+    Ok(format!("---\n{}{}", lines.join("\n"), body))
+}
+
+/// Write `version` into a skill directory's SKILL.md.
+pub fn write_skill_version(skill_path: &Path, version: &str) -> Result<()> {
+    let path = skill_path.join("SKILL.md");
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let updated = set_frontmatter_version(&content, version)?;
+    std::fs::write(&path, updated).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::parse_frontmatter;
+
+    #[test]
+    fn test_next_version() {
+        assert_eq!(next_version(Some("1.2.3"), Bump::Major).unwrap(), "2.0.0");
+        assert_eq!(next_version(Some("1.2.3"), Bump::Minor).unwrap(), "1.3.0");
+        assert_eq!(next_version(Some("v1.2.3"), Bump::Patch).unwrap(), "1.2.4");
+        assert_eq!(
+            next_version(Some("1.2.3-beta.1"), Bump::Patch).unwrap(),
+            "1.2.4"
+        );
+        assert_eq!(next_version(None, Bump::Minor).unwrap(), "0.1.0");
+        assert!(next_version(Some("1.2"), Bump::Patch).is_err());
+        assert!(next_version(Some("latest"), Bump::Patch).is_err());
+    }
+
+    #[test]
+    fn test_bump_from_str() {
+        assert_eq!("minor".parse::<Bump>().unwrap(), Bump::Minor);
+        assert_eq!(Bump::Major.to_string(), "major");
+        assert!("huge".parse::<Bump>().is_err());
+    }
+
+    #[test]
+    fn test_set_frontmatter_version() {
+        let content = "---\nname: ui\ndescription: UI\n---\n\n# UI\n";
+        let updated = set_frontmatter_version(content, "1.0.0").unwrap();
+        assert_eq!(
+            updated,
+            "---\nname: ui\nversion: 1.0.0\ndescription: UI\n---\n\n# UI\n"
+        );
+        assert_eq!(
+            set_frontmatter_version(&updated, "1.1.0").unwrap(),
+            "---\nname: ui\nversion: 1.1.0\ndescription: UI\n---\n\n# UI\n"
+        );
+        assert_eq!(
+            parse_frontmatter(&updated).unwrap().version.as_deref(),
+            Some("1.0.0")
+        );
+        assert!(set_frontmatter_version("# No frontmatter", "1.0.0").is_err());
+    }
+}
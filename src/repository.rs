@@ -1,16 +1,27 @@
 //! Repository operations orchestrating S3, local storage, and index.
 
 use anyhow::{Context, Result};
-use std::io::{Cursor, Read, Write};
+use chrono::{DateTime, Utc};
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 
-use crate::config::RepositoryConfig;
-use crate::index::{load_index, save_index, SkillsIndex};
-use crate::install::install_from_file;
+use crate::cache::{CachePolicy, SkillCache};
+use crate::config::{RepositoryBackend, RepositoryConfig};
+use crate::fsck::{self, FsckReport, Rebuild};
+use crate::git_storage::GitStorageClient;
+use crate::http_storage::HttpStorageClient;
+use crate::index::{compare_semver, load_index, update_index, IndexEntry, NotFound, SkillsIndex};
+use crate::install::{install_from_file, InstallResult};
 use crate::local_storage::LocalStorageClient;
+use crate::mirror::{self, MirrorOptions, MirrorReport};
+use crate::oci::OciStorageClient;
 use crate::output::Output;
+use crate::package::{read_package_files, read_package_frontmatter};
 use crate::s3::S3Client;
-use crate::storage::StorageOperations;
+use crate::storage::{is_precondition_failed, reader_sha256, PutCondition, StorageOperations};
 
 /// Parameters for uploading a skill to the repository.
 pub struct UploadParams<'a> {
@@ -23,10 +34,23 @@ pub struct UploadParams<'a> {
     pub source_dir: Option<&'a Path>,
 }
 
+/// A published version and its stored objects, as shown by `sb info`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionDetails {
+    pub version: String,
+    /// Upload time from the index, or else the package's modification time.
+    pub published: Option<DateTime<Utc>>,
+    /// Size of the .skill package, if it is stored.
+    pub size: Option<u64>,
+    pub has_changelog: bool,
+    /// Size of the source archive, if one was uploaded.
+    pub source_size: Option<u64>,
+}
+
 /// Repository managing skills in S3 with optional local cache.
 pub struct Repository<S: StorageOperations> {
     client: S,
-    local_cache: Option<LocalStorageClient>,
+    local_cache: Option<SkillCache>,
 }
 
 impl<S: StorageOperations> Repository<S> {
@@ -39,21 +63,44 @@ impl<S: StorageOperations> Repository<S> {
     }
 
     /// Create a new repository with a local cache layer.
-    pub fn new_with_cache(client: S, local_cache: LocalStorageClient) -> Self {
+    pub fn new_with_cache(client: S, local_cache: impl Into<SkillCache>) -> Self {
         Self {
             client,
-            local_cache: Some(local_cache),
+            local_cache: Some(local_cache.into()),
         }
     }
 }
 
-impl Repository<S3Client> {
+impl Repository<Box<dyn StorageOperations>> {
     /// Create a repository from config, with optional local cache.
+    ///
+    /// The backend is chosen by [`RepositoryConfig::backend`].
     pub fn from_config(repo_config: &RepositoryConfig) -> Result<Self> {
-        let client = S3Client::new(repo_config)?;
-        if repo_config.local_is_cache() {
+        if repo_config.url.is_some() && repo_config.bucket_name.is_some() {
+            anyhow::bail!(
+                "Repository '{}' sets both 'url' and 'bucket_name'; use one",
+                repo_config.display_name()
+            );
+        }
+        let client: Box<dyn StorageOperations> = match repo_config.backend() {
+            RepositoryBackend::S3 => Box::new(S3Client::new(repo_config)?),
+            RepositoryBackend::Http(url) => Box::new(HttpStorageClient::new(url)?),
+            RepositoryBackend::Git(url) => Box::new(GitStorageClient::from_url(
+                url,
+                repo_config.branch.as_deref(),
+            )?),
+            RepositoryBackend::Oci(url) => Box::new(OciStorageClient::from_url(url)?),
+        };
+        if let Some(local) = repo_config
+            .local
+            .as_ref()
+            .filter(|_| repo_config.local_is_cache())
+        {
             let local_path = repo_config.local_repo_path();
-            let local_cache = LocalStorageClient::new(&local_path)?;
+            let local_cache = SkillCache::new(
+                LocalStorageClient::new(&local_path)?,
+                CachePolicy::from_config(local),
+            );
             Ok(Self::new_with_cache(client, local_cache))
         } else {
             Ok(Self::new(client))
@@ -62,11 +109,36 @@ impl Repository<S3Client> {
 }
 
 impl<S: StorageOperations> Repository<S> {
-    /// Upload a skill to the repository.
+    /// Upload a skill to the repository, replacing the version if it exists.
     pub fn upload(&self, params: &UploadParams, output: &Output) -> Result<()> {
-        let skill_data = std::fs::read(params.skill_file).with_context(|| {
+        self.upload_version(params, true, output)
+    }
+
+    /// Upload a skill to the repository. Unless `overwrite` is set, fails if
+    /// the version is already published, including by a concurrent upload.
+    pub fn upload_version(
+        &self,
+        params: &UploadParams,
+        overwrite: bool,
+        output: &Output,
+    ) -> Result<()> {
+        let already_published = || {
+            anyhow::anyhow!(
+                "{} v{} is already published; use --force to overwrite it",
+                params.name,
+                params.version
+            )
+        };
+        let mut skill_file = File::open(params.skill_file).with_context(|| {
             format!("Failed to read skill file: {}", params.skill_file.display())
         })?;
+        let checksum = reader_sha256(&mut skill_file).with_context(|| {
+            format!("Failed to read skill file: {}", params.skill_file.display())
+        })?;
+        skill_file.seek(SeekFrom::Start(0))?;
+        // Packages that don't parse are still uploaded, just without metadata
+        let frontmatter = read_package_frontmatter(&mut skill_file).ok();
+        skill_file.seek(SeekFrom::Start(0))?;
 
         // Upload skill file
         let skill_key = format!(
@@ -74,45 +146,103 @@ impl<S: StorageOperations> Repository<S> {
             params.name, params.version, params.name
         );
         let pb = output.spinner(&format!("Uploading {}", skill_key));
-        self.client.put_object(&skill_key, &skill_data)?;
-        pb.finish_and_clear();
-        output.step(&format!("Uploaded: {}", skill_key));
-
-        // Upload changelog if provided
-        if let Some(changelog_path) = params.changelog {
-            let changelog_data = std::fs::read_to_string(changelog_path).with_context(|| {
-                format!("Failed to read changelog: {}", changelog_path.display())
-            })?;
-            let changelog_key = format!("skills/{}/{}/CHANGELOG.md", params.name, params.version);
+        if overwrite {
             self.client
-                .put_object(&changelog_key, changelog_data.as_bytes())?;
-            output.step(&format!("Uploaded: {}", changelog_key));
+                .put_object_from_reader(&skill_key, &mut skill_file)?;
+        } else {
+            let mut data = Vec::new();
+            skill_file.read_to_end(&mut data).with_context(|| {
+                format!("Failed to read skill file: {}", params.skill_file.display())
+            })?;
+            match self
+                .client
+                .put_object_if(&skill_key, &data, &PutCondition::IfNoneMatch)
+            {
+                Ok(_) => {}
+                Err(e)
+                    if is_precondition_failed(&e) && self.client.object_exists(&skill_key)? =>
+                {
+                    return Err(already_published());
+                }
+                Err(e) => return Err(e),
+            }
         }
+        pb.finish_and_clear();
+        output.step(&format!("Uploaded: {}", skill_key));
 
-        // Upload source archive if provided
-        if let Some(src_dir) = params.source_dir {
-            let archive = create_source_archive(src_dir, params.name)?;
-            let source_key = format!(
-                "source/{}/{}/{}-source.zip",
-                params.name, params.version, params.name
-            );
-            self.client.put_object(&source_key, &archive)?;
-            output.step(&format!("Uploaded: {}", source_key));
-        }
+        // Until the index lists it, a package this call claimed only blocks
+        // retries, so take it back if a later step fails
+        let lost_race = Cell::new(false);
+        let publish = || -> Result<()> {
+            // Upload changelog if provided
+            if let Some(changelog_path) = params.changelog {
+                let changelog_data =
+                    std::fs::read_to_string(changelog_path).with_context(|| {
+                        format!("Failed to read changelog: {}", changelog_path.display())
+                    })?;
+                let changelog_key =
+                    format!("skills/{}/{}/CHANGELOG.md", params.name, params.version);
+                self.client
+                    .put_object(&changelog_key, changelog_data.as_bytes())?;
+                output.step(&format!("Uploaded: {}", changelog_key));
+            }
 
-        // Update index
-        let mut index = load_index(&self.client)?;
-        index.add_or_update_skill(
-            params.name,
-            params.description,
-            params.llms_txt_url,
-            params.version,
-            &skill_key,
-        );
-        save_index(&self.client, &index)?;
-        output.step("Updated index");
+            // Upload source archive if provided
+            if let Some(src_dir) = params.source_dir {
+                // Archive to a temporary file so large sources aren't held in memory
+                let source_key = format!(
+                    "source/{}/{}/{}-source.zip",
+                    params.name, params.version, params.name
+                );
+                let temp = tempfile::tempfile().context("Failed to create a temporary file")?;
+                let mut archive = create_source_archive(src_dir, params.name, temp)?;
+                archive.seek(SeekFrom::Start(0))?;
+                self.client
+                    .put_object_from_reader(&source_key, &mut archive)?;
+                output.step(&format!("Uploaded: {}", source_key));
+            }
 
-        Ok(())
+            // Update index, describing the skill from its SKILL.md if not told otherwise
+            let description = match &frontmatter {
+                Some(f) if params.description.is_empty() => f.description.as_deref().unwrap_or(""),
+                _ => params.description,
+            };
+            update_index(&self.client, |index| {
+                // Another upload indexed this version first. Some backends, like
+                // OCI, list the version as soon as our package is stored, so only
+                // a different package counts.
+                let taken = index.find_skill(params.name).is_some_and(|entry| {
+                    entry
+                        .checksums
+                        .get(params.version)
+                        .is_some_and(|recorded| *recorded != checksum)
+                });
+                if taken && !overwrite {
+                    lost_race.set(true);
+                    return Err(already_published());
+                }
+                index.add_or_update_skill(
+                    params.name,
+                    description,
+                    params.llms_txt_url,
+                    params.version,
+                    &skill_key,
+                );
+                index.set_checksum(params.name, params.version, &checksum);
+                index.set_published(params.name, params.version, Utc::now());
+                if let Some(frontmatter) = &frontmatter {
+                    index.set_metadata(params.name, frontmatter);
+                }
+                Ok(())
+            })?;
+            output.step("Updated index");
+            Ok(())
+        };
+        publish().inspect_err(|_| {
+            if !overwrite && !lost_race.get() {
+                self.client.delete_object(&skill_key).ok();
+            }
+        })
     }
 
     /// Download a skill, using local cache when available. Returns path to the file.
@@ -124,31 +254,34 @@ impl<S: StorageOperations> Repository<S> {
         output: &Output,
     ) -> Result<PathBuf> {
         let index = load_index(&self.client)?;
-        let resolved_version = match version {
-            Some(v) => v.to_string(),
-            None => index
-                .latest_version(name)
-                .context(format!("Skill '{}' not found in repository", name))?
-                .to_string(),
+        let Some(entry) = index.find_skill(name) else {
+            let names = index.skills.iter().map(|s| s.name.as_str());
+            let mut message = format!("Skill '{}' not found in repository", name);
+            if let Some(suggestion) = crate::suggest::did_you_mean(name, names) {
+                message.push_str(&format!(" (did you mean '{}'?)", suggestion));
+            }
+            return Err(NotFound { message }.into());
         };
+        let resolved_version = entry.resolve_version(version)?.to_string();
+        if let Some(reason) = entry.yanked.get(&resolved_version) {
+            output.warn(&yank_warning(name, &resolved_version, reason));
+        }
+        if let Some(notice) = entry.deprecation_notice() {
+            output.warn(&notice);
+        }
 
         // Check local cache first
         if let Some(ref cache) = self.local_cache {
-            let cache_key = format!("skills/{}/{}/{}.skill", name, resolved_version, name);
-            if cache.object_exists(&cache_key).unwrap_or(false) {
+            if let Some(data) = cache.get(name, &resolved_version)? {
                 output.info(&format!(
                     "Using cached version: {} v{}",
                     name, resolved_version
                 ));
-                let data = cache.get_object(&cache_key)?;
                 return write_output(name, &data, output_dir);
             }
         }
 
         // Find S3 path from index
-        let entry = index
-            .find_skill(name)
-            .context(format!("Skill '{}' not found in repository", name))?;
         let s3_path = entry.versions.get(&resolved_version).with_context(|| {
             format!(
                 "Version '{}' not found for skill '{}'",
@@ -163,8 +296,12 @@ impl<S: StorageOperations> Repository<S> {
 
         // Store in local cache
         if let Some(ref cache) = self.local_cache {
-            let cache_key = format!("skills/{}/{}/{}.skill", name, resolved_version, name);
-            cache.put_object(&cache_key, &data).ok();
+            if let Err(e) = cache.put(name, &resolved_version, &data) {
+                output.warn(&format!(
+                    "Failed to cache {} v{}: {:#}",
+                    name, resolved_version, e
+                ));
+            }
         }
 
         write_output(name, &data, output_dir)
@@ -177,15 +314,14 @@ impl<S: StorageOperations> Repository<S> {
         version: Option<&str>,
         install_dir: &Path,
         output: &Output,
-    ) -> Result<()> {
+    ) -> Result<InstallResult> {
         let skill_path = self.download(name, version, None, output)?;
-        install_from_file(&skill_path, install_dir, output)?;
-        Ok(())
+        install_from_file(&skill_path, install_dir, output)
     }
 
     /// Delete a skill version (or all versions) from the repository.
     pub fn delete(&self, name: &str, version: Option<&str>, output: &Output) -> Result<()> {
-        let mut index = load_index(&self.client)?;
+        let index = load_index(&self.client)?;
 
         let delete_version_keys = |client: &S, n: &str, v: &str, out: &Output| {
             let keys = [
@@ -202,39 +338,182 @@ impl<S: StorageOperations> Repository<S> {
 
         if let Some(ver) = version {
             delete_version_keys(&self.client, name, ver, output);
-            index.remove_version(name, ver);
+            update_index(&self.client, |index| {
+                index.remove_version(name, ver);
+                Ok(())
+            })?;
             output.step(&format!("Deleted version {} of {}", ver, name));
         } else {
             let entry = index.find_skill(name);
             if let Some(entry) = entry {
-                for ver in entry.versions.keys().cloned().collect::<Vec<_>>() {
-                    delete_version_keys(&self.client, name, &ver, output);
+                for ver in entry.versions.keys() {
+                    delete_version_keys(&self.client, name, ver, output);
                 }
             }
-            index.remove_skill(name);
+            update_index(&self.client, |index| {
+                index.remove_skill(name);
+                Ok(())
+            })?;
             output.step(&format!("Deleted all versions of {}", name));
         }
 
-        save_index(&self.client, &index)?;
-
         // Clear local cache
         if let Some(ref cache) = self.local_cache {
-            if let Some(ver) = version {
-                let cache_key = format!("skills/{}/{}/{}.skill", name, ver, name);
-                cache.delete_object(&cache_key).ok();
-            } else {
-                let prefix = format!("skills/{}/", name);
-                if let Ok(keys) = cache.list_objects(&prefix) {
-                    for key in keys {
-                        cache.delete_object(&key).ok();
-                    }
-                }
-            }
+            cache.remove(name, version).ok();
         }
 
         Ok(())
     }
 
+    /// Check that the index and the stored objects agree.
+    pub fn fsck(&self) -> Result<FsckReport> {
+        fsck::check(&self.client)
+    }
+
+    /// Rebuild the index from the stored packages and save it.
+    pub fn reindex(&self) -> Result<Rebuild> {
+        fsck::reindex(&self.client)
+    }
+
+    /// Copy skills from this repository to `dest`.
+    pub fn mirror_to<T: StorageOperations>(
+        &self,
+        dest: &Repository<T>,
+        options: &MirrorOptions,
+        output: &Output,
+    ) -> Result<MirrorReport> {
+        mirror::mirror(&self.client, &dest.client, options, output)
+    }
+
+    /// Mark a version as yanked, so it is only installed when pinned.
+    pub fn yank(&self, name: &str, version: &str, reason: Option<&str>) -> Result<()> {
+        update_index(&self.client, |index| {
+            let entry = index
+                .find_skill_mut(name)
+                .with_context(|| format!("Skill '{}' not found in repository", name))?;
+            if !entry.versions.contains_key(version) {
+                anyhow::bail!("Version '{}' not found for skill '{}'", version, name);
+            }
+            entry
+                .yanked
+                .insert(version.to_string(), reason.unwrap_or_default().to_string());
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// Undo [`Repository::yank`]. Returns false if the version wasn't yanked.
+    pub fn unyank(&self, name: &str, version: &str) -> Result<bool> {
+        let mut was_yanked = false;
+        update_index(&self.client, |index| {
+            let entry = index
+                .find_skill_mut(name)
+                .with_context(|| format!("Skill '{}' not found in repository", name))?;
+            if !entry.versions.contains_key(version) {
+                anyhow::bail!("Version '{}' not found for skill '{}'", version, name);
+            }
+            was_yanked = entry.yanked.remove(version).is_some();
+            Ok(())
+        })?;
+        Ok(was_yanked)
+    }
+
+    /// Mark a skill as deprecated, optionally naming the skill that replaces it.
+    pub fn deprecate(
+        &self,
+        name: &str,
+        message: Option<&str>,
+        replaced_by: Option<&str>,
+    ) -> Result<()> {
+        if replaced_by == Some(name) {
+            anyhow::bail!("A skill can't be replaced by itself");
+        }
+        update_index(&self.client, |index| {
+            let entry = index
+                .find_skill_mut(name)
+                .with_context(|| format!("Skill '{}' not found in repository", name))?;
+            entry.deprecated = Some(message.unwrap_or_default().to_string());
+            entry.replaced_by = replaced_by.map(str::to_string);
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// Undo [`Repository::deprecate`]. Returns false if the skill wasn't
+    /// deprecated.
+    pub fn undeprecate(&self, name: &str) -> Result<bool> {
+        let mut was_deprecated = false;
+        update_index(&self.client, |index| {
+            let entry = index
+                .find_skill_mut(name)
+                .with_context(|| format!("Skill '{}' not found in repository", name))?;
+            was_deprecated = entry.deprecated.take().is_some() | entry.replaced_by.take().is_some();
+            Ok(())
+        })?;
+        Ok(was_deprecated)
+    }
+
+    /// Whether a version of a skill is in the index.
+    pub fn has_version(&self, name: &str, version: &str) -> Result<bool> {
+        let index = load_index(&self.client)?;
+        Ok(index
+            .find_skill(name)
+            .is_some_and(|entry| entry.versions.contains_key(version)))
+    }
+
+    /// Details of every version of an indexed skill, newest first.
+    pub fn version_details(&self, entry: &IndexEntry) -> Result<Vec<VersionDetails>> {
+        let mut versions: Vec<&String> = entry.versions.keys().collect();
+        versions.sort_by(|a, b| compare_semver(b, a));
+
+        let mut details = Vec::new();
+        for version in versions {
+            let package = self.client.head_object(&entry.versions[version])?;
+            let changelog_key = format!("skills/{}/{}/CHANGELOG.md", entry.name, version);
+            let source_key = format!(
+                "source/{}/{}/{}-source.zip",
+                entry.name, version, entry.name
+            );
+            let published = entry
+                .published
+                .get(version)
+                .and_then(|p| DateTime::parse_from_rfc3339(p).ok())
+                .map(|p| p.with_timezone(&Utc))
+                .or_else(|| package.as_ref().and_then(|m| m.last_modified));
+            details.push(VersionDetails {
+                version: version.clone(),
+                published,
+                size: package.map(|m| m.size),
+                has_changelog: self.client.object_exists(&changelog_key)?,
+                source_size: self.client.head_object(&source_key)?.map(|m| m.size),
+            });
+        }
+        Ok(details)
+    }
+
+    /// The files in a version's package, keyed by their path in the skill.
+    pub fn package_files(&self, name: &str, version: &str) -> Result<BTreeMap<String, Vec<u8>>> {
+        let index = load_index(&self.client)?;
+        let key = index
+            .find_skill(name)
+            .and_then(|entry| entry.versions.get(version))
+            .with_context(|| format!("Version '{}' not found for skill '{}'", version, name))?;
+        let data = self.client.get_object(key)?;
+        read_package_files(Cursor::new(data)).with_context(|| format!("Failed to read {}", key))
+    }
+
+    /// The CHANGELOG.md uploaded with a version, if any.
+    pub fn changelog(&self, name: &str, version: &str) -> Result<Option<String>> {
+        let key = format!("skills/{}/{}/CHANGELOG.md", name, version);
+        if !self.client.object_exists(&key)? {
+            return Ok(None);
+        }
+        let data = self.client.get_object(&key)?;
+        String::from_utf8(data)
+            .map(Some)
+            .with_context(|| format!("{} is not valid UTF-8", key))
+    }
+
     /// List all skills in the repository.
     pub fn list(&self, skill_filter: Option<&str>) -> Result<SkillsIndex> {
         let index = load_index(&self.client)?;
@@ -251,6 +530,15 @@ impl<S: StorageOperations> Repository<S> {
     }
 }
 
+/// Warning for installing a yanked version.
+pub fn yank_warning(name: &str, version: &str, reason: &str) -> String {
+    if reason.is_empty() {
+        format!("{} v{} has been yanked", name, version)
+    } else {
+        format!("{} v{} has been yanked: {}", name, version, reason)
+    }
+}
+
 /// Write skill data to output directory or a temp file.
 fn write_output(name: &str, data: &[u8], output_dir: Option<&Path>) -> Result<PathBuf> {
     let dir = match output_dir {
@@ -263,10 +551,9 @@ fn write_output(name: &str, data: &[u8], output_dir: Option<&Path>) -> Result<Pa
     Ok(dest)
 }
 
-/// Create a zip archive of a source directory.
-fn create_source_archive(source_dir: &Path, name: &str) -> Result<Vec<u8>> {
-    let buffer = Cursor::new(Vec::new());
-    let mut zip = zip::ZipWriter::new(buffer);
+/// Write a zip archive of a source directory to `file`, returning the file.
+fn create_source_archive(source_dir: &Path, name: &str, file: File) -> Result<File> {
+    let mut zip = zip::ZipWriter::new(file);
 
     let options = zip::write::SimpleFileOptions::default()
         .compression_method(zip::CompressionMethod::Deflated);
@@ -274,7 +561,7 @@ fn create_source_archive(source_dir: &Path, name: &str) -> Result<Vec<u8>> {
     let base = source_dir.to_path_buf();
 
     fn add_dir_to_zip(
-        zip: &mut zip::ZipWriter<Cursor<Vec<u8>>>,
+        zip: &mut zip::ZipWriter<File>,
         dir: &Path,
         base: &Path,
         prefix: &str,
@@ -291,10 +578,8 @@ fn create_source_archive(source_dir: &Path, name: &str) -> Result<Vec<u8>> {
                 add_dir_to_zip(zip, &path, base, prefix, options)?;
             } else {
                 zip.start_file(&archive_name, options)?;
-                let mut file = std::fs::File::open(&path)?;
-                let mut buf = Vec::new();
-                file.read_to_end(&mut buf)?;
-                zip.write_all(&buf)?;
+                let mut file = File::open(&path)?;
+                std::io::copy(&mut file, zip)?;
             }
         }
         Ok(())
@@ -303,8 +588,7 @@ fn create_source_archive(source_dir: &Path, name: &str) -> Result<Vec<u8>> {
     let prefix = format!("{}-source", name);
     add_dir_to_zip(&mut zip, &base, &base, &prefix, options)?;
 
-    let cursor = zip.finish()?;
-    Ok(cursor.into_inner())
+    Ok(zip.finish()?)
 }
 
 #[cfg(test)]
@@ -386,6 +670,46 @@ description: A test skill for repository testing with enough characters to pass
         assert_eq!(index.skills[0].versions.len(), 1);
     }
 
+    #[test]
+    fn test_upload_version_refuses_existing_package() {
+        let out = test_output();
+        let (repo, tmp) = setup();
+        let skill_file = create_test_skill(tmp.path());
+        let params = upload_params("test-skill", "1.0.0", &skill_file);
+
+        // Another upload of the same version got its package in first
+        let key = "skills/test-skill/1.0.0/test-skill.skill";
+        repo.client.put_object(key, b"theirs").unwrap();
+        let err = repo.upload_version(&params, false, &out).unwrap_err();
+        assert!(err.to_string().contains("already published"));
+        assert_eq!(repo.client.get_object(key).unwrap(), b"theirs");
+        assert!(repo.list(None).unwrap().skills.is_empty());
+
+        repo.upload_version(&params, true, &out).unwrap();
+        assert!(repo.has_version("test-skill", "1.0.0").unwrap());
+        assert!(repo.upload_version(&params, false, &out).is_err());
+    }
+
+    #[test]
+    fn test_upload_version_retries_after_failed_index_update() {
+        let out = test_output();
+        let (repo, tmp) = setup();
+        let skill_file = create_test_skill(tmp.path());
+        let params = upload_params("test-skill", "1.0.0", &skill_file);
+
+        // An index that doesn't parse fails the upload after the package claim
+        repo.client
+            .put_object(crate::index::INDEX_KEY, b"not json")
+            .unwrap();
+        assert!(repo.upload_version(&params, false, &out).is_err());
+        let key = "skills/test-skill/1.0.0/test-skill.skill";
+        assert!(!repo.client.object_exists(key).unwrap());
+
+        repo.client.delete_object(crate::index::INDEX_KEY).unwrap();
+        repo.upload_version(&params, false, &out).unwrap();
+        assert!(repo.has_version("test-skill", "1.0.0").unwrap());
+    }
+
     #[test]
     fn test_upload_and_download() {
         let out = test_output();
@@ -522,6 +846,38 @@ description: A test skill for repository testing with enough characters to pass
         assert!(path.exists());
     }
 
+    #[test]
+    fn test_yanked_version_only_downloads_when_pinned() {
+        let out = test_output();
+        let (repo, tmp) = setup();
+        let skill_file = create_test_skill(tmp.path());
+
+        repo.upload(&upload_params("test-skill", "1.0.0", &skill_file), &out)
+            .unwrap();
+        repo.upload(&upload_params("test-skill", "2.0.0", &skill_file), &out)
+            .unwrap();
+        assert!(repo.yank("test-skill", "3.0.0", None).is_err());
+        repo.yank("test-skill", "2.0.0", Some("broken links"))
+            .unwrap();
+
+        let index = repo.list(None).unwrap();
+        assert_eq!(index.latest_version("test-skill"), Some("1.0.0"));
+        assert_eq!(
+            index.skills[0].yanked.get("2.0.0").map(String::as_str),
+            Some("broken links")
+        );
+        assert!(repo.download("test-skill", Some("^2"), None, &out).is_err());
+        assert!(repo.download("test-skill", Some("^1"), None, &out).is_ok());
+        assert!(repo
+            .download("test-skill", Some("2.0.0"), None, &out)
+            .is_ok());
+
+        assert!(repo.unyank("test-skill", "2.0.0").unwrap());
+        assert!(!repo.unyank("test-skill", "2.0.0").unwrap());
+        let index = repo.list(None).unwrap();
+        assert_eq!(index.latest_version("test-skill"), Some("2.0.0"));
+    }
+
     #[test]
     fn test_upload_with_changelog() {
         let out = test_output();
@@ -1,12 +1,22 @@
 //! S3-compatible storage client wrapper.
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use s3::creds::Credentials;
+use s3::error::S3Error;
 use s3::region::Region;
+use s3::serde_types::HeadObjectResult;
 use s3::Bucket;
+use std::collections::HashMap;
+use std::io::{self, Cursor, Read};
 
 use crate::config::RepositoryConfig;
-use crate::storage::StorageOperations;
+use crate::storage::{
+    content_type_for_key, ObjectMetadata, PreconditionFailed, PutCondition, StorageOperations,
+};
+
+/// Part size for multipart uploads and ranged reads; S3 parts must be at least 5 MiB.
+const PART_SIZE: usize = 8 * 1024 * 1024;
 
 /// S3 client wrapping the rust-s3 Bucket with a synchronous interface.
 pub struct S3Client {
@@ -21,6 +31,7 @@ impl S3Client {
             .bucket_name
             .as_deref()
             .context("bucket_name is required in repository config")?;
+        crate::offline::ensure_online(format!("S3 bucket '{}'", bucket_name))?;
 
         let region = if let Some(ref endpoint) = config.endpoint {
             Region::Custom {
@@ -31,7 +42,8 @@ impl S3Client {
             config.region.parse().context("Invalid AWS region")?
         };
 
-        let credentials = Credentials::default().context("Failed to load AWS credentials")?;
+        let credentials = Credentials::new(None, None, None, None, config.profile.as_deref())
+            .context("Failed to load AWS credentials")?;
 
         let bucket = Bucket::new(bucket_name, region, credentials)
             .context("Failed to create S3 bucket client")?;
@@ -40,22 +52,146 @@ impl S3Client {
 
         Ok(Self { bucket, runtime })
     }
-}
-
-impl StorageOperations for S3Client {
-    fn put_object(&self, key: &str, data: &[u8]) -> Result<()> {
-        let response = self
-            .runtime
-            .block_on(self.bucket.put_object(key, data))
-            .with_context(|| format!("Failed to put object: {}", key))?;
 
-        if response.status_code() >= 300 {
-            anyhow::bail!(
+    /// Put an object with the bucket's extra headers, e.g. preconditions.
+    fn put_with(&self, bucket: &Bucket, key: &str, data: &[u8]) -> Result<HashMap<String, String>> {
+        let response = self.runtime.block_on(bucket.put_object_with_content_type(
+            key,
+            data,
+            content_type_for_key(key),
+        ));
+        match response {
+            Ok(response) if response.status_code() < 300 => Ok(response.headers()),
+            Ok(response) => anyhow::bail!(
                 "S3 put_object failed with status {} for key: {}",
                 response.status_code(),
                 key
-            );
+            ),
+            // 409 is S3's answer when a concurrent conditional write wins the race
+            Err(S3Error::HttpFailWithBody(412 | 409, _)) => Err(PreconditionFailed {
+                key: key.to_string(),
+            }
+            .into()),
+            Err(e) => Err(e).with_context(|| format!("Failed to put object: {}", key)),
         }
+    }
+
+    /// Upload from a reader in `PART_SIZE` parts.
+    fn put_multipart(&self, key: &str, first: Vec<u8>, reader: &mut dyn Read) -> Result<()> {
+        let content_type = content_type_for_key(key);
+        let upload = self
+            .runtime
+            .block_on(self.bucket.initiate_multipart_upload(key, content_type))
+            .with_context(|| format!("Failed to start upload: {}", key))?;
+
+        let result = (|| -> Result<()> {
+            let mut parts = Vec::new();
+            let mut chunk = first;
+            while !chunk.is_empty() {
+                let part_number = parts.len() as u32 + 1;
+                let part = self
+                    .runtime
+                    .block_on(self.bucket.put_multipart_chunk(
+                        chunk,
+                        key,
+                        part_number,
+                        &upload.upload_id,
+                        content_type,
+                    ))
+                    .with_context(|| format!("Failed to upload part {} of {}", part_number, key))?;
+                parts.push(part);
+                chunk = read_part(reader)?;
+            }
+            self.runtime
+                .block_on(
+                    self.bucket
+                        .complete_multipart_upload(key, &upload.upload_id, parts),
+                )
+                .with_context(|| format!("Failed to complete upload: {}", key))?;
+            Ok(())
+        })();
+
+        if result.is_err() {
+            self.runtime
+                .block_on(self.bucket.abort_upload(key, &upload.upload_id))
+                .ok();
+        }
+        result
+    }
+}
+
+/// Read up to `PART_SIZE` bytes; fewer means the reader is exhausted.
+fn read_part(reader: &mut dyn Read) -> Result<Vec<u8>> {
+    let mut chunk = Vec::with_capacity(PART_SIZE);
+    reader
+        .take(PART_SIZE as u64)
+        .read_to_end(&mut chunk)
+        .context("Failed to read upload data")?;
+    Ok(chunk)
+}
+
+fn parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+fn metadata_from_head(key: &str, head: &HeadObjectResult) -> ObjectMetadata {
+    ObjectMetadata {
+        size: head.content_length.unwrap_or_default().max(0) as u64,
+        etag: head.e_tag.clone().unwrap_or_default(),
+        last_modified: head.last_modified.as_deref().and_then(parse_http_date),
+        content_type: head
+            .content_type
+            .clone()
+            .or_else(|| Some(content_type_for_key(key).to_string())),
+    }
+}
+
+/// Reads an S3 object in `PART_SIZE` ranges as it is consumed.
+struct RangeReader<'a> {
+    client: &'a S3Client,
+    key: String,
+    size: u64,
+    pos: u64,
+    chunk: Cursor<Vec<u8>>,
+}
+
+impl Read for RangeReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.chunk.position() as usize >= self.chunk.get_ref().len() {
+            if self.pos >= self.size {
+                return Ok(0);
+            }
+            let end = (self.pos + PART_SIZE as u64).min(self.size) - 1;
+            // rust-s3 needs start < end; an open range reads the single last byte
+            let range_end = (end > self.pos).then_some(end);
+            let data = self
+                .client
+                .runtime
+                .block_on(
+                    self.client
+                        .bucket
+                        .get_object_range(&self.key, self.pos, range_end),
+                )
+                .map_err(io::Error::other)?
+                .to_vec();
+            if data.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!("S3 object {} ended early", self.key),
+                ));
+            }
+            self.pos += data.len() as u64;
+            self.chunk = Cursor::new(data);
+        }
+        self.chunk.read(buf)
+    }
+}
+
+impl StorageOperations for S3Client {
+    fn put_object(&self, key: &str, data: &[u8]) -> Result<()> {
+        self.put_with(&self.bucket, key, data)?;
         Ok(())
     }
 
@@ -117,16 +253,112 @@ impl StorageOperations for S3Client {
             Err(_) => Ok(false),
         }
     }
+
+    fn head_object(&self, key: &str) -> Result<Option<ObjectMetadata>> {
+        match self.runtime.block_on(self.bucket.head_object(key)) {
+            Ok((_, 404)) | Err(S3Error::HttpFailWithBody(404, _)) => Ok(None),
+            Ok((head, code)) if code < 300 => Ok(Some(metadata_from_head(key, &head))),
+            Ok((_, code)) => anyhow::bail!(
+                "S3 head_object failed with status {} for key: {}",
+                code,
+                key
+            ),
+            Err(e) => Err(e).with_context(|| format!("Failed to read metadata: {}", key)),
+        }
+    }
+
+    fn get_object_with_metadata(&self, key: &str) -> Result<(Vec<u8>, ObjectMetadata)> {
+        let response = self
+            .runtime
+            .block_on(self.bucket.get_object(key))
+            .with_context(|| format!("Failed to get object: {}", key))?;
+        if response.status_code() == 404 {
+            anyhow::bail!("Object not found: {}", key);
+        }
+        if response.status_code() >= 300 {
+            anyhow::bail!(
+                "S3 get_object failed with status {} for key: {}",
+                response.status_code(),
+                key
+            );
+        }
+
+        let headers = response.headers();
+        let data = response.to_vec();
+        let metadata = ObjectMetadata {
+            size: data.len() as u64,
+            etag: headers.get("etag").cloned().unwrap_or_default(),
+            last_modified: headers
+                .get("last-modified")
+                .and_then(|v| parse_http_date(v)),
+            content_type: headers
+                .get("content-type")
+                .cloned()
+                .or_else(|| Some(content_type_for_key(key).to_string())),
+        };
+        Ok((data, metadata))
+    }
+
+    fn get_object_reader(&self, key: &str) -> Result<Box<dyn Read + '_>> {
+        let metadata = self
+            .head_object(key)?
+            .with_context(|| format!("Object not found: {}", key))?;
+        Ok(Box::new(RangeReader {
+            client: self,
+            key: key.to_string(),
+            size: metadata.size,
+            pos: 0,
+            chunk: Cursor::new(Vec::new()),
+        }))
+    }
+
+    fn put_object_from_reader(&self, key: &str, reader: &mut dyn Read) -> Result<()> {
+        let first = read_part(reader)?;
+        if first.len() < PART_SIZE {
+            return self.put_object(key, &first);
+        }
+        self.put_multipart(key, first, reader)
+    }
+
+    fn put_object_if(
+        &self,
+        key: &str,
+        data: &[u8],
+        condition: &PutCondition,
+    ) -> Result<ObjectMetadata> {
+        let mut bucket = self.bucket.as_ref().clone();
+        match condition {
+            PutCondition::IfNoneMatch => bucket.add_header("If-None-Match", "*"),
+            PutCondition::IfMatch(etag) => bucket.add_header("If-Match", etag),
+        }
+        let headers = self.put_with(&bucket, key, data)?;
+        match headers.get("etag") {
+            Some(etag) => Ok(ObjectMetadata {
+                size: data.len() as u64,
+                etag: etag.clone(),
+                last_modified: None,
+                content_type: Some(content_type_for_key(key).to_string()),
+            }),
+            None => self
+                .head_object(key)?
+                .with_context(|| format!("Object not found after write: {}", key)),
+        }
+    }
 }
 
 /// Mock S3 client for testing, backed by an in-memory HashMap.
 pub mod mock {
     use super::*;
+    use crate::storage::content_etag;
     use std::cell::RefCell;
-    use std::collections::HashMap;
+
+    struct MockObject {
+        data: Vec<u8>,
+        metadata: ObjectMetadata,
+    }
 
     pub struct MockS3Client {
-        store: RefCell<HashMap<String, Vec<u8>>>,
+        store: RefCell<HashMap<String, MockObject>>,
     }
 
     impl Default for MockS3Client {
@@ -145,18 +377,24 @@ pub mod mock {
 
     impl StorageOperations for MockS3Client {
         fn put_object(&self, key: &str, data: &[u8]) -> Result<()> {
-            self.store
-                .borrow_mut()
-                .insert(key.to_string(), data.to_vec());
+            let metadata = ObjectMetadata {
+                size: data.len() as u64,
+                etag: format!("\"{}\"", content_etag(data)),
+                last_modified: Some(chrono::Utc::now()),
+                content_type: Some(content_type_for_key(key).to_string()),
+            };
+            self.store.borrow_mut().insert(
+                key.to_string(),
+                MockObject {
+                    data: data.to_vec(),
+                    metadata,
+                },
+            );
             Ok(())
         }
 
         fn get_object(&self, key: &str) -> Result<Vec<u8>> {
-            self.store
-                .borrow()
-                .get(key)
-                .cloned()
-                .ok_or_else(|| anyhow::anyhow!("Object not found: {}", key))
+            self.get_object_with_metadata(key).map(|(data, _)| data)
         }
 
         fn delete_object(&self, key: &str) -> Result<()> {
@@ -178,6 +416,34 @@ pub mod mock {
         fn object_exists(&self, key: &str) -> Result<bool> {
             Ok(self.store.borrow().contains_key(key))
         }
+
+        fn head_object(&self, key: &str) -> Result<Option<ObjectMetadata>> {
+            Ok(self.store.borrow().get(key).map(|o| o.metadata.clone()))
+        }
+
+        fn get_object_with_metadata(&self, key: &str) -> Result<(Vec<u8>, ObjectMetadata)> {
+            self.store
+                .borrow()
+                .get(key)
+                .map(|o| (o.data.clone(), o.metadata.clone()))
+                .ok_or_else(|| anyhow::anyhow!("Object not found: {}", key))
+        }
+
+        fn put_object_if(
+            &self,
+            key: &str,
+            data: &[u8],
+            condition: &PutCondition,
+        ) -> Result<ObjectMetadata> {
+            if !condition.is_met(self.head_object(key)?.as_ref()) {
+                return Err(PreconditionFailed {
+                    key: key.to_string(),
+                }
+                .into());
+            }
+            self.put_object(key, data)?;
+            Ok(self.head_object(key)?.expect("object was just written"))
+        }
     }
 }
 
@@ -222,6 +488,59 @@ mod tests {
         assert_eq!(keys, vec!["skills/a/1.0/a.skill", "skills/a/2.0/a.skill"]);
     }
 
+    #[test]
+    fn test_mock_metadata() {
+        let client = MockS3Client::new();
+        client.put_object("skills/a/1.0/a.skill", b"zip").unwrap();
+
+        let meta = client.head_object("skills/a/1.0/a.skill").unwrap().unwrap();
+        assert_eq!(meta.size, 3);
+        assert_eq!(meta.content_type.as_deref(), Some("application/zip"));
+        assert!(client.head_object("missing").unwrap().is_none());
+
+        let (data, read_meta) = client
+            .get_object_with_metadata("skills/a/1.0/a.skill")
+            .unwrap();
+        assert_eq!(data, b"zip");
+        assert_eq!(read_meta, meta);
+    }
+
+    #[test]
+    fn test_mock_conditional_put() {
+        let client = MockS3Client::new();
+        let meta = client
+            .put_object_if("index", b"v1", &PutCondition::IfNoneMatch)
+            .unwrap();
+        client.put_object("index", b"v2").unwrap();
+
+        let err = client
+            .put_object_if("index", b"v3", &PutCondition::IfMatch(meta.etag))
+            .unwrap_err();
+        assert!(crate::storage::is_precondition_failed(&err));
+        assert_eq!(client.get_object("index").unwrap(), b"v2");
+    }
+
+    #[test]
+    fn test_mock_streaming_defaults() {
+        let client = MockS3Client::new();
+        client
+            .put_object_from_reader("key", &mut &b"streamed"[..])
+            .unwrap();
+        let mut data = Vec::new();
+        client
+            .get_object_reader("key")
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap();
+        assert_eq!(data, b"streamed");
+    }
+
+    #[test]
+    fn test_parse_http_date() {
+        let date = parse_http_date("Mon, 12 Oct 2009 17:50:00 GMT").unwrap();
+        assert_eq!(date.to_rfc3339(), "2009-10-12T17:50:00+00:00");
+    }
+
     #[test]
     fn test_mock_object_exists() {
         let client = MockS3Client::new();
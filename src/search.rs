@@ -0,0 +1,154 @@
+//! Keyword search over skills indexes.
+
+use crate::index::{IndexEntry, SkillsIndex};
+
+/// A skill matching a search query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    /// Where the skill was found, e.g. a repository name or "GitHub releases".
+    pub source: String,
+    pub entry: IndexEntry,
+    /// Relevance; higher is better.
+    pub score: u32,
+}
+
+/// How well one lowercase query term matches an entry, or 0 for no match.
+///
+/// Matches on the name count most, then tags, then the description and
+/// finally the llms.txt URL. A term matching several fields adds them up.
+fn term_score(entry: &IndexEntry, term: &str) -> u32 {
+    let name = entry.name.to_lowercase();
+    let mut score = if name == term {
+        100
+    } else if name.starts_with(term) {
+        60
+    } else if name.contains(term) {
+        40
+    } else {
+        0
+    };
+    let tags: Vec<String> = entry.tags.iter().map(|t| t.to_lowercase()).collect();
+    if tags.iter().any(|t| t == term) {
+        score += 30;
+    } else if tags.iter().any(|t| t.contains(term)) {
+        score += 15;
+    }
+    if entry.description.to_lowercase().contains(term) {
+        score += 10;
+    }
+    if entry.llms_txt_url.to_lowercase().contains(term) {
+        score += 5;
+    }
+    score
+}
+
+/// Score an entry against a whitespace-separated query.
+///
+/// Every term has to match somewhere; `None` means the entry doesn't match.
+pub fn score(entry: &IndexEntry, query: &str) -> Option<u32> {
+    let mut total = 0;
+    for term in query.split_whitespace() {
+        match term_score(entry, &term.to_lowercase()) {
+            0 => return None,
+            score => total += score,
+        }
+    }
+    (total > 0).then_some(total)
+}
+
+/// The entries of `index` matching `query`, attributed to `source`.
+pub fn search_index(index: &SkillsIndex, source: &str, query: &str) -> Vec<SearchHit> {
+    index
+        .skills
+        .iter()
+        .filter_map(|entry| {
+            score(entry, query).map(|score| SearchHit {
+                source: source.to_string(),
+                entry: entry.clone(),
+                score,
+            })
+        })
+        .collect()
+}
+
+/// Sort hits best first. Ties go by name, then keep the order the sources
+/// were searched in.
+pub fn rank(hits: &mut [SearchHit]) {
+    hits.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.entry.name.cmp(&b.entry.name))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, description: &str, tags: &[&str], url: &str) -> IndexEntry {
+        IndexEntry {
+            name: name.to_string(),
+            description: description.to_string(),
+            llms_txt_url: url.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn index() -> SkillsIndex {
+        SkillsIndex {
+            skills: vec![
+                entry(
+                    "svelte",
+                    "Svelte 5 components",
+                    &["ui"],
+                    "https://svelte.dev/llms.txt",
+                ),
+                entry(
+                    "shadcn-svelte",
+                    "UI components for Svelte",
+                    &["svelte", "ui"],
+                    "https://shadcn-svelte.com/llms.txt",
+                ),
+                entry("tailwind", "Utility-first CSS", &["css"], ""),
+                entry("hono", "Web framework", &[], "https://hono.dev/llms.txt"),
+            ],
+        }
+    }
+
+    fn names(query: &str) -> Vec<String> {
+        let mut hits = search_index(&index(), "local", query);
+        rank(&mut hits);
+        hits.into_iter().map(|h| h.entry.name).collect()
+    }
+
+    #[test]
+    fn test_name_matches_rank_first() {
+        assert_eq!(names("svelte"), vec!["svelte", "shadcn-svelte"]);
+        assert_eq!(names("SHADCN"), vec!["shadcn-svelte"]);
+    }
+
+    #[test]
+    fn test_tags_description_and_url_match() {
+        assert_eq!(names("css"), vec!["tailwind"]);
+        assert_eq!(names("ui"), vec!["shadcn-svelte", "svelte"]);
+        assert_eq!(names("framework"), vec!["hono"]);
+        assert_eq!(names("hono.dev"), vec!["hono"]);
+    }
+
+    #[test]
+    fn test_every_term_must_match() {
+        assert_eq!(names("svelte components"), vec!["svelte", "shadcn-svelte"]);
+        assert_eq!(names("svelte css"), Vec::<String>::new());
+        assert_eq!(names("   "), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_ties_keep_source_order() {
+        let mut hits = search_index(&index(), "first", "hono");
+        hits.extend(search_index(&index(), "second", "hono"));
+        rank(&mut hits);
+        let sources: Vec<&str> = hits.iter().map(|h| h.source.as_str()).collect();
+        assert_eq!(sources, vec!["first", "second"]);
+    }
+}
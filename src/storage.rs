@@ -1,12 +1,316 @@
-//! Storage operations trait for S3 and filesystem backends.
+//! Storage operations trait for S3, HTTP and filesystem backends.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::{Cursor, Read};
+use std::time::Duration;
+
+/// Metadata about a stored object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectMetadata {
+    pub size: u64,
+    /// Opaque tag that changes whenever the object's content changes.
+    pub etag: String,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub content_type: Option<String>,
+}
+
+impl ObjectMetadata {
+    /// Metadata for `data` held by a backend with no native ETags.
+    pub fn for_data(key: &str, data: &[u8]) -> Self {
+        Self {
+            size: data.len() as u64,
+            etag: content_etag(data),
+            last_modified: None,
+            content_type: Some(content_type_for_key(key).to_string()),
+        }
+    }
+}
+
+/// Precondition for a compare-and-swap write.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PutCondition {
+    /// Only write if the object doesn't exist yet.
+    IfNoneMatch,
+    /// Only write if the object's current ETag is this one.
+    IfMatch(String),
+}
+
+impl PutCondition {
+    /// Condition that the object is still as it was when read: `IfMatch` its
+    /// ETag, or `IfNoneMatch` if it didn't exist.
+    pub fn unchanged(metadata: Option<&ObjectMetadata>) -> Self {
+        match metadata {
+            Some(m) => Self::IfMatch(m.etag.clone()),
+            None => Self::IfNoneMatch,
+        }
+    }
+
+    /// Whether an object with `current` metadata satisfies the condition.
+    pub fn is_met(&self, current: Option<&ObjectMetadata>) -> bool {
+        match (self, current) {
+            (Self::IfNoneMatch, current) => current.is_none(),
+            (Self::IfMatch(etag), Some(current)) => current.etag == *etag,
+            (Self::IfMatch(_), None) => false,
+        }
+    }
+}
+
+/// Error returned by [`StorageOperations::put_object_if`] when the object
+/// changed since it was read. Detect it with [`is_precondition_failed`].
+#[derive(Debug)]
+pub struct PreconditionFailed {
+    pub key: String,
+}
+
+impl fmt::Display for PreconditionFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Object was changed by another writer: {}", self.key)
+    }
+}
+
+impl std::error::Error for PreconditionFailed {}
+
+/// Whether `err` is a failed conditional write.
+pub fn is_precondition_failed(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<PreconditionFailed>().is_some()
+}
+
+/// How many times [`update_json_object`] tries before giving up.
+pub(crate) const UPDATE_ATTEMPTS: u32 = 8;
+
+/// Read the JSON document at `key`, apply `mutate` and write it back only if
+/// nobody else wrote it in the meantime. On a conflict the document is re-read
+/// and `mutate` applied again, after a jittered backoff so writers stop
+/// colliding. A missing document starts out as `T::default()`.
+///
+/// `what` names the document in errors, e.g. "skills index". Returns the
+/// document as saved.
+pub fn update_json_object<S, T, F>(client: &S, key: &str, what: &str, mut mutate: F) -> Result<T>
+where
+    S: StorageOperations + ?Sized,
+    T: Default + Serialize + DeserializeOwned,
+    F: FnMut(&mut T) -> Result<()>,
+{
+    for attempt in 0..UPDATE_ATTEMPTS {
+        let (mut value, condition) = match client.get_object_with_metadata(key) {
+            Ok((data, metadata)) => {
+                let value = serde_json::from_slice(&data)
+                    .with_context(|| format!("Failed to parse {}", what))?;
+                (value, PutCondition::IfMatch(metadata.etag))
+            }
+            // A failed read only means "not there yet" if the object really is missing
+            Err(e) => match client.head_object(key) {
+                Ok(None) => (T::default(), PutCondition::IfNoneMatch),
+                // Another writer created it since the read; read it again
+                Ok(Some(_)) if attempt + 1 < UPDATE_ATTEMPTS => continue,
+                _ => return Err(e.context(format!("Failed to read {}", what))),
+            },
+        };
+
+        mutate(&mut value)?;
+        let json = serde_json::to_string_pretty(&value)
+            .with_context(|| format!("Failed to serialize {}", what))?;
+        match client.put_object_if(key, json.as_bytes(), &condition) {
+            Ok(_) => return Ok(value),
+            Err(e) if is_precondition_failed(&e) => {
+                // Back off a little more each time, with jitter so writers stop colliding
+                let jitter = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.subsec_nanos() % 20)
+                    .unwrap_or(0);
+                let delay = 20 * u64::from(attempt + 1) + u64::from(jitter);
+                std::thread::sleep(Duration::from_millis(delay));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    anyhow::bail!(
+        "Gave up updating the {} after {} attempts: other writers kept changing it",
+        what,
+        UPDATE_ATTEMPTS
+    )
+}
+
+/// ETag derived from content, for backends without native ETags.
+pub fn content_etag(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// SHA-256 of everything `reader` yields, as hex.
+pub fn reader_sha256(reader: &mut dyn Read) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(reader, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Content type for an object, guessed from its key.
+pub fn content_type_for_key(key: &str) -> &'static str {
+    match key.rsplit_once('.').map(|(_, ext)| ext) {
+        Some("json") => "application/json",
+        Some("md") => "text/markdown; charset=utf-8",
+        Some("skill" | "zip") => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
 
 /// Trait for storage operations, enabling S3, filesystem, and mock implementations.
+///
+/// Only the whole-buffer operations are required. Metadata, streaming and
+/// conditional writes have default implementations built on them, which
+/// backends override when they can do better.
 pub trait StorageOperations {
     fn put_object(&self, key: &str, data: &[u8]) -> Result<()>;
     fn get_object(&self, key: &str) -> Result<Vec<u8>>;
     fn delete_object(&self, key: &str) -> Result<()>;
     fn list_objects(&self, prefix: &str) -> Result<Vec<String>>;
     fn object_exists(&self, key: &str) -> Result<bool>;
+
+    /// Metadata for `key`, or `None` if it doesn't exist.
+    fn head_object(&self, key: &str) -> Result<Option<ObjectMetadata>> {
+        if !self.object_exists(key)? {
+            return Ok(None);
+        }
+        let data = self.get_object(key)?;
+        Ok(Some(ObjectMetadata::for_data(key, &data)))
+    }
+
+    /// Read an object together with the metadata of the version read.
+    fn get_object_with_metadata(&self, key: &str) -> Result<(Vec<u8>, ObjectMetadata)> {
+        let data = self.get_object(key)?;
+        let metadata = ObjectMetadata::for_data(key, &data);
+        Ok((data, metadata))
+    }
+
+    /// Open an object for reading without loading it all at once.
+    fn get_object_reader(&self, key: &str) -> Result<Box<dyn Read + '_>> {
+        Ok(Box::new(Cursor::new(self.get_object(key)?)))
+    }
+
+    /// Write an object from a reader without loading it all at once.
+    fn put_object_from_reader(&self, key: &str, reader: &mut dyn Read) -> Result<()> {
+        let mut data = Vec::new();
+        reader
+            .read_to_end(&mut data)
+            .with_context(|| format!("Failed to read data for {}", key))?;
+        self.put_object(key, &data)
+    }
+
+    /// Write an object only if `condition` holds, failing with
+    /// [`PreconditionFailed`] otherwise. Returns the new object's metadata.
+    ///
+    /// The default checks and then writes, which is not atomic; backends with
+    /// native conditional writes override it.
+    fn put_object_if(
+        &self,
+        key: &str,
+        data: &[u8],
+        condition: &PutCondition,
+    ) -> Result<ObjectMetadata> {
+        if !condition.is_met(self.head_object(key)?.as_ref()) {
+            return Err(PreconditionFailed {
+                key: key.to_string(),
+            }
+            .into());
+        }
+        self.put_object(key, data)?;
+        Ok(ObjectMetadata::for_data(key, data))
+    }
+}
+
+/// Boxed backends, so a repository can pick its storage at runtime.
+impl<T: StorageOperations + ?Sized> StorageOperations for Box<T> {
+    fn put_object(&self, key: &str, data: &[u8]) -> Result<()> {
+        (**self).put_object(key, data)
+    }
+
+    fn get_object(&self, key: &str) -> Result<Vec<u8>> {
+        (**self).get_object(key)
+    }
+
+    fn delete_object(&self, key: &str) -> Result<()> {
+        (**self).delete_object(key)
+    }
+
+    fn list_objects(&self, prefix: &str) -> Result<Vec<String>> {
+        (**self).list_objects(prefix)
+    }
+
+    fn object_exists(&self, key: &str) -> Result<bool> {
+        (**self).object_exists(key)
+    }
+
+    fn head_object(&self, key: &str) -> Result<Option<ObjectMetadata>> {
+        (**self).head_object(key)
+    }
+
+    fn get_object_with_metadata(&self, key: &str) -> Result<(Vec<u8>, ObjectMetadata)> {
+        (**self).get_object_with_metadata(key)
+    }
+
+    fn get_object_reader(&self, key: &str) -> Result<Box<dyn Read + '_>> {
+        (**self).get_object_reader(key)
+    }
+
+    fn put_object_from_reader(&self, key: &str, reader: &mut dyn Read) -> Result<()> {
+        (**self).put_object_from_reader(key, reader)
+    }
+
+    fn put_object_if(
+        &self,
+        key: &str,
+        data: &[u8],
+        condition: &PutCondition,
+    ) -> Result<ObjectMetadata> {
+        (**self).put_object_if(key, data, condition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_condition() {
+        let meta = ObjectMetadata::for_data("skills_index.json", b"{}");
+        let other = ObjectMetadata::for_data("skills_index.json", b"[]");
+
+        assert!(PutCondition::IfNoneMatch.is_met(None));
+        assert!(!PutCondition::IfNoneMatch.is_met(Some(&meta)));
+        assert!(PutCondition::unchanged(Some(&meta)).is_met(Some(&meta)));
+        assert!(!PutCondition::unchanged(Some(&meta)).is_met(Some(&other)));
+        assert!(!PutCondition::unchanged(Some(&meta)).is_met(None));
+    }
+
+    #[test]
+    fn test_content_type_for_key() {
+        assert_eq!(
+            content_type_for_key("skills_index.json"),
+            "application/json"
+        );
+        assert_eq!(
+            content_type_for_key("skills/a/1.0/a.skill"),
+            "application/zip"
+        );
+        assert_eq!(
+            content_type_for_key("skills/a/1.0/CHANGELOG.md"),
+            "text/markdown; charset=utf-8"
+        );
+        assert_eq!(content_type_for_key("blob"), "application/octet-stream");
+    }
+
+    #[test]
+    fn test_precondition_failed_is_detectable() {
+        let err: anyhow::Error = PreconditionFailed {
+            key: "k".to_string(),
+        }
+        .into();
+        assert!(is_precondition_failed(&err));
+        assert!(is_precondition_failed(&err.context("while saving index")));
+        assert!(!is_precondition_failed(&anyhow::anyhow!("other")));
+    }
 }
@@ -72,13 +72,28 @@ impl ValidationResult {
 
 /// Parsed frontmatter from SKILL.md.
 #[derive(Debug, Default)]
-struct Frontmatter {
-    name: Option<String>,
-    description: Option<String>,
+pub struct Frontmatter {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// Version written by `sb release`.
+    pub version: Option<String>,
+    /// Search keywords, as `tags: [a, b]`, `tags: a, b` or a block list.
+    pub tags: Vec<String>,
+    pub author: Option<String>,
+    pub homepage: Option<String>,
+}
+
+/// Strip matching single or double quotes around a YAML scalar.
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    ['"', '\'']
+        .iter()
+        .find_map(|q| value.strip_prefix(*q)?.strip_suffix(*q))
+        .unwrap_or(value)
 }
 
 /// Parse YAML frontmatter from markdown content.
-fn parse_frontmatter(content: &str) -> Result<Frontmatter, ValidationError> {
+pub fn parse_frontmatter(content: &str) -> Result<Frontmatter, ValidationError> {
     // Use (?s) for DOTALL mode so . matches newlines
     let re = Regex::new(r"(?s)^---\n(.*?)\n---").unwrap();
 
@@ -94,13 +109,38 @@ fn parse_frontmatter(content: &str) -> Result<Frontmatter, ValidationError> {
 
     let mut frontmatter = Frontmatter::default();
 
-    // Simple YAML parsing for name and description fields
+    // Simple YAML parsing for the fields sb uses
+    let mut in_tags = false;
     for line in yaml_content.lines() {
         let line = line.trim();
+        if in_tags {
+            if let Some(tag) = line.strip_prefix('-') {
+                frontmatter.tags.push(unquote(tag).to_string());
+                continue;
+            }
+            in_tags = false;
+        }
         if let Some(value) = line.strip_prefix("name:") {
             frontmatter.name = Some(value.trim().to_string());
         } else if let Some(value) = line.strip_prefix("description:") {
             frontmatter.description = Some(value.trim().to_string());
+        } else if let Some(value) = line.strip_prefix("tags:") {
+            let value = value.trim();
+            in_tags = value.is_empty();
+            let list = value.strip_prefix('[').and_then(|v| v.strip_suffix(']'));
+            frontmatter.tags = list
+                .unwrap_or(value)
+                .split(',')
+                .map(unquote)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect();
+        } else if let Some(value) = line.strip_prefix("version:") {
+            frontmatter.version = Some(unquote(value).to_string()).filter(|v| !v.is_empty());
+        } else if let Some(value) = line.strip_prefix("author:") {
+            frontmatter.author = Some(unquote(value).to_string()).filter(|v| !v.is_empty());
+        } else if let Some(value) = line.strip_prefix("homepage:") {
+            frontmatter.homepage = Some(unquote(value).to_string()).filter(|v| !v.is_empty());
         }
     }
 
@@ -441,6 +481,27 @@ description: A test skill with a description that is at least fifty characters l
             .any(|w| w.contains("References directory is empty")));
     }
 
+    #[test]
+    fn test_parse_frontmatter_metadata() {
+        let inline = parse_frontmatter(
+            "---\nname: a\ntags: [svelte, \"ui\"]\nauthor: 'Jane Doe'\nhomepage: https://example.com\n---\n",
+        )
+        .unwrap();
+        assert_eq!(inline.tags, vec!["svelte", "ui"]);
+        assert_eq!(inline.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(inline.homepage.as_deref(), Some("https://example.com"));
+
+        let block =
+            parse_frontmatter("---\nname: a\ntags:\n  - svelte\n  - ui\ndescription: d\n---\n")
+                .unwrap();
+        assert_eq!(block.tags, vec!["svelte", "ui"]);
+        assert_eq!(block.description.as_deref(), Some("d"));
+
+        let plain = parse_frontmatter("---\nname: a\ntags: svelte, ui\n---\n").unwrap();
+        assert_eq!(plain.tags, vec!["svelte", "ui"]);
+        assert!(plain.author.is_none());
+    }
+
     #[test]
     fn test_validation_error_display() {
         assert_eq!(
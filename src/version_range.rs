@@ -0,0 +1,229 @@
+//! Version ranges such as `>=1.2.0, <2.0.0`, `^1.2` or `1.x`.
+
+use anyhow::{Context, Result};
+use std::fmt;
+use std::str::FromStr;
+
+type Version = [u64; 3];
+
+/// Parse the major.minor.patch part of a version, ignoring a leading `v` and
+/// any pre-release or build suffix.
+fn parse_version(s: &str) -> Option<Version> {
+    let mut version = [0; 3];
+    let core = s.trim().trim_start_matches('v');
+    let core = core.split(['-', '+']).next().unwrap_or_default();
+    for (i, part) in core.split('.').enumerate() {
+        *version.get_mut(i)? = part.parse().ok()?;
+    }
+    Some(version)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Comparator {
+    op: Op,
+    version: Version,
+}
+
+impl Comparator {
+    fn matches(&self, v: &Version) -> bool {
+        match self.op {
+            Op::Eq => v == &self.version,
+            Op::Gt => v > &self.version,
+            Op::Ge => v >= &self.version,
+            Op::Lt => v < &self.version,
+            Op::Le => v <= &self.version,
+        }
+    }
+}
+
+/// Bounds `>= lower, < upper`.
+fn between(lower: Version, upper: Version) -> Vec<Comparator> {
+    vec![
+        Comparator {
+            op: Op::Ge,
+            version: lower,
+        },
+        Comparator {
+            op: Op::Lt,
+            version: upper,
+        },
+    ]
+}
+
+/// Parse one comparator, which may expand to a pair of bounds.
+fn parse_comparator(text: &str) -> Result<Vec<Comparator>> {
+    let text = text.trim();
+    let (op, rest) = [">=", "<=", ">", "<", "=", "^", "~"]
+        .iter()
+        .find_map(|op| text.strip_prefix(op).map(|rest| (*op, rest.trim())))
+        .unwrap_or(("", text));
+
+    // Numeric parts given before any wildcard
+    let mut parts = Vec::new();
+    for part in rest.trim_start_matches('v').split('.') {
+        if matches!(part, "x" | "X" | "*") {
+            break;
+        }
+        parts.push(
+            part.parse::<u64>()
+                .with_context(|| format!("Invalid version '{}' in range", rest))?,
+        );
+    }
+    if parts.len() > 3 {
+        anyhow::bail!("Invalid version '{}' in range", rest);
+    }
+    let mut version = [0; 3];
+    version[..parts.len()].copy_from_slice(&parts);
+    let [major, minor, patch] = version;
+
+    let comparators = match (op, parts.len()) {
+        ("^", _) if major > 0 || parts.len() == 1 => between(version, [major + 1, 0, 0]),
+        ("^", _) if minor > 0 || parts.len() == 2 => between(version, [0, minor + 1, 0]),
+        ("^", _) => between(version, [0, 0, patch + 1]),
+        ("~", 1) => between(version, [major + 1, 0, 0]),
+        ("~", _) => between(version, [major, minor + 1, 0]),
+        ("" | "=", 0) => Vec::new(),
+        ("" | "=", 1) => between(version, [major + 1, 0, 0]),
+        ("" | "=", 2) => between(version, [major, minor + 1, 0]),
+        ("" | "=", _) => vec![Comparator {
+            op: Op::Eq,
+            version,
+        }],
+        (op, _) => {
+            let op = match op {
+                ">=" => Op::Ge,
+                "<=" => Op::Le,
+                ">" => Op::Gt,
+                _ => Op::Lt,
+            };
+            vec![Comparator { op, version }]
+        }
+    };
+    Ok(comparators)
+}
+
+/// A set of versions, written as comma-separated comparators that must all
+/// hold.
+///
+/// A full version (`1.2.3`) matches only itself; a partial one (`1.2`, `1.x`)
+/// matches every version it is a prefix of. `^` and `~` work as in Cargo, and
+/// `*` matches everything.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionRange {
+    text: String,
+    comparators: Vec<Comparator>,
+}
+
+impl VersionRange {
+    /// Parse a range such as `>=1.0.0, <2.0.0`.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut comparators = Vec::new();
+        for part in text.split(',') {
+            if part.trim().is_empty() {
+                anyhow::bail!("Empty comparator in version range '{}'", text);
+            }
+            comparators.extend(parse_comparator(part)?);
+        }
+        Ok(Self {
+            text: text.trim().to_string(),
+            comparators,
+        })
+    }
+
+    /// Whether `version` is in the range. Versions that don't parse never are.
+    pub fn matches(&self, version: &str) -> bool {
+        parse_version(version).is_some_and(|v| self.comparators.iter().all(|c| c.matches(&v)))
+    }
+}
+
+impl FromStr for VersionRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matching(range: &str, versions: &[&str]) -> Vec<String> {
+        let range = VersionRange::parse(range).unwrap();
+        versions
+            .iter()
+            .filter(|v| range.matches(v))
+            .map(|v| v.to_string())
+            .collect()
+    }
+
+    const VERSIONS: &[&str] = &[
+        "0.1.0", "0.1.5", "0.2.0", "1.0.0", "1.2.0", "1.2.7", "2.0.0",
+    ];
+
+    #[test]
+    fn test_exact_and_partial_versions() {
+        assert_eq!(matching("1.2.0", VERSIONS), vec!["1.2.0"]);
+        assert_eq!(matching("=v1.2.0", VERSIONS), vec!["1.2.0"]);
+        assert_eq!(matching("1.2", VERSIONS), vec!["1.2.0", "1.2.7"]);
+        assert_eq!(matching("1.x", VERSIONS), vec!["1.0.0", "1.2.0", "1.2.7"]);
+        assert_eq!(matching("*", VERSIONS), VERSIONS);
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(
+            matching(">=1.0.0, <2.0.0", VERSIONS),
+            vec!["1.0.0", "1.2.0", "1.2.7"]
+        );
+        assert_eq!(matching(">1.2.0", VERSIONS), vec!["1.2.7", "2.0.0"]);
+        assert_eq!(matching("<=0.1.5", VERSIONS), vec!["0.1.0", "0.1.5"]);
+    }
+
+    #[test]
+    fn test_caret_and_tilde() {
+        assert_eq!(matching("^1.2", VERSIONS), vec!["1.2.0", "1.2.7"]);
+        assert_eq!(
+            matching("^1.0.0", VERSIONS),
+            vec!["1.0.0", "1.2.0", "1.2.7"]
+        );
+        assert_eq!(matching("^0.1.0", VERSIONS), vec!["0.1.0", "0.1.5"]);
+        assert_eq!(matching("~1.2.0", VERSIONS), vec!["1.2.0", "1.2.7"]);
+        assert_eq!(matching("~1", VERSIONS), vec!["1.0.0", "1.2.0", "1.2.7"]);
+    }
+
+    #[test]
+    fn test_prerelease_suffix_is_ignored() {
+        assert!(VersionRange::parse("^1.0").unwrap().matches("1.3.0-beta.1"));
+        assert!(!VersionRange::parse("^1.0").unwrap().matches("latest"));
+    }
+
+    #[test]
+    fn test_invalid_ranges() {
+        assert!(VersionRange::parse("").is_err());
+        assert!(VersionRange::parse(">=1.0,").is_err());
+        assert!(VersionRange::parse("abc").is_err());
+        assert!(VersionRange::parse("1.2.3.4").is_err());
+    }
+
+    #[test]
+    fn test_display_keeps_input() {
+        let range: VersionRange = ">=1.0, <2.0".parse().unwrap();
+        assert_eq!(range.to_string(), ">=1.0, <2.0");
+    }
+}
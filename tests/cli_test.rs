@@ -294,6 +294,31 @@ fn test_repo_delete_help() {
         .stdout(predicate::str::contains("Delete"));
 }
 
+#[test]
+fn test_repo_delete_requires_purge() {
+    let temp = TempDir::new().unwrap();
+    sb().env("HOME", temp.path())
+        .current_dir(temp.path())
+        .args(["repo", "delete", "my-skill", "--version", "1.0.0", "--yes"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("sb repo yank my-skill 1.0.0"))
+        .stderr(predicate::str::contains("--purge"));
+}
+
+#[test]
+fn test_repo_yank_help() {
+    sb().args(["repo", "yank", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("exact"))
+        .stdout(predicate::str::contains("--reason"));
+    sb().args(["repo", "unyank", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("yanked"));
+}
+
 #[test]
 fn test_repo_list_help() {
     sb().args(["repo", "list", "--help"])
@@ -417,20 +442,28 @@ fn test_install_file_agent_flag() {
     // Install with --agent codex
     let skill_file = package_dir.join("agent-flag-skill.skill");
 
-    sb().args([
-        "install",
-        "agent-flag-skill",
-        "--file",
-        &skill_file.to_string_lossy(),
-        "--agent",
-        "codex",
-    ])
-    .assert()
-    .success()
-    .stderr(predicate::str::contains("Installed"));
-
-    // The --agent codex flag was accepted without error.
-    // Path verification is tricky since the install happens relative to test cwd.
+    let project = temp.path().join("project");
+    fs::create_dir_all(&project).unwrap();
+
+    sb().current_dir(&project)
+        .args([
+            "install",
+            "agent-flag-skill",
+            "--file",
+            &skill_file.to_string_lossy(),
+            "--agent",
+            "codex",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Installed"));
+
+    // Installed to the Codex dir and registered in AGENTS.md by the Codex adapter
+    assert!(project
+        .join(".agents/skills/agent-flag-skill/SKILL.md")
+        .exists());
+    let agents_md = fs::read_to_string(project.join("AGENTS.md")).unwrap();
+    assert!(agents_md.contains(".agents/skills/agent-flag-skill/SKILL.md"));
 }
 
 #[test]
@@ -506,3 +539,718 @@ fn test_agent_flag_help() {
         .success()
         .stdout(predicate::str::contains("--agent"));
 }
+
+#[test]
+fn test_install_from_subdirectory_uses_project_root() {
+    let temp = TempDir::new().unwrap();
+
+    let skill_dir = temp.path().join("root-skill");
+    common::create_valid_skill(&skill_dir);
+    let package_dir = temp.path().join("packages");
+    sb().args([
+        "package",
+        &skill_dir.to_string_lossy(),
+        "--output",
+        &package_dir.to_string_lossy(),
+    ])
+    .assert()
+    .success();
+
+    // Project with a .claude marker at its root, run from a nested directory
+    let project = temp.path().join("project");
+    fs::create_dir_all(project.join(".claude")).unwrap();
+    let nested = project.join("src/components");
+    fs::create_dir_all(&nested).unwrap();
+
+    sb().current_dir(&nested)
+        .args([
+            "install",
+            "root-skill",
+            "--file",
+            &package_dir.join("root-skill.skill").to_string_lossy(),
+        ])
+        .assert()
+        .success();
+
+    assert!(project.join(".claude/skills/root-skill/SKILL.md").exists());
+    assert!(!nested.join(".claude").exists());
+}
+
+#[test]
+fn test_install_project_root_override() {
+    let temp = TempDir::new().unwrap();
+
+    let skill_dir = temp.path().join("override-root-skill");
+    common::create_valid_skill(&skill_dir);
+    let package_dir = temp.path().join("packages");
+    sb().args([
+        "package",
+        &skill_dir.to_string_lossy(),
+        "--output",
+        &package_dir.to_string_lossy(),
+    ])
+    .assert()
+    .success();
+
+    let project = temp.path().join("elsewhere");
+    fs::create_dir_all(project.join(".opencode")).unwrap();
+
+    sb().current_dir(temp.path())
+        .args([
+            "--project-root",
+            &project.to_string_lossy(),
+            "install",
+            "override-root-skill",
+            "--file",
+            &package_dir
+                .join("override-root-skill.skill")
+                .to_string_lossy(),
+        ])
+        .assert()
+        .success();
+
+    assert!(project
+        .join(".opencode/skills/override-root-skill/SKILL.md")
+        .exists());
+}
+
+#[test]
+fn test_config_help() {
+    sb().args(["config", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("layered"));
+}
+
+#[test]
+fn test_config_show_merges_explicit_and_env() {
+    let temp = TempDir::new().unwrap();
+    let config_path = common::testdata_dir().join("skills_with_repo.json");
+
+    sb().current_dir(temp.path())
+        .env("SB_REGION", "ap-southeast-2")
+        .args(["--config", &config_path.to_string_lossy(), "config", "show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"bucket_name\": \"test-skills-bucket\"",
+        ))
+        .stdout(predicate::str::contains("\"region\": \"ap-southeast-2\""));
+}
+
+#[test]
+fn test_config_show_origin() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("skills.json"),
+        r#"{"skills": [], "repository": {"bucket_name": "project-bucket"}}"#,
+    )
+    .unwrap();
+
+    sb().current_dir(temp.path())
+        .env("SB_ENDPOINT", "https://s3.example.com")
+        .args(["--project-root", &temp.path().to_string_lossy()])
+        .args(["config", "show", "--origin"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("repository.bucket_name"))
+        .stderr(predicate::str::contains("project ("))
+        .stderr(predicate::str::contains("env (SB_ENDPOINT)"))
+        .stderr(predicate::str::contains("default (built-in)"));
+}
+
+#[test]
+fn test_config_validate_reports_line_numbers() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("skills.json");
+    fs::write(
+        &path,
+        "{\n  \"skills\": [],\n  \"repository\": {\n    \"bucketName\": \"b\"\n  }\n}\n",
+    )
+    .unwrap();
+
+    sb().args(["config", "validate", &path.to_string_lossy()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "line 4: repository: unknown key 'bucketName' (did you mean 'bucket_name'?)",
+        ));
+
+    sb().args(["config", "validate"])
+        .arg(common::testdata_dir().join("skills_with_repo.json"))
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_config_edit_commands_preserve_formatting() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("skills.json");
+    let original = "{\n    \"repository\": {\n        \"bucket_name\": \"b\"\n    }\n}\n";
+    fs::write(&path, original).unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+
+    sb().args(["--project-root", &root, "config", "add-skill", "svelte"])
+        .args(["--url", "https://svelte.dev/llms.txt"])
+        .assert()
+        .success();
+    sb().args(["--project-root", &root, "config", "set"])
+        .args(["skills[svelte].description", "Svelte docs"])
+        .assert()
+        .success();
+
+    let edited = fs::read_to_string(&path).unwrap();
+    assert!(
+        edited.starts_with("{\n    \"repository\": {\n        \"bucket_name\": \"b\"\n    },\n")
+    );
+    assert!(edited.contains("\"description\": \"Svelte docs\""));
+
+    sb().args(["--project-root", &root, "config", "set"])
+        .args(["repository.bucketName", "typo"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("did you mean 'bucket_name'"));
+
+    sb().args(["--project-root", &root, "config", "remove-skill", "svelte"])
+        .assert()
+        .success();
+    let config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(config["skills"], serde_json::json!([]));
+}
+
+#[test]
+fn test_repo_unknown_repo_name_suggests() {
+    let temp = TempDir::new().unwrap();
+    let config_path = temp.path().join("skills.json");
+    fs::write(
+        &config_path,
+        r#"{"repositories": [{"name": "community", "bucket_name": "community-skills"}]}"#,
+    )
+    .unwrap();
+
+    sb().args(["--config", &config_path.to_string_lossy()])
+        .args(["repo", "list", "--repo", "comunity"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("did you mean 'community'"));
+}
+
+#[test]
+fn test_repo_upload_refuses_read_only_repo() {
+    let temp = TempDir::new().unwrap();
+    let config_path = temp.path().join("skills.json");
+    fs::write(
+        &config_path,
+        r#"{"repositories": [{"name": "community", "bucket_name": "c", "read_only": true}]}"#,
+    )
+    .unwrap();
+    let skill_file = temp.path().join("my-skill.skill");
+    fs::write(&skill_file, "zip").unwrap();
+
+    sb().args(["--config", &config_path.to_string_lossy()])
+        .args(["repo", "--repo", "community", "upload", "my-skill", "1.0.0"])
+        .args(["--file", &skill_file.to_string_lossy()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("read-only"));
+}
+
+#[test]
+fn test_local_publish_then_install_offline() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().join("home");
+    let skill_dir = temp.path().join("test-skill");
+    common::create_valid_skill(&skill_dir);
+
+    let sb_at = || {
+        let mut cmd = sb();
+        cmd.env("HOME", &home).current_dir(temp.path());
+        cmd
+    };
+
+    sb_at()
+        .args(["package", &skill_dir.to_string_lossy()])
+        .assert()
+        .success();
+
+    // Defaults to dist/<skill>.skill
+    sb_at()
+        .args(["local", "publish", "test-skill", "1.0.0"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Published test-skill v1.0.0"));
+
+    let local = home.join(".skill-builder/local");
+    assert!(local.join("skills_index.json").exists());
+    assert!(local
+        .join("skills/test-skill/1.0.0/test-skill.skill")
+        .exists());
+
+    sb_at()
+        .args(["local", "list"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("v1.0.0"));
+
+    let install_dir = temp.path().join("installed");
+    sb_at()
+        .args(["install", "test-skill", "--local", "--install-dir"])
+        .arg(&install_dir)
+        .assert()
+        .success();
+    assert!(install_dir.join("test-skill/SKILL.md").exists());
+}
+
+#[test]
+fn test_local_publish_missing_file() {
+    let temp = TempDir::new().unwrap();
+    sb().env("HOME", temp.path())
+        .current_dir(temp.path())
+        .args(["local", "publish", "missing-skill", "1.0.0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Skill file not found: dist/missing-skill.skill",
+        ));
+}
+
+#[test]
+fn test_local_prune_keeps_published_skills() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().join("home");
+    let skill_dir = temp.path().join("test-skill");
+    common::create_valid_skill(&skill_dir);
+
+    let sb_at = || {
+        let mut cmd = sb();
+        cmd.env("HOME", &home).current_dir(temp.path());
+        cmd
+    };
+    sb_at()
+        .args(["package", &skill_dir.to_string_lossy()])
+        .assert()
+        .success();
+    sb_at()
+        .args(["local", "publish", "test-skill", "1.0.0"])
+        .assert()
+        .success();
+
+    let local = home.join(".skill-builder/local");
+    for version in ["0.9.0", "1.0.0"] {
+        let cached = local.join(format!(
+            "cache/skills/test-skill/{}/test-skill.skill",
+            version
+        ));
+        fs::create_dir_all(cached.parent().unwrap()).unwrap();
+        fs::write(&cached, "cached").unwrap();
+    }
+
+    sb_at()
+        .args(["local", "prune"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("No cache limits configured"))
+        .stderr(predicate::str::contains(
+            "0 entries (0 B) evicted, 2 entries",
+        ));
+
+    sb_at()
+        .args(["local", "prune", "--max-size-mb", "0", "--dry-run"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Would evict test-skill v0.9.0"))
+        .stderr(predicate::str::contains("2 entries (12 B) to evict"));
+    assert!(local
+        .join("cache/skills/test-skill/0.9.0/test-skill.skill")
+        .exists());
+
+    sb_at()
+        .args(["local", "prune", "--max-size-mb", "0"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("2 entries (12 B) evicted"));
+    assert!(!local.join("cache/skills/test-skill").exists());
+    assert!(local
+        .join("skills/test-skill/1.0.0/test-skill.skill")
+        .exists());
+}
+
+#[test]
+fn test_offline_mode_refuses_network_sources() {
+    let temp = TempDir::new().unwrap();
+    let config = temp.path().join("skills.json");
+    fs::write(
+        &config,
+        r#"{"repository": {"name": "company", "bucket_name": "company-skills"}}"#,
+    )
+    .unwrap();
+    let sb_at = || {
+        let mut cmd = sb();
+        cmd.env("HOME", temp.path())
+            .current_dir(temp.path())
+            .args(["--config", &config.to_string_lossy()]);
+        cmd
+    };
+
+    sb_at()
+        .args([
+            "--offline",
+            "download",
+            "--url",
+            "https://example.com/llms.txt",
+        ])
+        .args(["--name", "example"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "skipped network sources: llms.txt documentation downloads",
+        ));
+
+    sb_at()
+        .env("SB_OFFLINE", "1")
+        .args(["repo", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "skipped network sources: S3 bucket 'company-skills'",
+        ));
+
+    sb_at()
+        .env("SB_OFFLINE", "true")
+        .args(["install", "some-skill", "--install-dir"])
+        .arg(temp.path().join("installed"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Skill 'some-skill' not found in the local repository or cache",
+        ))
+        .stderr(predicate::str::contains(
+            "repository 'company', GitHub releases",
+        ));
+}
+
+#[test]
+fn test_info_shows_versions_changelog_and_files() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().join("home");
+    common::create_valid_skill(&temp.path().join("test-skill"));
+    let sb_at = || {
+        let mut cmd = sb();
+        cmd.env("HOME", &home).current_dir(temp.path());
+        cmd
+    };
+    sb_at()
+        .args(["package", "test-skill", "--skills-dir", "."])
+        .assert()
+        .success();
+
+    // Each upload carries the whole history, as the repo's own CHANGELOG.md does
+    let mut history = String::from("# Changelog\n");
+    for (version, note) in [("1.0.0", "First"), ("1.1.0", "Second"), ("2.0.0", "Third")] {
+        history = history.replacen(
+            "# Changelog\n",
+            &format!(
+                "# Changelog\n\n## [{}] - 2025-01-01\n\n- {} release\n",
+                version, note
+            ),
+            1,
+        );
+        fs::write(temp.path().join("CHANGELOG.md"), &history).unwrap();
+        sb_at()
+            .args(["local", "publish", "test-skill", version])
+            .args(["--changelog", "CHANGELOG.md"])
+            .assert()
+            .success();
+    }
+
+    sb_at()
+        .args(["info", "test-skill"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Repository: local"))
+        .stderr(predicate::str::contains("1.0.0"))
+        .stderr(predicate::str::contains("Changelog for v2.0.0:"))
+        .stderr(predicate::str::contains("- Third release"))
+        .stderr(predicate::str::contains("- Second release").not())
+        .stderr(predicate::str::contains("test-skill/SKILL.md"));
+
+    sb_at()
+        .args(["info", "test-skill", "--since", "1.0.0"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Changes since v1.0.0:"))
+        .stderr(predicate::str::contains("- Third release"))
+        .stderr(predicate::str::contains("- Second release"))
+        .stderr(predicate::str::contains("- First release").not());
+
+    sb_at()
+        .args(["info", "test-skill", "--version", "3.0.0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Version '3.0.0' not found for skill 'test-skill'",
+        ));
+}
+
+#[test]
+fn test_changelog_generated_from_published_package() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().join("home");
+    let skill_dir = temp.path().join("test-skill");
+    common::create_valid_skill(&skill_dir);
+    fs::write(skill_dir.join("references/button.md"), "# Button").unwrap();
+    let sb_at = || {
+        let mut cmd = sb();
+        cmd.env("HOME", &home).current_dir(temp.path());
+        cmd
+    };
+    let publish = |version: &str| {
+        sb_at()
+            .args(["package", "test-skill", "--skills-dir", "."])
+            .assert()
+            .success();
+        sb_at()
+            .args(["local", "publish", "test-skill", version])
+            .args(["--changelog", "auto"])
+            .assert()
+            .success();
+    };
+    publish("1.0.0");
+
+    sb_at()
+        .args(["changelog", "test-skill", "--skills-dir", "."])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr(predicate::str::contains("No changes since v1.0.0."));
+
+    fs::write(skill_dir.join("references/button.md"), "# Button v2").unwrap();
+    fs::write(skill_dir.join("references/kbd.md"), "# Kbd").unwrap();
+    sb_at()
+        .args(["changelog", "test-skill", "--skills-dir", "."])
+        .args(["--version", "1.1.0"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("## [1.1.0] - "))
+        .stdout(predicate::str::contains(
+            "### Added\n\n- New reference: `kbd.md`\n",
+        ))
+        .stdout(predicate::str::contains(
+            "### Changed\n\n- Updated reference: `button.md`\n",
+        ));
+
+    publish("1.1.0");
+    let changelog =
+        fs::read_to_string(home.join(".skill-builder/local/skills/test-skill/1.1.0/CHANGELOG.md"))
+            .unwrap();
+    let new = changelog.find("## [1.1.0]").unwrap();
+    let first = changelog.find("## [1.0.0]").unwrap();
+    assert!(new < first, "newest section first:\n{}", changelog);
+    assert!(changelog.contains("- Initial release with 2 reference docs"));
+}
+
+#[test]
+fn test_release_bumps_packages_and_refuses_overwrites() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().join("home");
+    let skill_dir = temp.path().join("skills/test-skill");
+    common::create_valid_skill(&skill_dir);
+    let sb_at = || {
+        let mut cmd = sb();
+        cmd.env("HOME", &home).current_dir(temp.path());
+        cmd
+    };
+    let local = home.join(".skill-builder/local");
+
+    sb_at()
+        .args([
+            "release",
+            "test-skill",
+            "--bump",
+            "minor",
+            "--repo",
+            "local",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Bumping test-skill (unreleased) -> 0.1.0 (minor)",
+        ))
+        .stderr(predicate::str::contains("Released: test-skill v0.1.0"));
+    assert!(fs::read_to_string(skill_dir.join("SKILL.md"))
+        .unwrap()
+        .contains("\nversion: 0.1.0\n"));
+    assert!(temp.path().join("dist/test-skill.skill").exists());
+
+    sb_at()
+        .args([
+            "release",
+            "test-skill",
+            "--bump",
+            "patch",
+            "--repo",
+            "local",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("0.1.0 -> 0.1.1 (patch)"));
+    assert!(local
+        .join("skills/test-skill/0.1.1/test-skill.skill")
+        .exists());
+    assert!(fs::read_to_string(skill_dir.join("SKILL.md"))
+        .unwrap()
+        .contains("\nversion: 0.1.1\n"));
+
+    sb_at()
+        .args([
+            "release",
+            "test-skill",
+            "--version",
+            "0.1.0",
+            "--repo",
+            "local",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "test-skill v0.1.0 is already published",
+        ));
+    // Refused before SKILL.md was touched
+    assert!(fs::read_to_string(skill_dir.join("SKILL.md"))
+        .unwrap()
+        .contains("\nversion: 0.1.1\n"));
+
+    sb_at()
+        .args([
+            "release",
+            "test-skill",
+            "--version",
+            "0.1.0",
+            "--repo",
+            "local",
+        ])
+        .arg("--force")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Overwriting published test-skill v0.1.0",
+        ));
+
+    sb_at()
+        .args(["local", "publish", "test-skill", "0.1.1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("use --force to overwrite it"));
+}
+
+#[test]
+fn test_failed_release_restores_skill_md() {
+    let temp = TempDir::new().unwrap();
+    let skill_dir = temp.path().join("skills/test-skill");
+    common::create_valid_skill(&skill_dir);
+    let original = fs::read_to_string(skill_dir.join("SKILL.md")).unwrap();
+    // Packaging fails: the output directory is a file
+    fs::write(temp.path().join("dist"), "not a directory").unwrap();
+
+    sb().env("HOME", temp.path().join("home"))
+        .current_dir(temp.path())
+        .args([
+            "release",
+            "test-skill",
+            "--version",
+            "1.0.0",
+            "--repo",
+            "local",
+        ])
+        .assert()
+        .failure();
+    assert_eq!(
+        fs::read_to_string(skill_dir.join("SKILL.md")).unwrap(),
+        original
+    );
+}
+
+#[test]
+fn test_outdated_reports_updates_and_deprecations() {
+    use skill_builder::local_storage::LocalStorageClient;
+    use skill_builder::output::Output;
+    use skill_builder::repository::{Repository, UploadParams};
+
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().join("home");
+    common::create_valid_skill(&temp.path().join("skills/test-skill"));
+    let sb_at = || {
+        let mut cmd = sb();
+        cmd.env("HOME", &home).current_dir(temp.path());
+        cmd
+    };
+
+    let release = |args: &[&str]| {
+        sb_at()
+            .args(["release", "test-skill", "--repo", "local"])
+            .args(args)
+            .assert()
+            .success();
+    };
+    release(&["--version", "1.0.0"]);
+    sb_at()
+        .args([
+            "install",
+            "test-skill",
+            "--local",
+            "--install-dir",
+            "installed",
+        ])
+        .assert()
+        .success();
+    release(&["--bump", "minor"]);
+
+    sb_at()
+        .args(["outdated", "--install-dir", "installed"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("v1.1.0 available"));
+
+    let repo =
+        Repository::new(LocalStorageClient::new(&home.join(".skill-builder/local")).unwrap());
+    repo.upload(
+        &UploadParams {
+            name: "new-skill",
+            version: "2.0.0",
+            description: "Replacement",
+            llms_txt_url: "",
+            skill_file: &temp.path().join("dist/test-skill.skill"),
+            changelog: None,
+            source_dir: None,
+        },
+        &Output::new(true),
+    )
+    .unwrap();
+    repo.deprecate("test-skill", Some("renamed"), Some("new-skill"))
+        .unwrap();
+
+    sb_at()
+        .args(["outdated", "--install-dir", "installed"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "test-skill is deprecated: renamed (replaced by new-skill)",
+        ))
+        .stderr(predicate::str::contains("install new-skill v2.0.0"));
+
+    sb_at()
+        .args(["install", "test-skill", "--local", "--install-dir", "other"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("test-skill is deprecated"));
+    sb_at()
+        .args(["install", "test-skill", "--local", "--install-dir", "other"])
+        .arg("--follow-replaced")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "test-skill is replaced by new-skill",
+        ))
+        .stderr(predicate::str::contains("deprecated").not());
+}
@@ -58,3 +58,15 @@ This skill has no YAML frontmatter.
     )
     .unwrap();
 }
+
+/// Run git in `dir` with a fixed committer, returning trimmed stdout.
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let out = std::process::Command::new("git")
+        .current_dir(dir)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "git {:?} failed", args);
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
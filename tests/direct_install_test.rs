@@ -0,0 +1,157 @@
+//! Integration tests for `sb install` with a package URL or a git source.
+
+mod common;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use sha2::{Digest, Sha256};
+use skill_builder::lockfile::{Lockfile, LOCKFILE};
+use skill_builder::package::package_skill;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let out = std::process::Command::new("git")
+        .current_dir(dir)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "git {:?} failed", args);
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+fn sb(dir: &Path) -> Command {
+    #[allow(deprecated)]
+    let mut cmd = Command::cargo_bin("sb").unwrap();
+    cmd.env("HOME", dir).current_dir(dir);
+    cmd
+}
+
+/// A git repository with a valid skill at `skills/test-skill`, tagged
+/// `v1.0.0`, followed by a commit that breaks it. Returns the tagged commit.
+fn skills_repo(dir: &Path) -> String {
+    fs::create_dir_all(dir).unwrap();
+    git(dir, &["init", "--quiet"]);
+    common::create_valid_skill(&dir.join("skills/test-skill"));
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "--quiet", "-m", "add test-skill"]);
+    git(dir, &["tag", "v1.0.0"]);
+    let tagged = git(dir, &["rev-parse", "HEAD"]);
+
+    fs::write(dir.join("skills/test-skill/SKILL.md"), "# No frontmatter").unwrap();
+    git(dir, &["commit", "--quiet", "-am", "break test-skill"]);
+    tagged
+}
+
+#[test]
+fn test_install_from_git_ref_records_lockfile() {
+    let tmp = TempDir::new().unwrap();
+    let repo = tmp.path().join("upstream");
+    let tagged = skills_repo(&repo);
+    let remote = format!("git+file://{}", repo.display());
+
+    let source = format!("{}#v1.0.0:skills/test-skill", remote);
+    sb(tmp.path())
+        .args(["install", &source, "--install-dir", "out"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Installed"));
+    assert!(tmp.path().join("out/test-skill/SKILL.md").exists());
+    assert!(tmp.path().join("out/test-skill/references").exists());
+
+    let lockfile = Lockfile::load(&tmp.path().join(LOCKFILE)).unwrap();
+    let locked = &lockfile.skills["test-skill"];
+    assert_eq!(locked.source, source);
+    assert_eq!(locked.commit.as_deref(), Some(tagged.as_str()));
+
+    // A commit that isn't a branch or tag tip is found too
+    fs::remove_dir_all(tmp.path().join("out")).unwrap();
+    sb(tmp.path())
+        .args([
+            "install",
+            &format!("{}#{}:skills/test-skill", remote, tagged),
+        ])
+        .args(["--install-dir", "out"])
+        .assert()
+        .success();
+    assert!(tmp.path().join("out/test-skill/SKILL.md").exists());
+
+    // The default branch has the broken skill, which is validated first
+    sb(tmp.path())
+        .args(["install", &format!("{}#:skills/test-skill", remote)])
+        .args(["--install-dir", "broken"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Skill validation failed"));
+    assert!(!tmp.path().join("broken").exists());
+
+    sb(tmp.path())
+        .args(["install", &format!("{}#v9.9.9:skills/test-skill", remote)])
+        .args(["--install-dir", "out"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No ref 'v9.9.9'"));
+}
+
+#[tokio::test]
+async fn test_install_from_url_records_lockfile() {
+    let server = MockServer::start().await;
+    let package = tokio::task::spawn_blocking(|| {
+        let tmp = TempDir::new().unwrap();
+        let skill_dir = tmp.path().join("test-skill");
+        common::create_valid_skill(&skill_dir);
+        let package = package_skill(&skill_dir, tmp.path().join("dist")).unwrap();
+        fs::read(package.output_path).unwrap()
+    })
+    .await
+    .unwrap();
+    Mock::given(method("GET"))
+        .and(path("/files/test-skill.skill"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(package.clone()))
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/files/test-skill.skill", server.uri());
+    tokio::task::spawn_blocking(move || {
+        let tmp = TempDir::new().unwrap();
+        sb(tmp.path())
+            .args([
+                "install",
+                &url,
+                "--version",
+                "1.0.0",
+                "--install-dir",
+                "out",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "don't apply to a URL or git source",
+            ));
+
+        sb(tmp.path())
+            .args(["install", &url, "--install-dir", "out"])
+            .assert()
+            .success();
+        assert!(tmp.path().join("out/test-skill/SKILL.md").exists());
+
+        let lockfile = Lockfile::load(&tmp.path().join(LOCKFILE)).unwrap();
+        let locked = &lockfile.skills["test-skill"];
+        assert_eq!(locked.source, url);
+        assert_eq!(locked.commit, None);
+        assert_eq!(locked.sha256, hex::encode(Sha256::digest(&package)));
+
+        sb(tmp.path())
+            .args(["install", &format!("{}/missing.skill", server.uri())])
+            .args(["--install-dir", "out"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("HTTP 404"));
+    })
+    .await
+    .unwrap();
+}
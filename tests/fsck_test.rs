@@ -0,0 +1,268 @@
+//! Integration tests for repository consistency checks and index rebuilds.
+
+mod common;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use skill_builder::fsck::Issue;
+use skill_builder::index::INDEX_KEY;
+use skill_builder::local_storage::LocalStorageClient;
+use skill_builder::output::Output;
+use skill_builder::package::package_skill;
+use skill_builder::repository::{Repository, UploadParams};
+use skill_builder::storage::{ObjectMetadata, PutCondition, StorageOperations};
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const PACKAGE_KEY: &str = "skills/test-skill/1.0.0/test-skill.skill";
+
+fn build_package(dir: &Path) -> PathBuf {
+    let skill_dir = dir.join("test-skill");
+    common::create_valid_skill(&skill_dir);
+    package_skill(&skill_dir, dir.join("dist"))
+        .unwrap()
+        .output_path
+}
+
+/// A local repository holding test-skill 1.0.0 and 1.1.0.
+fn setup(dir: &Path) -> (Repository<LocalStorageClient>, LocalStorageClient) {
+    let package = build_package(dir);
+    let store = dir.join("store");
+    let repo = Repository::new(LocalStorageClient::new(&store).unwrap());
+    for version in ["1.0.0", "1.1.0"] {
+        repo.upload(
+            &UploadParams {
+                name: "test-skill",
+                version,
+                description: "A test skill",
+                llms_txt_url: "https://example.com/llms.txt",
+                skill_file: &package,
+                changelog: None,
+                source_dir: None,
+            },
+            &Output::new(true),
+        )
+        .unwrap();
+    }
+    (repo, LocalStorageClient::with_dir(&store))
+}
+
+#[test]
+fn test_fsck_clean_repository() {
+    let tmp = TempDir::new().unwrap();
+    let (repo, _) = setup(tmp.path());
+
+    let report = repo.fsck().unwrap();
+    assert!(report.is_clean(), "{:?}", report.issues);
+    assert_eq!(report.packages_checked, 2);
+}
+
+#[test]
+fn test_fsck_reports_each_kind_of_problem() {
+    let tmp = TempDir::new().unwrap();
+    let (repo, files) = setup(tmp.path());
+
+    files
+        .delete_object("skills/test-skill/1.1.0/test-skill.skill")
+        .unwrap();
+    files
+        .put_object("source/other/1.0.0/other-source.zip", b"zip")
+        .unwrap();
+    files.put_object(PACKAGE_KEY, b"not a zip").unwrap();
+
+    let issues = repo.fsck().unwrap().issues;
+    assert!(issues.contains(&Issue::MissingObject {
+        skill: "test-skill".to_string(),
+        version: "1.1.0".to_string(),
+        key: "skills/test-skill/1.1.0/test-skill.skill".to_string(),
+    }));
+    assert!(issues.contains(&Issue::OrphanObject {
+        key: "source/other/1.0.0/other-source.zip".to_string()
+    }));
+    assert!(issues
+        .iter()
+        .any(|i| matches!(i, Issue::ChecksumMismatch { key, .. } if key == PACKAGE_KEY)));
+    assert!(issues
+        .iter()
+        .any(|i| matches!(i, Issue::UnreadablePackage { key, .. } if key == PACKAGE_KEY)));
+}
+
+#[test]
+fn test_reindex_rebuilds_lost_index() {
+    let tmp = TempDir::new().unwrap();
+    let (repo, files) = setup(tmp.path());
+    files.delete_object(INDEX_KEY).unwrap();
+
+    assert_eq!(repo.fsck().unwrap().issues, vec![Issue::MissingIndex]);
+
+    let rebuild = repo.reindex().unwrap();
+    assert!(rebuild.skipped.is_empty());
+    let entry = rebuild.index.find_skill("test-skill").unwrap();
+    assert_eq!(entry.versions.len(), 2);
+    assert_eq!(entry.checksums.len(), 2);
+    assert!(entry
+        .description
+        .starts_with("A test skill for integration testing"));
+
+    assert!(repo.fsck().unwrap().is_clean());
+    assert_eq!(repo.list(None).unwrap(), rebuild.index);
+}
+
+#[test]
+fn test_reindex_keeps_fields_missing_from_manifests() {
+    let tmp = TempDir::new().unwrap();
+    let (repo, files) = setup(tmp.path());
+    files
+        .delete_object("skills/test-skill/1.1.0/test-skill.skill")
+        .unwrap();
+
+    let rebuild = repo.reindex().unwrap();
+    let entry = rebuild.index.find_skill("test-skill").unwrap();
+    assert_eq!(entry.llms_txt_url, "https://example.com/llms.txt");
+    assert_eq!(entry.versions.keys().collect::<Vec<_>>(), vec!["1.0.0"]);
+    assert!(repo.fsck().unwrap().is_clean());
+}
+
+/// Storage that runs a hook right after its first listing of packages, to
+/// publish while a reindex is between listing and writing the index.
+struct PublishAfterListing {
+    inner: LocalStorageClient,
+    hook: RefCell<Option<Box<dyn FnOnce()>>>,
+}
+
+impl StorageOperations for PublishAfterListing {
+    fn put_object(&self, key: &str, data: &[u8]) -> anyhow::Result<()> {
+        self.inner.put_object(key, data)
+    }
+
+    fn get_object(&self, key: &str) -> anyhow::Result<Vec<u8>> {
+        self.inner.get_object(key)
+    }
+
+    fn delete_object(&self, key: &str) -> anyhow::Result<()> {
+        self.inner.delete_object(key)
+    }
+
+    fn list_objects(&self, prefix: &str) -> anyhow::Result<Vec<String>> {
+        let keys = self.inner.list_objects(prefix)?;
+        if let Some(hook) = self.hook.borrow_mut().take() {
+            hook();
+        }
+        Ok(keys)
+    }
+
+    fn object_exists(&self, key: &str) -> anyhow::Result<bool> {
+        self.inner.object_exists(key)
+    }
+
+    fn head_object(&self, key: &str) -> anyhow::Result<Option<ObjectMetadata>> {
+        self.inner.head_object(key)
+    }
+
+    fn get_object_with_metadata(&self, key: &str) -> anyhow::Result<(Vec<u8>, ObjectMetadata)> {
+        self.inner.get_object_with_metadata(key)
+    }
+
+    fn put_object_if(
+        &self,
+        key: &str,
+        data: &[u8],
+        condition: &PutCondition,
+    ) -> anyhow::Result<ObjectMetadata> {
+        self.inner.put_object_if(key, data, condition)
+    }
+}
+
+#[test]
+fn test_reindex_keeps_versions_published_while_it_runs() {
+    let tmp = TempDir::new().unwrap();
+    let (_, files) = setup(tmp.path());
+    let store = tmp.path().join("store");
+    let package = tmp.path().join("dist/test-skill.skill");
+
+    let publisher_store = store.clone();
+    let hook = move || {
+        Repository::new(LocalStorageClient::with_dir(&publisher_store))
+            .upload(
+                &UploadParams {
+                    name: "test-skill",
+                    version: "1.2.0",
+                    description: "A test skill",
+                    llms_txt_url: "https://example.com/llms.txt",
+                    skill_file: &package,
+                    changelog: None,
+                    source_dir: None,
+                },
+                &Output::new(true),
+            )
+            .unwrap();
+    };
+    let repo = Repository::new(PublishAfterListing {
+        inner: files,
+        hook: RefCell::new(Some(Box::new(hook))),
+    });
+
+    let rebuild = repo.reindex().unwrap();
+    let entry = rebuild.index.find_skill("test-skill").unwrap();
+    let mut versions: Vec<_> = entry.versions.keys().collect();
+    versions.sort();
+    assert_eq!(versions, vec!["1.0.0", "1.1.0", "1.2.0"]);
+    let stored = Repository::new(LocalStorageClient::with_dir(&store));
+    assert_eq!(stored.list(None).unwrap(), rebuild.index);
+}
+
+#[test]
+fn test_reindex_keeps_reporting_damaged_packages() {
+    let tmp = TempDir::new().unwrap();
+    let (repo, files) = setup(tmp.path());
+
+    // A valid package, but not the one that was published
+    let other = tmp.path().join("other");
+    let skill_dir = other.join("test-skill");
+    common::create_valid_skill(&skill_dir);
+    fs::write(skill_dir.join("references/extra.md"), "# Extra").unwrap();
+    let package = package_skill(&skill_dir, other.join("dist")).unwrap();
+    files
+        .put_object(PACKAGE_KEY, &fs::read(package.output_path).unwrap())
+        .unwrap();
+
+    repo.reindex().unwrap();
+    let issues = repo.fsck().unwrap().issues;
+    assert!(matches!(
+        issues.as_slice(),
+        [Issue::ChecksumMismatch { key, .. }] if key == PACKAGE_KEY
+    ));
+}
+
+#[test]
+fn test_local_fsck_cli() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path().join("home");
+    let (_, files) = setup(tmp.path());
+    let local = home.join(".skill-builder/local");
+    fs::create_dir_all(local.parent().unwrap()).unwrap();
+    fs::rename(files.root(), &local).unwrap();
+    fs::remove_file(local.join(INDEX_KEY)).unwrap();
+
+    let sb = || {
+        #[allow(deprecated)]
+        let mut cmd = Command::cargo_bin("sb").unwrap();
+        cmd.env("HOME", &home).current_dir(tmp.path());
+        cmd
+    };
+
+    sb().args(["local", "fsck"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("skills_index.json is missing"))
+        .stderr(predicate::str::contains("Run with --fix"));
+
+    sb().args(["local", "fsck", "--fix"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("1 skills, 2 versions"));
+
+    sb().args(["local", "fsck"]).assert().success();
+}
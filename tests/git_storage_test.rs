@@ -0,0 +1,213 @@
+//! Integration tests for the git repository backend, using a local bare repo.
+
+mod common;
+
+use assert_cmd::Command;
+use common::git;
+use skill_builder::git_storage::GitStorageClient;
+use skill_builder::index::update_index;
+use skill_builder::output::Output;
+use skill_builder::repository::{Repository, UploadParams};
+use skill_builder::storage::StorageOperations;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn test_output() -> Output {
+    Output::new(true)
+}
+
+/// Create an empty bare repository to act as the remote.
+fn bare_remote(dir: &Path) -> PathBuf {
+    let remote = dir.join("skills.git");
+    fs::create_dir_all(&remote).unwrap();
+    git(&remote, &["init", "--quiet", "--bare"]);
+    remote
+}
+
+fn create_test_skill_file(dir: &Path) -> PathBuf {
+    let skill_dir = dir.join("git-test-skill");
+    common::create_valid_skill(&skill_dir);
+
+    let dist = dir.join("dist");
+    skill_builder::package::package_skill(&skill_dir, &dist).unwrap();
+    dist.join("git-test-skill.skill")
+}
+
+fn upload(repo: &Repository<GitStorageClient>, version: &str, skill_file: &Path) {
+    repo.upload(
+        &UploadParams {
+            name: "git-test-skill",
+            version,
+            description: "desc",
+            llms_txt_url: "https://example.com/llms.txt",
+            skill_file,
+            changelog: None,
+            source_dir: None,
+        },
+        &test_output(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_upload_commits_to_branch() {
+    let tmp = TempDir::new().unwrap();
+    let remote = bare_remote(tmp.path());
+    let skill_file = create_test_skill_file(tmp.path());
+
+    let client = GitStorageClient::new(
+        &remote.to_string_lossy(),
+        "skills",
+        &tmp.path().join("work"),
+    )
+    .unwrap();
+    upload(&Repository::new(client), "1.2.0", &skill_file);
+
+    let log = git(&remote, &["log", "--format=%s", "skills"]);
+    assert_eq!(
+        log.lines().collect::<Vec<_>>(),
+        vec!["update skills index", "publish git-test-skill 1.2.0"]
+    );
+    let files = git(&remote, &["ls-tree", "-r", "--name-only", "skills"]);
+    assert!(files.contains("skills/git-test-skill/1.2.0/git-test-skill.skill"));
+    assert!(files.contains("skills_index.json"));
+}
+
+#[test]
+fn test_second_clone_sees_published_skills() {
+    let tmp = TempDir::new().unwrap();
+    let remote = bare_remote(tmp.path());
+    let remote_url = remote.to_string_lossy().to_string();
+    let skill_file = create_test_skill_file(tmp.path());
+
+    let publisher = GitStorageClient::new(&remote_url, "main", &tmp.path().join("a")).unwrap();
+    let publisher = Repository::new(publisher);
+    upload(&publisher, "1.0.0", &skill_file);
+
+    // A second writer starting from a stale clone still pushes cleanly
+    let other = GitStorageClient::new(&remote_url, "main", &tmp.path().join("b")).unwrap();
+    upload(&publisher, "1.1.0", &skill_file);
+    other.put_object("notes/readme.md", b"hello").unwrap();
+
+    let reader = GitStorageClient::new(&remote_url, "main", &tmp.path().join("c")).unwrap();
+    assert!(reader.object_exists("notes/readme.md").unwrap());
+    assert!(!reader
+        .list_objects("")
+        .unwrap()
+        .iter()
+        .any(|k| k.starts_with(".git")));
+
+    let reader = Repository::new(reader);
+    let index = reader.list(None).unwrap();
+    assert_eq!(index.skills[0].versions.len(), 2);
+
+    let install_dir = tmp.path().join("installed");
+    reader
+        .install(
+            "git-test-skill",
+            Some("1.0.0"),
+            &install_dir,
+            &test_output(),
+        )
+        .unwrap();
+    assert!(install_dir.join("git-test-skill/SKILL.md").exists());
+}
+
+#[test]
+fn test_delete_commits_removal() {
+    let tmp = TempDir::new().unwrap();
+    let remote = bare_remote(tmp.path());
+    let skill_file = create_test_skill_file(tmp.path());
+
+    let client =
+        GitStorageClient::new(&remote.to_string_lossy(), "main", &tmp.path().join("work")).unwrap();
+    let repo = Repository::new(client);
+    upload(&repo, "1.0.0", &skill_file);
+    repo.delete("git-test-skill", Some("1.0.0"), &test_output())
+        .unwrap();
+
+    let log = git(&remote, &["log", "--format=%s", "main"]);
+    assert!(log.contains("delete git-test-skill 1.0.0"));
+    let files = git(&remote, &["ls-tree", "-r", "--name-only", "main"]);
+    assert!(!files.contains("git-test-skill.skill"));
+}
+
+#[test]
+fn test_clones_racing_on_the_index_keep_every_entry() {
+    let tmp = TempDir::new().unwrap();
+    let remote = bare_remote(tmp.path());
+    let remote_url = remote.to_string_lossy().to_string();
+
+    let writers: Vec<_> = ["a", "b"]
+        .into_iter()
+        .map(|clone| {
+            let client =
+                GitStorageClient::new(&remote_url, "main", &tmp.path().join(clone)).unwrap();
+            std::thread::spawn(move || {
+                for i in 0..4 {
+                    let name = format!("skill-{}-{}", clone, i);
+                    update_index(&client, |index| {
+                        index.add_or_update_skill(
+                            &name,
+                            "desc",
+                            "https://example.com/llms.txt",
+                            "1.0.0",
+                            &format!("skills/{}/1.0.0/{}.skill", name, name),
+                        );
+                        Ok(())
+                    })
+                    .unwrap();
+                }
+                client
+            })
+        })
+        .collect();
+    let clients: Vec<_> = writers.into_iter().map(|w| w.join().unwrap()).collect();
+
+    // Neither clone is left mid-rebase, so both can keep writing
+    for (i, client) in clients.iter().enumerate() {
+        client
+            .put_object(&format!("notes/{}.md", i), b"still usable")
+            .unwrap();
+    }
+
+    let reader = GitStorageClient::new(&remote_url, "main", &tmp.path().join("c")).unwrap();
+    let index = Repository::new(reader).list(None).unwrap();
+    assert_eq!(index.skills.len(), 8);
+}
+
+#[test]
+fn test_cli_install_from_git_repository() {
+    let tmp = TempDir::new().unwrap();
+    let remote = bare_remote(tmp.path());
+    let skill_file = create_test_skill_file(tmp.path());
+
+    let client =
+        GitStorageClient::new(&remote.to_string_lossy(), "main", &tmp.path().join("work")).unwrap();
+    upload(&Repository::new(client), "1.0.0", &skill_file);
+
+    let config = tmp.path().join("skills.json");
+    fs::write(
+        &config,
+        format!(
+            r#"{{"repository": {{"name": "team", "url": "git+file://{}"}}}}"#,
+            remote.display()
+        ),
+    )
+    .unwrap();
+    let install_dir = tmp.path().join("installed");
+
+    #[allow(deprecated)]
+    Command::cargo_bin("sb")
+        .unwrap()
+        .env("HOME", tmp.path().join("home"))
+        .args(["--config", &config.to_string_lossy()])
+        .args(["install", "git-test-skill", "--remote", "--install-dir"])
+        .arg(&install_dir)
+        .assert()
+        .success();
+
+    assert!(install_dir.join("git-test-skill/SKILL.md").exists());
+    assert!(tmp.path().join("home/.skill-builder/git").exists());
+}
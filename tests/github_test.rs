@@ -0,0 +1,409 @@
+//! Integration tests for publishing to and installing from GitHub releases,
+//! against a stand-in for the GitHub API.
+
+mod common;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use skill_builder::github::{publish_release, GitHubClient, GitHubReleaseStorage, PublishParams};
+use skill_builder::output::Output;
+use skill_builder::package::package_skill;
+use skill_builder::repository::Repository;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use wiremock::matchers::{body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+/// Package a valid skill and sign it with a stand-in signature file.
+fn package(dir: &Path) -> PathBuf {
+    let skill_dir = dir.join("test-skill");
+    common::create_valid_skill(&skill_dir);
+    let package = package_skill(&skill_dir, dir.join("dist"))
+        .unwrap()
+        .output_path;
+    fs::write(dir.join("dist/test-skill.skill.sig"), "signature").unwrap();
+    package
+}
+
+fn release_json(server: &MockServer, assets: &str) -> String {
+    format!(
+        r#"{{"id": 1, "tag_name": "v1.0.0", "html_url": "https://github.com/acme/skills/releases/tag/v1.0.0",
+            "upload_url": "{}/uploads/repos/acme/skills/releases/1/assets{{?name,label}}", "assets": {}}}"#,
+        server.uri(),
+        assets
+    )
+}
+
+/// Answer every asset upload with the uploaded asset.
+async fn mount_uploads(server: &MockServer, expected: u64) {
+    Mock::given(method("POST"))
+        .and(path("/uploads/repos/acme/skills/releases/1/assets"))
+        .and(header("authorization", "Bearer secret"))
+        .respond_with(|request: &Request| {
+            let name = request
+                .url
+                .query_pairs()
+                .find(|(k, _)| k == "name")
+                .map(|(_, v)| v.to_string())
+                .unwrap_or_default();
+            ResponseTemplate::new(201).set_body_string(format!(
+                r#"{{"id": 9, "name": "{}", "browser_download_url": "https://example.com/{}"}}"#,
+                name, name
+            ))
+        })
+        .expect(expected)
+        .mount(server)
+        .await;
+}
+
+fn params<'a>(skill_file: &'a Path, signature: Option<&'a Path>, force: bool) -> PublishParams<'a> {
+    PublishParams {
+        repo: "acme/skills",
+        skill: "test-skill",
+        version: "1.0.0",
+        skill_file,
+        signature,
+        notes: Some("First release"),
+        target: None,
+        force,
+    }
+}
+
+#[tokio::test]
+async fn test_publish_creates_release_and_uploads_assets() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/repos/acme/skills/releases/tags/v1.0.0"))
+        .respond_with(ResponseTemplate::new(404).set_body_string(r#"{"message": "Not Found"}"#))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/repos/acme/skills/releases"))
+        .and(header("authorization", "Bearer secret"))
+        .and(body_string_contains(r#""tag_name":"v1.0.0""#))
+        .and(body_string_contains(r#""body":"First release""#))
+        .respond_with(ResponseTemplate::new(201).set_body_string(release_json(&server, "[]")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    mount_uploads(&server, 3).await;
+
+    let api_url = server.uri();
+    let package_data = tokio::task::spawn_blocking(move || {
+        let tmp = TempDir::new().unwrap();
+        let skill_file = package(tmp.path());
+        let signature = tmp.path().join("dist/test-skill.skill.sig");
+        let client = GitHubClient::new(&api_url, Some("secret".to_string())).unwrap();
+        let release = publish_release(
+            &client,
+            &params(&skill_file, Some(&signature), false),
+            &Output::new(true),
+        )
+        .unwrap();
+        assert_eq!(release.tag_name, "v1.0.0");
+        fs::read(&skill_file).unwrap()
+    })
+    .await
+    .unwrap();
+
+    let uploads: Vec<Request> = server
+        .received_requests()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|r| r.url.path().starts_with("/uploads/"))
+        .collect();
+    let uploaded = |name: &str| {
+        uploads
+            .iter()
+            .find(|r| r.url.query().unwrap_or_default() == format!("name={}", name))
+            .unwrap_or_else(|| panic!("{} was not uploaded", name))
+    };
+    assert_eq!(uploaded("test-skill.skill").body, package_data);
+    let checksum = String::from_utf8(uploaded("test-skill.skill.sha256").body.clone()).unwrap();
+    assert!(checksum.ends_with("  test-skill.skill\n"), "{}", checksum);
+    assert_eq!(checksum.split_whitespace().next().unwrap().len(), 64);
+    assert_eq!(uploaded("test-skill.skill.sig").body, b"signature");
+}
+
+#[tokio::test]
+async fn test_publish_replaces_assets_only_with_force() {
+    let server = MockServer::start().await;
+    let existing = r#"[
+        {"id": 7, "name": "test-skill.skill", "browser_download_url": "https://example.com/a"},
+        {"id": 8, "name": "other-skill.skill", "browser_download_url": "https://example.com/b"}
+    ]"#;
+    Mock::given(method("GET"))
+        .and(path("/repos/acme/skills/releases/tags/v1.0.0"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(release_json(&server, existing)))
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/repos/acme/skills/releases/1"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(release_json(&server, existing)))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/repos/acme/skills/releases/assets/7"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+    mount_uploads(&server, 2).await;
+
+    let api_url = server.uri();
+    tokio::task::spawn_blocking(move || {
+        let tmp = TempDir::new().unwrap();
+        let skill_file = package(tmp.path());
+        let client = GitHubClient::new(&api_url, Some("secret".to_string())).unwrap();
+        let out = Output::new(true);
+
+        let err = publish_release(&client, &params(&skill_file, None, false), &out).unwrap_err();
+        assert!(
+            err.to_string().contains("use --force to replace it"),
+            "{}",
+            err
+        );
+        publish_release(&client, &params(&skill_file, None, true), &out).unwrap();
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_publish_cli() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/repos/acme/skills/releases/tags/v1.0.0"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/repos/acme/skills/releases"))
+        .respond_with(ResponseTemplate::new(201).set_body_string(release_json(&server, "[]")))
+        .mount(&server)
+        .await;
+    // The signature next to the package is found without --signature
+    mount_uploads(&server, 3).await;
+
+    let api_url = server.uri();
+    tokio::task::spawn_blocking(move || {
+        let tmp = TempDir::new().unwrap();
+        package(tmp.path());
+        let sb = || {
+            #[allow(deprecated)]
+            let mut cmd = Command::cargo_bin("sb").unwrap();
+            cmd.env("HOME", tmp.path())
+                .env("SB_GITHUB_API_URL", &api_url)
+                .env_remove("GH_TOKEN")
+                .current_dir(tmp.path());
+            cmd
+        };
+        let publish = [
+            "github",
+            "publish",
+            "test-skill",
+            "1.0.0",
+            "--repo",
+            "acme/skills",
+        ];
+
+        sb().env_remove("GITHUB_TOKEN")
+            .args(publish)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("needs a token"));
+        sb().env("GITHUB_TOKEN", "secret")
+            .args(publish)
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Uploaded: test-skill.skill.sig"))
+            .stderr(predicate::str::contains(
+                "https://github.com/acme/skills/releases/tag/v1.0.0",
+            ));
+    })
+    .await
+    .unwrap();
+}
+
+/// Two releases of test-skill, whose packages are served by `server`, plus a
+/// draft that must be ignored.
+fn releases_json(server: &MockServer) -> String {
+    let release = |version: &str, id: u32, notes: &str| {
+        format!(
+            r#"{{"tag_name": "v{v}", "draft": false, "published_at": "2025-0{id}-01T00:00:00Z", "body": "{notes}",
+                "assets": [{{"id": {id}, "name": "test-skill.skill", "size": 1234,
+                             "url": "{uri}/api-assets/{id}",
+                             "browser_download_url": "{uri}/download/v{v}/test-skill.skill"}}]}}"#,
+            v = version,
+            id = id,
+            notes = notes,
+            uri = server.uri()
+        )
+    };
+    format!(
+        r#"[{}, {}, {{"tag_name": "v9.0.0", "draft": true, "assets": [{{"name": "test-skill.skill", "browser_download_url": "x"}}]}}]"#,
+        release("1.1.0", 2, "- Second release"),
+        release("1.0.0", 1, "- First release")
+    )
+}
+
+/// Serve the release listing and both versions' packages.
+async fn mount_releases(server: &MockServer, package: Vec<u8>) {
+    Mock::given(method("GET"))
+        .and(path("/repos/acme/skills/releases"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(releases_json(server)))
+        .mount(server)
+        .await;
+    for version in ["1.0.0", "1.1.0"] {
+        Mock::given(method("GET"))
+            .and(path(format!("/download/v{}/test-skill.skill", version)))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(package.clone()))
+            .mount(server)
+            .await;
+    }
+}
+
+fn package_bytes() -> Vec<u8> {
+    let tmp = TempDir::new().unwrap();
+    fs::read(package(tmp.path())).unwrap()
+}
+
+#[tokio::test]
+async fn test_install_and_info_from_github_releases() {
+    let server = MockServer::start().await;
+    let package = tokio::task::spawn_blocking(package_bytes).await.unwrap();
+    mount_releases(&server, package).await;
+
+    let api_url = server.uri();
+    tokio::task::spawn_blocking(move || {
+        let tmp = TempDir::new().unwrap();
+        let sb = || {
+            #[allow(deprecated)]
+            let mut cmd = Command::cargo_bin("sb").unwrap();
+            cmd.env("HOME", tmp.path())
+                .env("SB_GITHUB_API_URL", &api_url)
+                .env_remove("GITHUB_TOKEN")
+                .env_remove("GH_TOKEN")
+                .current_dir(tmp.path());
+            cmd
+        };
+        let install = ["install", "--github", "--repo", "acme/skills", "--install-dir", "out"];
+
+        // Ranges resolve against the released versions
+        sb().args(install)
+            .args(["test-skill", "--version", "~1.0"])
+            .assert()
+            .success();
+        assert!(tmp.path().join("out/test-skill/SKILL.md").exists());
+
+        sb().args(install)
+            .arg("test-skil")
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("did you mean 'test-skill'?"));
+        sb().args(install)
+            .args(["test-skill", "--version", "3.0.0"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("available: 1.1.0, 1.0.0"));
+
+        sb().args(["info", "test-skill", "--github", "acme/skills"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Repository: GitHub releases of acme/skills"))
+            .stderr(predicate::str::contains("2025-02-01"))
+            .stderr(predicate::str::contains("Changelog for v1.1.0:"))
+            .stderr(predicate::str::contains("- Second release"))
+            .stderr(predicate::str::contains("test-skill/SKILL.md"));
+        sb().args(["info", "tset-skill", "--github", "acme/skills"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Skill 'tset-skill' not found in GitHub releases of acme/skills (did you mean 'test-skill'?)",
+            ));
+    })
+    .await
+    .unwrap();
+
+    // Only the install asked for v1.0.0
+    let downloads: Vec<String> = server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| r.url.path().to_string())
+        .filter(|p| p.starts_with("/download/"))
+        .collect();
+    assert_eq!(
+        downloads,
+        vec![
+            "/download/v1.0.0/test-skill.skill",
+            "/download/v1.1.0/test-skill.skill"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_private_release_assets_download_with_token() {
+    let server = MockServer::start().await;
+    let package = tokio::task::spawn_blocking(package_bytes).await.unwrap();
+    Mock::given(method("GET"))
+        .and(path("/repos/acme/skills/releases"))
+        .and(header("authorization", "Bearer secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(releases_json(&server)))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api-assets/2"))
+        .and(header("authorization", "Bearer secret"))
+        .and(header("accept", "application/octet-stream"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(package.clone()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let api_url = server.uri();
+    tokio::task::spawn_blocking(move || {
+        let client = GitHubClient::new(&api_url, Some("secret".to_string())).unwrap();
+        let repo = Repository::new(GitHubReleaseStorage::new(client, "acme/skills"));
+        let index = repo.list(None).unwrap();
+        assert_eq!(index.latest_version("test-skill"), Some("1.1.0"));
+
+        let tmp = TempDir::new().unwrap();
+        let path = repo
+            .download("test-skill", None, Some(tmp.path()), &Output::new(true))
+            .unwrap();
+        assert_eq!(fs::read(path).unwrap(), package);
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_rate_limit_errors_say_when_to_retry() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/repos/acme/skills/releases"))
+        .respond_with(
+            ResponseTemplate::new(403)
+                .insert_header("x-ratelimit-remaining", "0")
+                .insert_header("x-ratelimit-reset", "1767225600")
+                .set_body_string(r#"{"message": "API rate limit exceeded"}"#),
+        )
+        .mount(&server)
+        .await;
+
+    let api_url = server.uri();
+    tokio::task::spawn_blocking(move || {
+        let client = GitHubClient::new(&api_url, None).unwrap();
+        let err = client.list_releases("acme/skills").unwrap_err().to_string();
+        assert!(err.contains("GitHub API rate limit exceeded"), "{}", err);
+        assert!(err.contains("resets at 00:00:00 UTC"), "{}", err);
+        assert!(err.contains("set GITHUB_TOKEN"), "{}", err);
+    })
+    .await
+    .unwrap();
+}
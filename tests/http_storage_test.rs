@@ -0,0 +1,237 @@
+//! Integration tests for the read-only HTTP repository backend.
+
+mod common;
+
+use skill_builder::config::{Config, RepositoryConfig};
+use skill_builder::http_storage::HttpStorageClient;
+use skill_builder::install_resolver::{resolve_and_install, InstallOptions, InstallSource};
+use skill_builder::output::Output;
+use skill_builder::package::package_skill;
+use skill_builder::storage::StorageOperations;
+use std::fs;
+use tempfile::TempDir;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const INDEX: &str = r#"{
+  "skills": [
+    {
+      "name": "test-skill",
+      "description": "A test skill",
+      "llms_txt_url": "https://example.com/llms.txt",
+      "versions": {"1.0.0": "skills/test-skill/1.0.0/test-skill.skill"}
+    }
+  ]
+}"#;
+
+async fn mount_index(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/repo/skills_index.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(INDEX))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_http_client_reads_objects() {
+    let server = MockServer::start().await;
+    mount_index(&server).await;
+    Mock::given(method("HEAD"))
+        .and(path("/repo/skills_index.json"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let base_url = format!("{}/repo/", server.uri());
+    let (data, exists, missing, missing_get, keys) = tokio::task::spawn_blocking(move || {
+        let client = HttpStorageClient::new(&base_url).unwrap();
+        (
+            client.get_object("skills_index.json").unwrap(),
+            client.object_exists("skills_index.json").unwrap(),
+            client.object_exists("missing.json").unwrap(),
+            client.get_object("missing.json"),
+            client.list_objects("skills/").unwrap(),
+        )
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(data, INDEX.as_bytes());
+    assert!(exists);
+    assert!(!missing);
+    assert!(missing_get
+        .unwrap_err()
+        .to_string()
+        .contains("Object not found"));
+    assert_eq!(keys, vec!["skills/test-skill/1.0.0/test-skill.skill"]);
+}
+
+#[tokio::test]
+async fn test_listing_fails_when_the_index_cant_be_read() {
+    let server = MockServer::start().await;
+    Mock::given(path("/broken/skills_index.json"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+
+    let uri = server.uri();
+    let (empty, broken) = tokio::task::spawn_blocking(move || {
+        let list = |dir: &str| {
+            HttpStorageClient::new(&format!("{}/{}", uri, dir))
+                .unwrap()
+                .list_objects("skills/")
+        };
+        (list("empty"), list("broken"))
+    })
+    .await
+    .unwrap();
+
+    // A missing index is an empty repository, an unavailable one isn't
+    assert!(empty.unwrap().is_empty());
+    assert!(broken.unwrap_err().to_string().contains("HTTP 503"));
+}
+
+#[tokio::test]
+async fn test_anonymous_install_from_http_repository() {
+    let server = MockServer::start().await;
+    mount_index(&server).await;
+
+    let temp = TempDir::new().unwrap();
+    let skill_dir = temp.path().join("test-skill");
+    common::create_valid_skill(&skill_dir);
+    let package = package_skill(&skill_dir, temp.path().join("dist")).unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/repo/skills/test-skill/1.0.0/test-skill.skill"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_bytes(fs::read(&package.output_path).unwrap()),
+        )
+        .mount(&server)
+        .await;
+
+    let config = Config {
+        repositories: vec![RepositoryConfig {
+            name: Some("static".to_string()),
+            url: Some(format!("{}/repo", server.uri())),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let install_dir = temp.path().join("installed");
+
+    let resolved = tokio::task::spawn_blocking({
+        let install_dir = install_dir.clone();
+        move || {
+            let options = InstallOptions {
+                skill_name: "test-skill",
+                version: None,
+                github_repo: None,
+                install_dir: &install_dir,
+                local_only: false,
+                remote_only: true,
+                github_only: false,
+                offline: false,
+                follow_replaced: false,
+            };
+            resolve_and_install(&config, &options, &Output::new(true))
+        }
+    })
+    .await
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(resolved.source, InstallSource::Remote("static".to_string()));
+    assert!(install_dir.join("test-skill/SKILL.md").exists());
+}
+
+#[test]
+fn test_http_repository_is_read_only() {
+    let config = Config::parse(
+        r#"{"repositories": [{"name": "static", "url": "https://skills.example.com"}]}"#,
+    )
+    .unwrap();
+
+    assert!(config.find_repository("static").unwrap().is_read_only());
+    assert!(config.write_repository(Some("static")).is_err());
+}
+
+#[tokio::test]
+async fn test_remote_cascade_skips_only_missing_skills() {
+    let server = MockServer::start().await;
+    mount_index(&server).await;
+    Mock::given(path("/broken/skills_index.json"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let temp = TempDir::new().unwrap();
+    let skill_dir = temp.path().join("test-skill");
+    common::create_valid_skill(&skill_dir);
+    let package = package_skill(&skill_dir, temp.path().join("dist")).unwrap();
+    Mock::given(method("GET"))
+        .and(path("/repo/skills/test-skill/1.0.0/test-skill.skill"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_bytes(fs::read(&package.output_path).unwrap()),
+        )
+        .mount(&server)
+        .await;
+
+    let repository = |name: &str, dir: &str, priority: i32| RepositoryConfig {
+        name: Some(name.to_string()),
+        url: Some(format!("{}/{}", server.uri(), dir)),
+        priority,
+        ..Default::default()
+    };
+    // Tried by priority: "empty" has no index, so "static" is next; "broken"
+    // comes last and is never asked
+    let cascade = Config {
+        repositories: vec![
+            repository("broken", "broken", 2),
+            repository("static", "repo", 1),
+            repository("empty", "empty", 0),
+        ],
+        ..Default::default()
+    };
+    // A repository that fails to answer stops the cascade
+    let failing = Config {
+        repositories: vec![
+            repository("broken", "broken", 0),
+            repository("static", "repo", 1),
+        ],
+        ..Default::default()
+    };
+
+    let install_dir = temp.path().join("installed");
+    let (resolved, err) = tokio::task::spawn_blocking(move || {
+        let options = InstallOptions {
+            skill_name: "test-skill",
+            version: None,
+            github_repo: None,
+            install_dir: &install_dir,
+            local_only: false,
+            remote_only: true,
+            github_only: false,
+            offline: false,
+            follow_replaced: false,
+        };
+        let output = Output::new(true);
+        (
+            resolve_and_install(&cascade, &options, &output),
+            resolve_and_install(&failing, &options, &output),
+        )
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(
+        resolved.unwrap().source,
+        InstallSource::Remote("static".to_string())
+    );
+    let err = format!("{:#}", err.unwrap_err());
+    assert!(
+        err.starts_with("Failed to install 'test-skill' from repository 'broken'"),
+        "{}",
+        err
+    );
+    assert!(err.contains("HTTP 500"), "{}", err);
+}
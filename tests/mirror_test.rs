@@ -0,0 +1,243 @@
+//! Integration tests for mirroring skills between repositories.
+
+mod common;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use skill_builder::git_storage::GitStorageClient;
+use skill_builder::index::INDEX_KEY;
+use skill_builder::local_storage::LocalStorageClient;
+use skill_builder::mirror::MirrorOptions;
+use skill_builder::output::Output;
+use skill_builder::package::package_skill;
+use skill_builder::repository::{Repository, UploadParams};
+use skill_builder::storage::StorageOperations;
+use skill_builder::version_range::VersionRange;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn build_package(dir: &Path) -> PathBuf {
+    let skill_dir = dir.join("test-skill");
+    common::create_valid_skill(&skill_dir);
+    package_skill(&skill_dir, dir.join("dist"))
+        .unwrap()
+        .output_path
+}
+
+fn upload<S: StorageOperations>(repo: &Repository<S>, package: &Path, version: &str) {
+    let changelog = package.with_file_name("CHANGELOG.md");
+    fs::write(&changelog, format!("## {}\n", version)).unwrap();
+    repo.upload(
+        &UploadParams {
+            name: "test-skill",
+            version,
+            description: "A test skill",
+            llms_txt_url: "https://example.com/llms.txt",
+            skill_file: package,
+            changelog: Some(&changelog),
+            source_dir: None,
+        },
+        &Output::new(true),
+    )
+    .unwrap();
+}
+
+/// A local repository holding test-skill 0.9.0, 1.0.0 and 1.1.0.
+fn source_repo(dir: &Path) -> Repository<LocalStorageClient> {
+    let package = build_package(dir);
+    let repo = Repository::new(LocalStorageClient::new(&dir.join("source")).unwrap());
+    for version in ["0.9.0", "1.0.0", "1.1.0"] {
+        upload(&repo, &package, version);
+    }
+    repo
+}
+
+fn versions<S: StorageOperations>(repo: &Repository<S>) -> Vec<String> {
+    let index = repo.list(Some("test-skill")).unwrap();
+    let mut versions: Vec<String> = index
+        .find_skill("test-skill")
+        .map(|e| e.versions.keys().cloned().collect())
+        .unwrap_or_default();
+    versions.sort();
+    versions
+}
+
+#[test]
+fn test_mirror_copies_versions_in_range() {
+    let tmp = TempDir::new().unwrap();
+    let source = source_repo(tmp.path());
+    let dest_dir = tmp.path().join("dest");
+    let dest = Repository::new(LocalStorageClient::new(&dest_dir).unwrap());
+    let range = VersionRange::parse("^1.0").unwrap();
+    let options = MirrorOptions {
+        versions: Some(&range),
+        ..Default::default()
+    };
+
+    let report = source
+        .mirror_to(&dest, &options, &Output::new(true))
+        .unwrap();
+    assert_eq!(report.versions.len(), 2);
+    assert_eq!(report.copied.len(), 4);
+    assert!(report.unchanged.is_empty());
+
+    assert_eq!(versions(&dest), vec!["1.0.0", "1.1.0"]);
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("skills/test-skill/1.1.0/CHANGELOG.md")).unwrap(),
+        "## 1.1.0\n"
+    );
+    let entry = dest.list(None).unwrap().skills.remove(0);
+    assert_eq!(entry.llms_txt_url, "https://example.com/llms.txt");
+    assert_eq!(entry.checksums.len(), 2);
+    assert!(dest.fsck().unwrap().is_clean());
+
+    // Everything is already there the second time round
+    let again = source
+        .mirror_to(&dest, &options, &Output::new(true))
+        .unwrap();
+    assert!(again.copied.is_empty());
+    assert_eq!(again.unchanged.len(), 4);
+}
+
+#[test]
+fn test_mirror_recopies_changed_objects_and_keeps_other_versions() {
+    let tmp = TempDir::new().unwrap();
+    let source = source_repo(tmp.path());
+    let dest_dir = tmp.path().join("dest");
+    let dest = Repository::new(LocalStorageClient::new(&dest_dir).unwrap());
+    upload(&dest, &build_package(tmp.path()), "2.0.0");
+    let files = LocalStorageClient::with_dir(&dest_dir);
+    files
+        .put_object("skills/test-skill/1.0.0/CHANGELOG.md", b"stale")
+        .unwrap();
+
+    let report = source
+        .mirror_to(
+            &dest,
+            &MirrorOptions {
+                skill: Some("test-skill"),
+                ..Default::default()
+            },
+            &Output::new(true),
+        )
+        .unwrap();
+    assert!(report
+        .copied
+        .contains(&"skills/test-skill/1.0.0/CHANGELOG.md".to_string()));
+    assert_eq!(
+        files
+            .get_object("skills/test-skill/1.0.0/CHANGELOG.md")
+            .unwrap(),
+        b"## 1.0.0\n"
+    );
+    assert_eq!(versions(&dest), vec!["0.9.0", "1.0.0", "1.1.0", "2.0.0"]);
+}
+
+#[test]
+fn test_mirror_dry_run_writes_nothing() {
+    let tmp = TempDir::new().unwrap();
+    let source = source_repo(tmp.path());
+    let dest_dir = tmp.path().join("dest");
+    let dest = Repository::new(LocalStorageClient::new(&dest_dir).unwrap());
+
+    let report = source
+        .mirror_to(
+            &dest,
+            &MirrorOptions {
+                dry_run: true,
+                ..Default::default()
+            },
+            &Output::new(true),
+        )
+        .unwrap();
+    assert_eq!(report.versions.len(), 3);
+    assert_eq!(report.copied.len(), 6);
+    assert!(!dest_dir.join(INDEX_KEY).exists());
+    assert!(!dest_dir.join("skills").exists());
+}
+
+#[test]
+fn test_mirror_unknown_skill_fails() {
+    let tmp = TempDir::new().unwrap();
+    let source = source_repo(tmp.path());
+    let dest = Repository::new(LocalStorageClient::new(&tmp.path().join("dest")).unwrap());
+
+    let err = source
+        .mirror_to(
+            &dest,
+            &MirrorOptions {
+                skill: Some("other-skill"),
+                ..Default::default()
+            },
+            &Output::new(true),
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("'other-skill' not found"));
+}
+
+#[test]
+fn test_cli_mirror_git_repository_to_local() {
+    let tmp = TempDir::new().unwrap();
+    let remote = tmp.path().join("skills.git");
+    fs::create_dir_all(&remote).unwrap();
+    let status = std::process::Command::new("git")
+        .current_dir(&remote)
+        .args(["init", "--quiet", "--bare"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let package = build_package(tmp.path());
+    let client =
+        GitStorageClient::new(&remote.to_string_lossy(), "main", &tmp.path().join("work")).unwrap();
+    let repo = Repository::new(client);
+    upload(&repo, &package, "1.0.0");
+    upload(&repo, &package, "2.0.0");
+
+    let config = tmp.path().join("skills.json");
+    fs::write(
+        &config,
+        format!(
+            r#"{{"repository": {{"name": "public", "url": "git+file://{}"}}}}"#,
+            remote.display()
+        ),
+    )
+    .unwrap();
+    let home = tmp.path().join("home");
+    let sb = || {
+        #[allow(deprecated)]
+        let mut cmd = Command::cargo_bin("sb").unwrap();
+        cmd.env("HOME", &home)
+            .current_dir(tmp.path())
+            .args(["--config", &config.to_string_lossy()]);
+        cmd
+    };
+    let mirror = ["repo", "mirror", "--from", "public", "--to", "local"];
+
+    sb().args(mirror)
+        .args(["--versions", ">=2.0", "--dry-run"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Would copy: skills/test-skill/2.0.0/test-skill.skill",
+        ))
+        .stderr(predicate::str::contains("1 versions, 2 objects to copy"));
+    let local = home.join(".skill-builder/local");
+    assert!(!local.join(INDEX_KEY).exists());
+
+    sb().args(mirror)
+        .args(["--versions", ">=2.0"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("1 versions, 2 objects copied"));
+    assert!(local
+        .join("skills/test-skill/2.0.0/test-skill.skill")
+        .exists());
+    assert!(!local.join("skills/test-skill/1.0.0").exists());
+
+    sb().args(["repo", "mirror", "--from", "local", "--to", "missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No repository named 'missing'"));
+}
@@ -0,0 +1,651 @@
+//! Integration tests for the OCI registry backend, against an in-memory
+//! stand-in for the registry:2 distribution API.
+
+mod common;
+
+use assert_cmd::Command;
+use sha2::{Digest, Sha256};
+use skill_builder::config::{Config, RepositoryConfig};
+use skill_builder::index::load_index;
+use skill_builder::index::{update_index, INDEX_KEY};
+use skill_builder::install_resolver::{resolve_and_install, InstallOptions, InstallSource};
+use skill_builder::oci::{OciStorageClient, SKILL_ARTIFACT_TYPE, SKILL_LAYER_MEDIA_TYPE};
+use skill_builder::output::Output;
+use skill_builder::package::package_skill;
+use skill_builder::repository::{Repository, UploadParams};
+use skill_builder::storage::{is_precondition_failed, PutCondition, StorageOperations};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+use wiremock::matchers::path_regex;
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+#[derive(Default)]
+struct RegistryState {
+    blobs: HashMap<String, Vec<u8>>,
+    /// Manifests by digest.
+    manifests: HashMap<String, Vec<u8>>,
+    /// Tags by repository, pointing at manifest digests.
+    tags: HashMap<String, BTreeMap<String, String>>,
+    uploads: usize,
+    /// Tags per page of a tag list, paging with `Link` headers when set.
+    tag_page_size: Option<usize>,
+}
+
+/// Stateful responder implementing the parts of the distribution API `sb` uses.
+#[derive(Clone, Default)]
+struct Registry(Arc<Mutex<RegistryState>>);
+
+fn sha256(data: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(data))
+}
+
+impl Registry {
+    fn manifest(&self, repository: &str, tag: &str) -> Option<serde_json::Value> {
+        let state = self.0.lock().unwrap();
+        let digest = state.tags.get(repository)?.get(tag)?;
+        serde_json::from_slice(&state.manifests[digest]).ok()
+    }
+
+    fn page_tags(&self, page_size: usize) {
+        self.0.lock().unwrap().tag_page_size = Some(page_size);
+    }
+
+    /// The stored skills index, as written rather than as `sb` presents it.
+    fn stored_index(&self, namespace: &str) -> serde_json::Value {
+        let manifest = self
+            .manifest(&format!("{}/skill-builder-index", namespace), "latest")
+            .unwrap();
+        let digest = manifest["layers"][0]["digest"].as_str().unwrap();
+        let state = self.0.lock().unwrap();
+        serde_json::from_slice(&state.blobs[digest]).unwrap()
+    }
+
+    fn untag(&self, repository: &str, tag: &str) {
+        let mut state = self.0.lock().unwrap();
+        state.tags.get_mut(repository).unwrap().remove(tag);
+    }
+
+    fn retag(&self, repository: &str, from: &str, to: &str) {
+        let mut state = self.0.lock().unwrap();
+        let tags = state.tags.get_mut(repository).unwrap();
+        let digest = tags[from].clone();
+        tags.insert(to.to_string(), digest);
+    }
+}
+
+impl Respond for Registry {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let mut state = self.0.lock().unwrap();
+        let path = request.url.path().trim_start_matches("/v2/").to_string();
+        let method = request.method.as_str();
+
+        if let Some(repo) = path.strip_suffix("/tags/list") {
+            let Some(tags) = state.tags.get(repo) else {
+                return ResponseTemplate::new(404);
+            };
+            let last = request
+                .url
+                .query_pairs()
+                .find(|(k, _)| k == "last")
+                .map(|(_, v)| v.to_string());
+            let rest: Vec<&String> = tags
+                .keys()
+                .filter(|t| last.as_ref().is_none_or(|last| *t > last))
+                .collect();
+            let page_size = state.tag_page_size.unwrap_or(rest.len());
+            let page = &rest[..page_size.min(rest.len())];
+            let response = ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "name": repo,
+                "tags": page,
+            }));
+            return match page.last() {
+                Some(last) if page.len() < rest.len() => response.insert_header(
+                    "Link",
+                    format!(
+                        "</v2/{}/tags/list?n={}&last={}>; rel=\"next\"",
+                        repo, page_size, last
+                    ),
+                ),
+                _ => response,
+            };
+        }
+
+        if let Some((repo, rest)) = path.split_once("/blobs/") {
+            if rest.starts_with("uploads/") {
+                if method == "POST" {
+                    state.uploads += 1;
+                    let location = format!("/v2/{}/blobs/uploads/{}", repo, state.uploads);
+                    return ResponseTemplate::new(202).insert_header("Location", location);
+                }
+                let digest = request
+                    .url
+                    .query_pairs()
+                    .find(|(k, _)| k == "digest")
+                    .map(|(_, v)| v.to_string())
+                    .unwrap_or_default();
+                if sha256(&request.body) != digest {
+                    return ResponseTemplate::new(400);
+                }
+                state.blobs.insert(digest, request.body.clone());
+                return ResponseTemplate::new(201);
+            }
+            return match state.blobs.get(rest) {
+                Some(data) if method == "GET" => {
+                    ResponseTemplate::new(200).set_body_bytes(data.clone())
+                }
+                Some(_) => ResponseTemplate::new(200),
+                None => ResponseTemplate::new(404),
+            };
+        }
+
+        if let Some((repo, reference)) = path.split_once("/manifests/") {
+            let repo = repo.to_string();
+            let digest = if reference.starts_with("sha256:") {
+                Some(reference.to_string())
+            } else {
+                state
+                    .tags
+                    .get(&repo)
+                    .and_then(|tags| tags.get(reference))
+                    .cloned()
+            };
+            return match method {
+                "PUT" => {
+                    // Honour conditional pushes, as some registries do
+                    let if_match = request
+                        .headers
+                        .get("if-match")
+                        .and_then(|v| v.to_str().ok())
+                        .map(|v| v.trim_matches('"').to_string());
+                    let if_none_match = request.headers.contains_key("if-none-match");
+                    let current = state.tags.get(&repo).and_then(|tags| tags.get(reference));
+                    let met = match (&if_match, current) {
+                        (Some(expected), current) => current == Some(expected),
+                        (None, current) => !if_none_match || current.is_none(),
+                    };
+                    if !met {
+                        return ResponseTemplate::new(412);
+                    }
+                    let digest = sha256(&request.body);
+                    state.manifests.insert(digest.clone(), request.body.clone());
+                    state
+                        .tags
+                        .entry(repo)
+                        .or_default()
+                        .insert(reference.to_string(), digest);
+                    ResponseTemplate::new(201)
+                }
+                "DELETE" => match digest {
+                    Some(digest) if state.manifests.remove(&digest).is_some() => {
+                        for tags in state.tags.values_mut() {
+                            tags.retain(|_, d| *d != digest);
+                        }
+                        ResponseTemplate::new(202)
+                    }
+                    _ => ResponseTemplate::new(404),
+                },
+                _ => match digest.and_then(|d| state.manifests.get(&d)) {
+                    Some(body) => ResponseTemplate::new(200)
+                        .set_body_raw(body.clone(), "application/vnd.oci.image.manifest.v1+json"),
+                    None => ResponseTemplate::new(404),
+                },
+            };
+        }
+
+        ResponseTemplate::new(404)
+    }
+}
+
+/// Token auth in front of a registry, like ghcr.io or Docker Hub: each token
+/// covers one repository and the actions it was issued for, and the token
+/// is the scope itself.
+#[derive(Clone)]
+struct TokenAuth {
+    registry: Registry,
+    realm: String,
+    tokens_issued: Arc<Mutex<Vec<String>>>,
+}
+
+impl Respond for TokenAuth {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        if request.url.path() == "/token" {
+            let scope = request
+                .url
+                .query_pairs()
+                .find(|(k, _)| k == "scope")
+                .map(|(_, v)| v.to_string())
+                .unwrap_or_default();
+            self.tokens_issued.lock().unwrap().push(scope.clone());
+            return ResponseTemplate::new(200).set_body_json(serde_json::json!({ "token": scope }));
+        }
+
+        let path = request.url.path().trim_start_matches("/v2/");
+        let repo = ["/manifests/", "/blobs/", "/tags/"]
+            .iter()
+            .find_map(|marker| path.split_once(marker).map(|(repo, _)| repo))
+            .unwrap_or_default();
+        let pull = matches!(request.method.as_str(), "GET" | "HEAD");
+        let token = request
+            .headers
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .unwrap_or_default();
+        let allowed = token == format!("repository:{}:pull,push", repo)
+            || (pull && token == format!("repository:{}:pull", repo));
+        if allowed {
+            return self.registry.respond(request);
+        }
+        let action = if pull { "pull" } else { "pull,push" };
+        ResponseTemplate::new(401).insert_header(
+            "WWW-Authenticate",
+            format!(
+                r#"Bearer realm="{}",service="registry",scope="repository:{}:{}""#,
+                self.realm, repo, action
+            ),
+        )
+    }
+}
+
+async fn start_registry() -> (MockServer, Registry) {
+    let server = MockServer::start().await;
+    let registry = Registry::default();
+    Mock::given(path_regex("^/v2/"))
+        .respond_with(registry.clone())
+        .mount(&server)
+        .await;
+    (server, registry)
+}
+
+fn registry_host(server: &MockServer) -> String {
+    server.uri().trim_start_matches("http://").to_string()
+}
+
+fn build_package(dir: &Path) -> PathBuf {
+    let skill_dir = dir.join("test-skill");
+    common::create_valid_skill(&skill_dir);
+    package_skill(&skill_dir, dir.join("dist"))
+        .unwrap()
+        .output_path
+}
+
+fn upload(client: OciStorageClient, package: &Path, versions: &[&str]) {
+    let repo = Repository::new(client);
+    for version in versions {
+        repo.upload(
+            &UploadParams {
+                name: "test-skill",
+                version,
+                description: "A test skill",
+                llms_txt_url: "https://example.com/llms.txt",
+                skill_file: package,
+                changelog: None,
+                source_dir: None,
+            },
+            &Output::new(true),
+        )
+        .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_push_and_pull_with_cli() {
+    let (server, registry) = start_registry().await;
+    let temp = TempDir::new().unwrap();
+    let package = build_package(temp.path());
+    let reference = format!("oci://{}/team/test-skill", registry_host(&server));
+
+    let home = temp.path().to_path_buf();
+    let pulled_dir = temp.path().join("pulled");
+    tokio::task::spawn_blocking({
+        let package = package.clone();
+        let pulled_dir = pulled_dir.clone();
+        move || {
+            for version in ["1.0.0", "1.2.0"] {
+                #[allow(deprecated)]
+                Command::cargo_bin("sb")
+                    .unwrap()
+                    .env("HOME", &home)
+                    .current_dir(&home)
+                    .args(["repo", "push", &format!("{}:{}", reference, version)])
+                    .arg("--file")
+                    .arg(&package)
+                    .assert()
+                    .success();
+            }
+
+            #[allow(deprecated)]
+            Command::cargo_bin("sb")
+                .unwrap()
+                .env("HOME", &home)
+                .current_dir(&home)
+                .args(["repo", "pull", &reference, "--output"])
+                .arg(&pulled_dir)
+                .assert()
+                .success()
+                .stderr(predicates::str::contains("test-skill v1.2.0"));
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(
+        fs::read(pulled_dir.join("test-skill.skill")).unwrap(),
+        fs::read(&package).unwrap()
+    );
+
+    let manifest = registry.manifest("team/test-skill", "1.2.0").unwrap();
+    assert_eq!(manifest["artifactType"], SKILL_ARTIFACT_TYPE);
+    assert_eq!(manifest["layers"][0]["mediaType"], SKILL_LAYER_MEDIA_TYPE);
+    assert_eq!(
+        manifest["layers"][0]["annotations"]["org.opencontainers.image.title"],
+        "test-skill.skill"
+    );
+    let annotations = &manifest["annotations"];
+    assert_eq!(annotations["org.opencontainers.image.title"], "test-skill");
+    assert_eq!(annotations["org.opencontainers.image.version"], "1.2.0");
+    assert!(annotations["org.opencontainers.image.description"]
+        .as_str()
+        .unwrap()
+        .starts_with("A test skill for integration testing"));
+    assert!(registry
+        .manifest("team/skill-builder-index", "latest")
+        .is_some());
+}
+
+#[tokio::test]
+async fn test_index_versions_follow_registry_tags() {
+    let (server, registry) = start_registry().await;
+    let temp = TempDir::new().unwrap();
+    let package = build_package(temp.path());
+    let host = registry_host(&server);
+
+    // Tags removed or added outside sb show up in the index
+    let versions = tokio::task::spawn_blocking({
+        let registry = registry.clone();
+        move || {
+            upload(
+                OciStorageClient::new(&host, "team").unwrap(),
+                &package,
+                &["1.0.0", "2.0.0"],
+            );
+            registry.untag("team/test-skill", "1.0.0");
+            registry.retag("team/test-skill", "2.0.0", "2.1.0");
+
+            let client = OciStorageClient::new(&host, "team").unwrap();
+            let index = load_index(&client).unwrap();
+            let entry = index.find_skill("test-skill").unwrap();
+            let mut versions: Vec<String> = entry.versions.keys().cloned().collect();
+            versions.sort();
+            versions
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(versions, vec!["2.0.0", "2.1.0"]);
+}
+
+#[tokio::test]
+async fn test_paged_tag_lists_are_followed() {
+    let (server, registry) = start_registry().await;
+    let temp = TempDir::new().unwrap();
+    let package = build_package(temp.path());
+    let host = registry_host(&server);
+
+    let (versions, pulled, indexed) = tokio::task::spawn_blocking(move || {
+        upload(
+            OciStorageClient::new(&host, "team").unwrap(),
+            &package,
+            &["1.0.0", "1.2.0", "1.10.0"],
+        );
+        registry.page_tags(1);
+
+        let client = OciStorageClient::new(&host, "team").unwrap();
+        let versions = client.list_versions("test-skill").unwrap();
+        let (pulled, _) = client.pull("test-skill", None).unwrap();
+        let index = load_index(&client).unwrap();
+        let indexed = index.find_skill("test-skill").unwrap().versions.len();
+        (versions, pulled, indexed)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(versions, vec!["1.10.0", "1.2.0", "1.0.0"]);
+    assert_eq!(pulled, "1.10.0");
+    assert_eq!(indexed, 3);
+}
+
+#[tokio::test]
+async fn test_pull_skips_yanked_versions_and_other_tags() {
+    let (server, registry) = start_registry().await;
+    let temp = TempDir::new().unwrap();
+    let package = build_package(temp.path());
+    let host = registry_host(&server);
+
+    let (versions, pulled) = tokio::task::spawn_blocking(move || {
+        upload(
+            OciStorageClient::new(&host, "team").unwrap(),
+            &package,
+            &["1.0.0", "1.1.0", "2.0.0"],
+        );
+        registry.retag("team/test-skill", "2.0.0", "latest");
+        Repository::new(OciStorageClient::new(&host, "team").unwrap())
+            .yank("test-skill", "2.0.0", Some("broken"))
+            .unwrap();
+
+        let client = OciStorageClient::new(&host, "team").unwrap();
+        let versions = client.list_versions("test-skill").unwrap();
+        let (pulled, _) = client.pull("test-skill", None).unwrap();
+        (versions, pulled)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(versions, vec!["2.0.0", "1.1.0", "1.0.0"]);
+    assert_eq!(pulled, "1.1.0");
+}
+
+#[tokio::test]
+async fn test_tokens_are_scoped_per_repository_and_action() {
+    let server = MockServer::start().await;
+    let auth = TokenAuth {
+        registry: Registry::default(),
+        realm: format!("{}/token", server.uri()),
+        tokens_issued: Arc::default(),
+    };
+    Mock::given(wiremock::matchers::any())
+        .respond_with(auth.clone())
+        .mount(&server)
+        .await;
+    let temp = TempDir::new().unwrap();
+    let package = build_package(temp.path());
+    let host = registry_host(&server);
+
+    let pulled = tokio::task::spawn_blocking(move || {
+        // Reads the index, then pushes the skill and the index
+        let client = OciStorageClient::new(&host, "team").unwrap();
+        let repo = Repository::new(client);
+        repo.upload(
+            &UploadParams {
+                name: "test-skill",
+                version: "1.0.0",
+                description: "A test skill",
+                llms_txt_url: "https://example.com/llms.txt",
+                skill_file: &package,
+                changelog: None,
+                source_dir: None,
+            },
+            &Output::new(true),
+        )
+        .unwrap();
+        assert!(repo.has_version("test-skill", "1.0.0").unwrap());
+
+        let client = OciStorageClient::new(&host, "team").unwrap();
+        client.pull("test-skill", None).unwrap().0
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(pulled, "1.0.0");
+    let issued = auth.tokens_issued.lock().unwrap().clone();
+    assert!(issued.contains(&"repository:team/skill-builder-index:pull".to_string()));
+    assert!(issued.contains(&"repository:team/skill-builder-index:pull,push".to_string()));
+    assert!(issued.contains(&"repository:team/test-skill:pull,push".to_string()));
+}
+
+#[tokio::test]
+async fn test_concurrent_index_updates_keep_every_entry() {
+    let (server, registry) = start_registry().await;
+    let host = registry_host(&server);
+
+    let stale = tokio::task::spawn_blocking(move || {
+        let client = OciStorageClient::new(&host, "team").unwrap();
+        update_index(&client, |_| Ok(())).unwrap();
+        let (_, read) = client.get_object_with_metadata(INDEX_KEY).unwrap();
+        let key = "skills/first/1.0.0/CHANGELOG.md";
+        client.put_object(key, b"# Changelog").unwrap();
+        update_index(&client, |index| {
+            index.add_or_update_skill("first", "", "", "1.0.0", key);
+            Ok(())
+        })
+        .unwrap();
+        let stale = client.put_object_if(
+            INDEX_KEY,
+            br#"{"skills": []}"#,
+            &PutCondition::IfMatch(read.etag),
+        );
+
+        let writers: Vec<_> = ["a", "b"]
+            .into_iter()
+            .map(|writer| {
+                let host = host.clone();
+                std::thread::spawn(move || {
+                    let client = OciStorageClient::new(&host, "team").unwrap();
+                    for i in 0..4 {
+                        // The index only keeps skills with a version in the registry
+                        let name = format!("skill-{}-{}", writer, i);
+                        let key = format!("skills/{}/1.0.0/CHANGELOG.md", name);
+                        client.put_object(&key, b"# Changelog").unwrap();
+                        update_index(&client, |index| {
+                            index.add_or_update_skill(&name, "", "", "1.0.0", &key);
+                            Ok(())
+                        })
+                        .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        stale
+    })
+    .await
+    .unwrap();
+
+    assert!(stale.is_err_and(|e| is_precondition_failed(&e)));
+    let index = registry.stored_index("team");
+    assert_eq!(index["skills"].as_array().unwrap().len(), 9);
+}
+
+#[tokio::test]
+async fn test_listing_fails_when_the_registry_does() {
+    let (server, _) = start_registry().await;
+    let broken = MockServer::start().await;
+    Mock::given(path_regex("^/v2/"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&broken)
+        .await;
+    let (empty, failed) = (registry_host(&server), registry_host(&broken));
+
+    let (empty, failed) = tokio::task::spawn_blocking(move || {
+        let list = |host: &str| {
+            OciStorageClient::new(host, "team")
+                .unwrap()
+                .list_objects("")
+        };
+        (list(&empty), list(&failed))
+    })
+    .await
+    .unwrap();
+
+    assert!(empty.unwrap().is_empty());
+    assert!(failed.is_err());
+}
+
+#[tokio::test]
+async fn test_delete_removes_version_manifest() {
+    let (server, registry) = start_registry().await;
+    let temp = TempDir::new().unwrap();
+    let package = build_package(temp.path());
+    let host = registry_host(&server);
+
+    tokio::task::spawn_blocking(move || {
+        upload(
+            OciStorageClient::new(&host, "").unwrap(),
+            &package,
+            &["1.0.0", "1.1.0"],
+        );
+        let repo = Repository::new(OciStorageClient::new(&host, "").unwrap());
+        repo.delete("test-skill", Some("1.0.0"), &Output::new(true))
+            .unwrap();
+    })
+    .await
+    .unwrap();
+
+    assert!(registry.manifest("test-skill", "1.0.0").is_none());
+    assert!(registry.manifest("test-skill", "1.1.0").is_some());
+}
+
+#[tokio::test]
+async fn test_install_from_configured_oci_repository() {
+    let (server, _registry) = start_registry().await;
+    let temp = TempDir::new().unwrap();
+    let package = build_package(temp.path());
+    let host = registry_host(&server);
+    let install_dir = temp.path().join("installed");
+
+    let resolved = tokio::task::spawn_blocking({
+        let install_dir = install_dir.clone();
+        move || {
+            upload(
+                OciStorageClient::new(&host, "skills").unwrap(),
+                &package,
+                &["1.0.0"],
+            );
+            let config = Config {
+                repositories: vec![RepositoryConfig {
+                    name: Some("registry".to_string()),
+                    url: Some(format!("oci://{}/skills", host)),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            };
+            let options = InstallOptions {
+                skill_name: "test-skill",
+                version: None,
+                github_repo: None,
+                install_dir: &install_dir,
+                local_only: false,
+                remote_only: true,
+                github_only: false,
+                offline: false,
+                follow_replaced: false,
+            };
+            resolve_and_install(&config, &options, &Output::new(true))
+        }
+    })
+    .await
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(
+        resolved.source,
+        InstallSource::Remote("registry".to_string())
+    );
+    assert!(install_dir.join("test-skill/SKILL.md").exists());
+}
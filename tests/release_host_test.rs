@@ -0,0 +1,184 @@
+//! Integration tests for installing from GitLab and Gitea releases configured
+//! in the `releases` section, against a stand-in for the host.
+
+mod common;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use skill_builder::package::package_skill;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn package_bytes() -> Vec<u8> {
+    let tmp = TempDir::new().unwrap();
+    let skill_dir = tmp.path().join("test-skill");
+    common::create_valid_skill(&skill_dir);
+    let package = package_skill(&skill_dir, tmp.path().join("dist"))
+        .unwrap()
+        .output_path;
+    fs::read(package).unwrap()
+}
+
+/// `sb` in `dir`, with `releases` set to `releases` in the project config.
+fn sb(dir: &Path, releases: &str) -> Command {
+    fs::write(
+        dir.join("skills.json"),
+        format!(r#"{{"releases": {}}}"#, releases),
+    )
+    .unwrap();
+    #[allow(deprecated)]
+    let mut cmd = Command::cargo_bin("sb").unwrap();
+    cmd.env("HOME", dir)
+        .env_remove("GITLAB_TOKEN")
+        .env_remove("GITEA_TOKEN")
+        .current_dir(dir);
+    cmd
+}
+
+#[tokio::test]
+async fn test_install_latest_from_private_gitlab() {
+    let server = MockServer::start().await;
+    let package = tokio::task::spawn_blocking(package_bytes).await.unwrap();
+    Mock::given(method("GET"))
+        .and(path("/api/v4/projects/group%2Fskills/releases"))
+        .and(header("private-token", "secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"[{"tag_name": "v1.1.0"}]"#))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(
+            "/group/skills/-/releases/v1.1.0/downloads/test-skill.skill",
+        ))
+        .and(header("private-token", "secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(package))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let releases = format!(
+        r#"{{"kind": "gitlab", "base_url": "{}", "repo": "group/skills"}}"#,
+        server.uri()
+    );
+    tokio::task::spawn_blocking(move || {
+        let tmp = TempDir::new().unwrap();
+        let install = ["install", "test-skill", "--github", "--install-dir", "out"];
+
+        sb(tmp.path(), &releases)
+            .args(install)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "set GITLAB_TOKEN if the project is private",
+            ));
+
+        sb(tmp.path(), &releases)
+            .env("GITLAB_TOKEN", "secret")
+            .args(install)
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("GitLab releases of group/skills"))
+            .stderr(predicate::str::contains("Latest release: v1.1.0"));
+        assert!(tmp.path().join("out/test-skill/SKILL.md").exists());
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_install_version_from_gitea_with_custom_token() {
+    let server = MockServer::start().await;
+    let package = tokio::task::spawn_blocking(package_bytes).await.unwrap();
+    Mock::given(method("GET"))
+        .and(path(
+            "/acme/skills/releases/download/v1.0.0/test-skill.skill",
+        ))
+        .and(header("authorization", "token deploy"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(package))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // --repo overrides the configured project
+    let releases = format!(
+        r#"{{"kind": "gitea", "base_url": "{}", "repo": "other/project", "token_env": "DEPLOY_TOKEN"}}"#,
+        server.uri()
+    );
+    tokio::task::spawn_blocking(move || {
+        let tmp = TempDir::new().unwrap();
+        sb(tmp.path(), &releases)
+            .env("DEPLOY_TOKEN", "deploy")
+            .args(["install", "test-skill", "--version", "1.0.0"])
+            .args(["--github", "--repo", "acme/skills", "--install-dir", "out"])
+            .assert()
+            .success();
+        assert!(tmp.path().join("out/test-skill/SKILL.md").exists());
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_token_is_not_forwarded_to_other_hosts() {
+    let server = MockServer::start().await;
+    let elsewhere = MockServer::start().await;
+    let package = tokio::task::spawn_blocking(package_bytes).await.unwrap();
+    // A release asset link pointing at another host
+    Mock::given(method("GET"))
+        .and(path(
+            "/group/skills/-/releases/v1.0.0/downloads/test-skill.skill",
+        ))
+        .and(header("private-token", "secret"))
+        .respond_with(ResponseTemplate::new(302).insert_header(
+            "Location",
+            format!("{}/assets/test-skill.skill", elsewhere.uri()),
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/assets/test-skill.skill"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(package))
+        .expect(1)
+        .mount(&elsewhere)
+        .await;
+
+    let releases = format!(
+        r#"{{"kind": "gitlab", "base_url": "{}", "repo": "group/skills"}}"#,
+        server.uri()
+    );
+    tokio::task::spawn_blocking(move || {
+        let tmp = TempDir::new().unwrap();
+        sb(tmp.path(), &releases)
+            .env("GITLAB_TOKEN", "secret")
+            .args(["install", "test-skill", "--version", "1.0.0"])
+            .args(["--github", "--install-dir", "out"])
+            .assert()
+            .success();
+        assert!(tmp.path().join("out/test-skill/SKILL.md").exists());
+    })
+    .await
+    .unwrap();
+
+    let requests = elsewhere.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(!requests[0].headers.contains_key("private-token"));
+}
+
+#[test]
+fn test_releases_config_needs_a_project() {
+    let tmp = TempDir::new().unwrap();
+    sb(
+        tmp.path(),
+        r#"{"kind": "gitlab", "base_url": "https://gitlab.example.com"}"#,
+    )
+    .args(["install", "test-skill", "--github", "--install-dir", "out"])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains(
+        "No project configured for GitLab releases at https://gitlab.example.com",
+    ));
+}
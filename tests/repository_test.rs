@@ -2,6 +2,7 @@
 
 mod common;
 
+use skill_builder::cache::{CachePolicy, SkillCache};
 use skill_builder::local_storage::LocalStorageClient;
 use skill_builder::output::Output;
 use skill_builder::repository::{Repository, UploadParams};
@@ -175,3 +176,105 @@ fn test_download_nonexistent_skill_fails() {
     let result = repo.download("nonexistent", Some("1.0.0"), None, &out);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_concurrent_uploads_keep_every_index_entry() {
+    let tmp = TempDir::new().unwrap();
+    let skill_file = create_test_skill_file(tmp.path());
+    let store = tmp.path().join("store");
+    LocalStorageClient::new(&store).unwrap();
+
+    // Separate clients, as separate CI jobs would have
+    let writers: Vec<_> = ["a", "b", "c", "d"]
+        .into_iter()
+        .map(|name| {
+            let store = store.clone();
+            let skill_file = skill_file.clone();
+            std::thread::spawn(move || {
+                let repo = Repository::new(LocalStorageClient::with_dir(&store));
+                for version in ["1.0.0", "1.1.0", "1.2.0"] {
+                    repo.upload(&upload_params(name, version, &skill_file), &test_output())
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    let index = Repository::new(LocalStorageClient::with_dir(&store))
+        .list(None)
+        .unwrap();
+    assert_eq!(index.skills.len(), 4);
+    for entry in &index.skills {
+        assert_eq!(entry.versions.len(), 3, "versions of {}", entry.name);
+    }
+}
+
+#[test]
+fn test_download_cache_respects_policy_and_spares_published_skills() {
+    let out = test_output();
+    let tmp = TempDir::new().unwrap();
+    let skill_file = create_test_skill_file(tmp.path());
+    let local = tmp.path().join("local");
+
+    // A skill published to the local repository itself
+    let published = Repository::new(LocalStorageClient::new(&local).unwrap());
+    published
+        .upload(&upload_params("s", "0.1.0", &skill_file), &out)
+        .unwrap();
+
+    let cache = SkillCache::new(
+        LocalStorageClient::new(&local).unwrap(),
+        CachePolicy {
+            keep_versions: Some(1),
+            ..Default::default()
+        },
+    );
+    let repo = Repository::new_with_cache(MockS3Client::new(), cache);
+    for version in ["1.0.0", "1.1.0"] {
+        repo.upload(&upload_params("s", version, &skill_file), &out)
+            .unwrap();
+        repo.download("s", Some(version), None, &out).unwrap();
+    }
+
+    assert!(!local.join("cache/skills/s/1.0.0/s.skill").exists());
+    assert!(local.join("cache/skills/s/1.1.0/s.skill").exists());
+    assert!(local.join("skills/s/0.1.0/s.skill").exists());
+    assert_eq!(published.list(None).unwrap().skills[0].versions.len(), 1);
+}
+
+#[test]
+fn test_version_details_and_changelog() {
+    let out = test_output();
+    let (repo, tmp) = setup();
+    let skill_file = create_test_skill_file(tmp.path());
+    let changelog = tmp.path().join("CHANGELOG.md");
+    fs::write(&changelog, "## [2.0.0]\n\n- Rewritten\n").unwrap();
+
+    repo.upload(&upload_params("s", "1.0.0", &skill_file), &out)
+        .unwrap();
+    let mut params = upload_params("s", "2.0.0", &skill_file);
+    params.changelog = Some(&changelog);
+    repo.upload(&params, &out).unwrap();
+
+    let entry = repo.list(Some("s")).unwrap().skills.remove(0);
+    let details = repo.version_details(&entry).unwrap();
+    let versions: Vec<&str> = details.iter().map(|d| d.version.as_str()).collect();
+    assert_eq!(versions, vec!["2.0.0", "1.0.0"]);
+    assert!(details.iter().all(|d| d.published.is_some()));
+    assert_eq!(
+        details[0].size,
+        Some(fs::metadata(&skill_file).unwrap().len())
+    );
+    assert!(details[0].has_changelog);
+    assert!(!details[1].has_changelog);
+    assert_eq!(details[0].source_size, None);
+
+    assert_eq!(
+        repo.changelog("s", "2.0.0").unwrap().as_deref(),
+        Some("## [2.0.0]\n\n- Rewritten\n")
+    );
+    assert_eq!(repo.changelog("s", "1.0.0").unwrap(), None);
+}
@@ -0,0 +1,160 @@
+//! Integration tests for `sb search` and the metadata it ranks on.
+
+mod common;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use skill_builder::local_storage::LocalStorageClient;
+use skill_builder::output::Output;
+use skill_builder::package::package_skill;
+use skill_builder::repository::{Repository, UploadParams};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const RELEASES: &str = r#"[
+  {
+    "tag_name": "v2.0.0",
+    "assets": [
+      {"name": "svelte-runes.skill", "browser_download_url": "https://example.com/v2.0.0/svelte-runes.skill"},
+      {"name": "checksums.txt", "browser_download_url": "https://example.com/v2.0.0/checksums.txt"}
+    ]
+  },
+  {
+    "tag_name": "v1.0.0",
+    "assets": [
+      {"name": "svelte-runes.skill", "browser_download_url": "https://example.com/v1.0.0/svelte-runes.skill"}
+    ]
+  }
+]"#;
+
+/// Write a skill whose SKILL.md carries tags, an author and a homepage.
+fn create_tagged_skill(dir: &Path) {
+    common::create_valid_skill(dir);
+    fs::write(
+        dir.join("SKILL.md"),
+        "---\nname: test-skill\ndescription: Components for building accessible user interfaces quickly\ntags: [svelte, ui]\nauthor: Jane Doe\nhomepage: https://example.com/test-skill\n---\n\n# Test Skill\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_upload_records_skill_metadata() {
+    let tmp = TempDir::new().unwrap();
+    let skill_dir = tmp.path().join("test-skill");
+    create_tagged_skill(&skill_dir);
+    let package = package_skill(&skill_dir, tmp.path().join("dist"))
+        .unwrap()
+        .output_path;
+
+    let repo = Repository::new(LocalStorageClient::new(&tmp.path().join("repo")).unwrap());
+    repo.upload(
+        &UploadParams {
+            name: "test-skill",
+            version: "1.0.0",
+            description: "A test skill",
+            llms_txt_url: "https://example.com/llms.txt",
+            skill_file: &package,
+            changelog: None,
+            source_dir: None,
+        },
+        &Output::new(true),
+    )
+    .unwrap();
+
+    let entry = repo.list(None).unwrap().skills.remove(0);
+    assert_eq!(entry.tags, vec!["svelte", "ui"]);
+    assert_eq!(entry.author.as_deref(), Some("Jane Doe"));
+    assert_eq!(
+        entry.homepage.as_deref(),
+        Some("https://example.com/test-skill")
+    );
+
+    // Reindexing reads the same metadata back from the package
+    let rebuilt = repo.reindex().unwrap();
+    assert_eq!(rebuilt.index.skills[0].tags, entry.tags);
+    assert_eq!(rebuilt.index.skills[0].author, entry.author);
+}
+
+#[tokio::test]
+async fn test_search_ranks_local_and_github_results() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/repos/acme/skills/releases"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(RELEASES))
+        .mount(&server)
+        .await;
+
+    let api_url = server.uri();
+    tokio::task::spawn_blocking(move || {
+        let tmp = TempDir::new().unwrap();
+        let home = tmp.path().join("home");
+        create_tagged_skill(&tmp.path().join("test-skill"));
+        let sb = || {
+            #[allow(deprecated)]
+            let mut cmd = Command::cargo_bin("sb").unwrap();
+            cmd.env("HOME", &home)
+                .env("SB_GITHUB_API_URL", &api_url)
+                .current_dir(tmp.path());
+            cmd
+        };
+        sb().args(["package", "test-skill"])
+            .arg("--skills-dir")
+            .arg(tmp.path())
+            .assert()
+            .success();
+        sb().args(["local", "publish", "test-skill", "1.0.0"])
+            .assert()
+            .success();
+
+        let assert = sb()
+            .args(["search", "svelte", "--repo", "acme/skills"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Tags: svelte, ui"))
+            .stderr(predicate::str::contains("Author: Jane Doe"))
+            .stderr(predicate::str::contains(
+                "Source: GitHub releases of acme/skills",
+            ));
+        let stderr = String::from_utf8(assert.get_output().stderr.clone()).unwrap();
+        let runes = stderr.find("svelte-runes v2.0.0").unwrap();
+        let local = stderr.find("test-skill v1.0.0").unwrap();
+        assert!(runes < local, "name matches rank first:\n{}", stderr);
+
+        sb().args(["search", "accessible", "interfaces", "--no-github"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("test-skill v1.0.0"))
+            .stderr(predicate::str::contains("Source: local repository"));
+
+        sb().args(["search", "nothing-like-this", "--no-github"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "No skills match 'nothing-like-this'",
+            ));
+    })
+    .await
+    .unwrap();
+}
+
+#[test]
+fn test_search_offline_skips_github() {
+    let tmp = TempDir::new().unwrap();
+    #[allow(deprecated)]
+    Command::cargo_bin("sb")
+        .unwrap()
+        .env("HOME", tmp.path())
+        .current_dir(tmp.path())
+        .args(["--offline", "search", "svelte"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Skipping GitHub releases of antstanley/skill-builder",
+        ))
+        .stderr(predicate::str::contains(
+            "No skill sources could be searched",
+        ));
+}